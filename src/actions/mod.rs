@@ -0,0 +1,148 @@
+//! Git operations run on a single repository on behalf of interactive front ends.
+//!
+//! Everything goes through [`GitRunner`], so the same backend and test doubles used for the
+//! report also apply to actions; commands the native backend does not implement fall back to
+//! the git binary.
+
+mod push;
+
+use std::fmt;
+use std::path::Path;
+
+use crate::git::{GitCommandError, GitRunner, run_checked};
+
+pub use push::{
+    InvalidPattern, PushFilter, PushPlan, PushResult, PushSkip, PushTarget, plan_push, push_all,
+};
+
+/// Most commits listed in [`RepoDetails::unpushed`].
+const UNPUSHED_LOG_LIMIT: &str = "--max-count=20";
+
+/// A change made to a repository at the user's request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RepoAction {
+    /// Stage every change, including untracked and deleted files.
+    StageAll,
+    /// Commit the staged changes with the given message.
+    Commit(String),
+    /// Push the given branch to its upstream. The refspec is explicit, so the branch does not
+    /// have to be checked out.
+    Push(String),
+    /// Stash the working tree, including untracked files.
+    Stash,
+    /// Fetch every remote.
+    Fetch,
+}
+
+impl RepoAction {
+    #[must_use]
+    pub fn label(&self) -> &'static str {
+        match self {
+            RepoAction::StageAll => "stage all",
+            RepoAction::Commit(_) => "commit",
+            RepoAction::Push(_) => "push",
+            RepoAction::Stash => "stash",
+            RepoAction::Fetch => "fetch",
+        }
+    }
+}
+
+/// Why an action failed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ActionError(ActionErrorKind);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ActionErrorKind {
+    /// A git command run by the action failed.
+    Git(GitCommandError),
+    /// The branch to push has no upstream to push to.
+    NoUpstream(String),
+}
+
+impl fmt::Display for ActionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.0 {
+            ActionErrorKind::Git(err) => err.fmt(f),
+            ActionErrorKind::NoUpstream(branch) => {
+                write!(f, "branch {branch} has no upstream configured")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ActionError {}
+
+impl From<GitCommandError> for ActionError {
+    fn from(err: GitCommandError) -> Self {
+        ActionError(ActionErrorKind::Git(err))
+    }
+}
+
+/// Run `action` in `repo`.
+///
+/// # Errors
+/// Returns an error when git cannot be spawned or exits with a non-zero status, or when the
+/// branch to push has no upstream.
+pub fn run_action(
+    repo: &Path,
+    git: &dyn GitRunner,
+    action: &RepoAction,
+) -> Result<(), ActionError> {
+    let push;
+    let args: Vec<&str> = match action {
+        RepoAction::StageAll => vec!["add", "--all"],
+        RepoAction::Commit(message) => vec!["commit", "--quiet", "-m", message],
+        RepoAction::Push(branch) => {
+            let (remote, remote_ref) = push::upstream_of(repo, git, branch)?
+                .ok_or_else(|| ActionError(ActionErrorKind::NoUpstream(branch.clone())))?;
+            push = push::push_args(&remote, branch, &remote_ref);
+            push.iter().map(String::as_str).collect()
+        }
+        RepoAction::Stash => vec!["stash", "push", "--include-untracked", "--quiet"],
+        RepoAction::Fetch => vec!["fetch", "--all", "--prune", "--quiet"],
+    };
+    run_checked(repo, git, &args, &[])?;
+    Ok(())
+}
+
+/// What a front end shows about one repository.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RepoDetails {
+    /// `git status --short --branch`.
+    pub status: Vec<String>,
+    /// Diffstat of the working tree and index against `HEAD`.
+    pub diffstat: Vec<String>,
+    /// One line per commit of `HEAD` that no remote has, newest first.
+    pub unpushed: Vec<String>,
+}
+
+/// Collect [`RepoDetails`] for `repo`; a query that fails shows its error in place of output.
+#[must_use]
+pub fn repo_details(repo: &Path, git: &dyn GitRunner) -> RepoDetails {
+    RepoDetails {
+        status: lines(repo, git, &["status", "--short", "--branch"]),
+        diffstat: lines(repo, git, &["diff", "--stat", "HEAD", "--"]),
+        unpushed: lines(
+            repo,
+            git,
+            &[
+                "log",
+                "--oneline",
+                UNPUSHED_LOG_LIMIT,
+                "HEAD",
+                "--not",
+                "--remotes",
+            ],
+        ),
+    }
+}
+
+fn lines(repo: &Path, git: &dyn GitRunner, args: &[&str]) -> Vec<String> {
+    match run_checked(repo, git, args, &[]) {
+        Ok(out) => String::from_utf8_lossy(&out.stdout)
+            .lines()
+            .map(str::to_string)
+            .collect(),
+        Err(err) => vec![err.to_string()],
+    }
+}
@@ -0,0 +1,322 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use rayon::ThreadPoolBuilder;
+use rayon::prelude::*;
+use serde::Serialize;
+
+use crate::git::{GitCommandError, GitRunner, run_checked};
+use crate::types::{PushableEntry, ReportData};
+
+/// Glob patterns selecting which pushable branches to push; an empty list matches everything.
+#[derive(Debug, Clone, Default)]
+pub struct PushFilter {
+    roots: GlobSet,
+    repos: GlobSet,
+    branches: GlobSet,
+}
+
+impl PushFilter {
+    /// Build a filter from root, repository and branch patterns.
+    ///
+    /// # Errors
+    /// Returns the first pattern that is not a valid glob.
+    pub fn new(
+        roots: &[String],
+        repos: &[String],
+        branches: &[String],
+    ) -> Result<Self, InvalidPattern> {
+        Ok(PushFilter {
+            roots: glob_set(roots)?,
+            repos: glob_set(repos)?,
+            branches: glob_set(branches)?,
+        })
+    }
+
+    /// Whether `entry` passes every non-empty pattern list. Roots match on either the root as
+    /// given or its expanded path.
+    #[must_use]
+    pub fn matches(&self, entry: &PushableEntry) -> bool {
+        let root = self.roots.is_empty()
+            || self.roots.is_match(&entry.root_display)
+            || self.roots.is_match(&entry.root_full);
+        let repo = self.repos.is_empty() || self.repos.is_match(&entry.repo);
+        let branch = self.branches.is_empty() || self.branches.is_match(&entry.branch);
+        root && repo && branch
+    }
+}
+
+fn glob_set(patterns: &[String]) -> Result<GlobSet, InvalidPattern> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        let glob = Glob::new(pattern).map_err(|err| InvalidPattern {
+            pattern: pattern.clone(),
+            reason: err.kind().to_string(),
+        })?;
+        builder.add(glob);
+    }
+    builder.build().map_err(|err| InvalidPattern {
+        pattern: patterns.join(", "),
+        reason: err.to_string(),
+    })
+}
+
+/// A `--root`, `--repo` or `--branch` pattern that is not a valid glob.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidPattern {
+    pub pattern: String,
+    pub reason: String,
+}
+
+impl fmt::Display for InvalidPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid pattern '{}': {}", self.pattern, self.reason)
+    }
+}
+
+impl std::error::Error for InvalidPattern {}
+
+/// A branch that will be pushed to its configured upstream.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PushTarget {
+    pub repo: String,
+    pub branch: String,
+    /// Commits the upstream does not have yet.
+    pub revs: u64,
+    pub root_display: String,
+    pub root_full: String,
+    pub path: PathBuf,
+    /// Remote name from `branch.<name>.remote`.
+    pub remote: String,
+    /// Ref on the remote from `branch.<name>.merge`, e.g. `refs/heads/main`.
+    pub remote_ref: String,
+}
+
+impl PushTarget {
+    /// Upstream as git abbreviates it, e.g. `origin/main`.
+    #[must_use]
+    pub fn upstream(&self) -> String {
+        let short = self
+            .remote_ref
+            .strip_prefix("refs/heads/")
+            .unwrap_or(&self.remote_ref);
+        format!("{}/{short}", self.remote)
+    }
+
+    /// Arguments of the `git push` that publishes the branch. The refspec is explicit so the
+    /// push does not depend on `push.default` or on which branch is checked out.
+    #[must_use]
+    pub fn args(&self) -> Vec<String> {
+        push_args(&self.remote, &self.branch, &self.remote_ref)
+    }
+
+    /// The push as a shell command line, for `--dry-run`.
+    #[must_use]
+    pub fn command_line(&self) -> String {
+        format!(
+            "git -C {} {}",
+            shell_quote(&self.path.to_string_lossy()),
+            self.args()
+                .iter()
+                .map(|arg| shell_quote(arg))
+                .collect::<Vec<_>>()
+                .join(" ")
+        )
+    }
+}
+
+fn shell_quote(arg: &str) -> String {
+    let plain = !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./:@+=~".contains(c));
+    if plain {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', r"'\''"))
+    }
+}
+
+/// A pushable branch left out of the plan.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PushSkip {
+    pub repo: String,
+    pub branch: String,
+    pub reason: String,
+}
+
+/// Branches selected for a push, in report order.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PushPlan {
+    pub targets: Vec<PushTarget>,
+    pub skipped: Vec<PushSkip>,
+}
+
+/// Select the pushable branches of `data` that pass `filter` and look up their upstreams.
+#[must_use]
+pub fn plan_push(data: &ReportData, filter: &PushFilter, git: &dyn GitRunner) -> PushPlan {
+    let mut plan = PushPlan::default();
+    for entry in data.pushable.iter().filter(|entry| filter.matches(entry)) {
+        let skip = |reason: String| PushSkip {
+            repo: entry.repo.clone(),
+            branch: entry.branch.clone(),
+            reason,
+        };
+        match upstream_of(&entry.path, git, &entry.branch) {
+            Ok(Some((remote, remote_ref))) => plan.targets.push(PushTarget {
+                repo: entry.repo.clone(),
+                branch: entry.branch.clone(),
+                revs: entry.revs,
+                root_display: entry.root_display.clone(),
+                root_full: entry.root_full.clone(),
+                path: entry.path.clone(),
+                remote,
+                remote_ref,
+            }),
+            Ok(None) => plan
+                .skipped
+                .push(skip("no upstream configured".to_string())),
+            Err(err) => plan.skipped.push(skip(err.to_string())),
+        }
+    }
+    plan
+}
+
+/// Arguments of a `git push` of `branch` to `remote_ref` on `remote`.
+pub(super) fn push_args(remote: &str, branch: &str, remote_ref: &str) -> Vec<String> {
+    vec![
+        "push".to_string(),
+        remote.to_string(),
+        format!("refs/heads/{branch}:{remote_ref}"),
+    ]
+}
+
+/// Remote name and remote ref of the upstream of `branch`; `None` when it has none.
+pub(super) fn upstream_of(
+    repo: &Path,
+    git: &dyn GitRunner,
+    branch: &str,
+) -> Result<Option<(String, String)>, GitCommandError> {
+    let refname = format!("refs/heads/{branch}");
+    let out = run_checked(
+        repo,
+        git,
+        &[
+            "for-each-ref",
+            "--format=%(upstream:remotename) %(upstream:remoteref)",
+            &refname,
+        ],
+        &[],
+    )?;
+    let text = String::from_utf8_lossy(&out.stdout);
+    Ok(match text.trim().split_once(' ') {
+        Some((remote, remote_ref)) if !remote.is_empty() && !remote_ref.is_empty() => {
+            Some((remote.to_string(), remote_ref.to_string()))
+        }
+        _ => None,
+    })
+}
+
+/// The outcome of pushing one [`PushTarget`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PushResult {
+    pub target: PushTarget,
+    /// `None` when the push succeeded.
+    pub error: Option<String>,
+    pub duration: Duration,
+}
+
+/// Push every target, `jobs` at a time (`None` = number of CPUs); results keep plan order.
+#[must_use]
+pub fn push_all(
+    targets: &[PushTarget],
+    git: &dyn GitRunner,
+    jobs: Option<usize>,
+) -> Vec<PushResult> {
+    let run = |target: &PushTarget| {
+        let started = Instant::now();
+        let args = target.args();
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        let error = run_checked(&target.path, git, &args, &[])
+            .err()
+            .map(|err| err.to_string());
+        PushResult {
+            target: target.clone(),
+            error,
+            duration: started.elapsed(),
+        }
+    };
+
+    let threads = match jobs {
+        Some(jobs) if jobs > 0 => jobs,
+        _ => num_cpus::get(),
+    };
+    if threads <= 1 || targets.len() <= 1 {
+        return targets.iter().map(run).collect();
+    }
+    match ThreadPoolBuilder::new().num_threads(threads).build() {
+        Ok(pool) => pool.install(|| targets.par_iter().map(run).collect()),
+        Err(_) => targets.iter().map(run).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(root: &str, repo: &str, branch: &str) -> PushableEntry {
+        PushableEntry {
+            repo: repo.to_string(),
+            branch: branch.to_string(),
+            revs: 1,
+            earliest_secs: None,
+            latest_secs: None,
+            root_display: root.to_string(),
+            root_full: root.replace('~', "/home/me"),
+            path: PathBuf::from(root.replace('~', "/home/me")).join(repo),
+        }
+    }
+
+    #[test]
+    fn filter_matches_every_given_pattern_list() -> Result<(), InvalidPattern> {
+        let all = PushFilter::new(&[], &[], &[])?;
+        assert!(all.matches(&entry("~/src", "app", "main")));
+
+        let filter = PushFilter::new(
+            &["/home/me/*".to_string()],
+            &["app*".to_string(), "lib".to_string()],
+            &["feature/*".to_string()],
+        )?;
+        assert!(filter.matches(&entry("~/src", "app-web", "feature/login")));
+        assert!(!filter.matches(&entry("~/src", "app", "main")));
+        assert!(!filter.matches(&entry("~/src", "tools", "feature/x")));
+        assert!(!filter.matches(&entry("/srv", "lib", "feature/x")));
+
+        let Err(err) = PushFilter::new(&[], &["[z-a]".to_string()], &[]) else {
+            panic!("invalid range accepted");
+        };
+        assert_eq!(err.pattern, "[z-a]");
+        Ok(())
+    }
+
+    #[test]
+    fn command_line_quotes_only_what_the_shell_needs() {
+        let target = PushTarget {
+            repo: "app".to_string(),
+            branch: "fix it".to_string(),
+            revs: 2,
+            root_display: "~/src".to_string(),
+            root_full: "/home/me/src".to_string(),
+            path: PathBuf::from("/home/me/src/app"),
+            remote: "origin".to_string(),
+            remote_ref: "refs/heads/main".to_string(),
+        };
+        assert_eq!(target.upstream(), "origin/main");
+        assert_eq!(
+            target.command_line(),
+            "git -C /home/me/src/app push origin 'refs/heads/fix it:refs/heads/main'"
+        );
+    }
+}
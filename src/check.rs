@@ -0,0 +1,174 @@
+//! Gate conditions evaluated against a finished report so scripts can act on the exit code.
+//!
+//! Every condition owns one bit of the exit status, so a caller can tell which categories
+//! matched from the code alone (`uncommitted` + `pushable` exits with `4 | 16 = 20`). Exit
+//! status 1 stays reserved for the binary failing outright, e.g. an unreadable config.
+
+use clap::ValueEnum;
+
+use crate::ReportData;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, ValueEnum)]
+pub enum FailCondition {
+    /// A repository could not be inspected (exit bit 2)
+    Errors,
+    /// A repository has uncommitted changes (exit bit 4)
+    Uncommitted,
+    /// A repository has staged changes (exit bit 8)
+    Staged,
+    /// A branch has commits not pushed to its upstream (exit bit 16)
+    Pushable,
+    /// A repository is not covered by the `git_rewrite` config (exit bit 32)
+    Untracked,
+    /// A `git_rewrite` pair has commits waiting to be rewritten (exit bit 64)
+    GitRewrite,
+}
+
+impl FailCondition {
+    pub const ALL: [FailCondition; 6] = [
+        FailCondition::Errors,
+        FailCondition::Uncommitted,
+        FailCondition::Staged,
+        FailCondition::Pushable,
+        FailCondition::Untracked,
+        FailCondition::GitRewrite,
+    ];
+
+    /// Bit set in the exit status when this condition matches.
+    #[must_use]
+    pub fn exit_bit(self) -> i32 {
+        match self {
+            FailCondition::Errors => 2,
+            FailCondition::Uncommitted => 4,
+            FailCondition::Staged => 8,
+            FailCondition::Pushable => 16,
+            FailCondition::Untracked => 32,
+            FailCondition::GitRewrite => 64,
+        }
+    }
+
+    /// The condition's `--fail-on` spelling.
+    #[must_use]
+    pub fn label(self) -> &'static str {
+        match self {
+            FailCondition::Errors => "errors",
+            FailCondition::Uncommitted => "uncommitted",
+            FailCondition::Staged => "staged",
+            FailCondition::Pushable => "pushable",
+            FailCondition::Untracked => "untracked",
+            FailCondition::GitRewrite => "git-rewrite",
+        }
+    }
+
+    /// Number of report rows that make this condition match.
+    #[must_use]
+    pub fn count(self, data: &ReportData) -> usize {
+        match self {
+            FailCondition::Errors => data.errors.len(),
+            FailCondition::Uncommitted => data.uncommitted.len(),
+            FailCondition::Staged => data.staged.len(),
+            FailCondition::Pushable => data.pushable.iter().filter(|e| e.revs > 0).count(),
+            FailCondition::Untracked => data.untracked_repos.len(),
+            FailCondition::GitRewrite => data.git_rewrite.as_ref().map_or(0, |entries| {
+                entries.iter().filter(|e| e.commits > 0).count()
+            }),
+        }
+    }
+}
+
+/// Combine the exit bits of every condition in `conditions` that matches `data`.
+#[must_use]
+pub fn exit_code(data: &ReportData, conditions: &[FailCondition]) -> i32 {
+    conditions
+        .iter()
+        .filter(|condition| condition.count(data) > 0)
+        .fold(0, |code, condition| code | condition.exit_bit())
+}
+
+/// One-line result such as `fail (uncommitted): errors=0 uncommitted=2 staged=0 ...`.
+#[must_use]
+pub fn summary_line(data: &ReportData, conditions: &[FailCondition]) -> String {
+    let failing: Vec<&str> = conditions
+        .iter()
+        .filter(|condition| condition.count(data) > 0)
+        .map(|condition| condition.label())
+        .collect();
+    let status = if failing.is_empty() {
+        "ok".to_string()
+    } else {
+        format!("fail ({})", failing.join(", "))
+    };
+    let counts: Vec<String> = FailCondition::ALL
+        .iter()
+        .map(|condition| format!("{}={}", condition.label(), condition.count(data)))
+        .collect();
+    format!("{status}: {}", counts.join(" "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{PushableEntry, UncommittedEntry};
+
+    fn pushable(revs: u64) -> PushableEntry {
+        PushableEntry {
+            repo: "a".to_string(),
+            branch: "main".to_string(),
+            revs,
+            earliest_secs: None,
+            latest_secs: None,
+            root_display: "~/src".to_string(),
+            root_full: "/tmp/src".to_string(),
+            path: "/tmp/src/a".into(),
+        }
+    }
+
+    fn report() -> ReportData {
+        ReportData {
+            uncommitted: vec![UncommittedEntry {
+                repo: "a".to_string(),
+                branch: "main".to_string(),
+                upstream: None,
+                worktree: None,
+                lines: 3,
+                files: 1,
+                untracked: 0,
+                root_display: "~/src".to_string(),
+                root_full: "/tmp/src".to_string(),
+                path: "/tmp/src/a".into(),
+            }],
+            pushable: vec![pushable(2), pushable(0)],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn exit_code_combines_bits_of_selected_matching_conditions() {
+        let data = report();
+        assert_eq!(exit_code(&data, &FailCondition::ALL), 4 | 16);
+        assert_eq!(exit_code(&data, &[FailCondition::Staged]), 0);
+        assert_eq!(exit_code(&data, &[]), 0);
+        assert_eq!(exit_code(&ReportData::default(), &FailCondition::ALL), 0);
+    }
+
+    #[test]
+    fn summary_line_lists_failing_conditions_and_all_counts() {
+        let data = report();
+        assert_eq!(
+            summary_line(&data, &[FailCondition::Pushable, FailCondition::Staged]),
+            "fail (pushable): errors=0 uncommitted=1 staged=0 pushable=1 untracked=0 git-rewrite=0"
+        );
+        assert!(summary_line(&data, &[FailCondition::Staged]).starts_with("ok: "));
+    }
+
+    #[test]
+    fn labels_match_fail_on_values() {
+        for condition in FailCondition::ALL {
+            let value = condition.to_possible_value();
+            assert_eq!(
+                value.as_ref().map(clap::builder::PossibleValue::get_name),
+                Some(condition.label())
+            );
+        }
+    }
+}
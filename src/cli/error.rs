@@ -0,0 +1,40 @@
+#[derive(Debug)]
+pub(crate) struct CliError(pub(crate) String);
+
+impl std::fmt::Display for CliError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for CliError {}
+
+impl From<uncommitted::GitRewriteError> for CliError {
+    fn from(err: uncommitted::GitRewriteError) -> Self {
+        CliError(err.to_string())
+    }
+}
+
+impl From<uncommitted::config::ConfigError> for CliError {
+    fn from(err: uncommitted::config::ConfigError) -> Self {
+        CliError(err.to_string())
+    }
+}
+
+impl From<uncommitted::history::HistoryError> for CliError {
+    fn from(err: uncommitted::history::HistoryError) -> Self {
+        CliError(err.to_string())
+    }
+}
+
+impl From<uncommitted::output::TemplateError> for CliError {
+    fn from(err: uncommitted::output::TemplateError) -> Self {
+        CliError(err.to_string())
+    }
+}
+
+impl From<uncommitted::status::StatusError> for CliError {
+    fn from(err: uncommitted::status::StatusError) -> Self {
+        CliError(err.to_string())
+    }
+}
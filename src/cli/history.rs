@@ -0,0 +1,106 @@
+use serde_json::json;
+use uncommitted::{
+    DefaultClock,
+    history::{
+        RepoChange, Snapshot, diff_snapshots, load_snapshots, repo_timelines, select_snapshot,
+    },
+    output::{format_history_diff, format_history_timeline},
+};
+
+use super::{CliError, HistoryCommand, OutputFormat, Settings};
+
+pub(crate) fn run_history(action: &HistoryCommand, settings: &Settings) -> Result<i32, CliError> {
+    settings.output.require_tab_or_json("history")?;
+    let Some(path) = &settings.history_file else {
+        return Err(CliError(
+            "no history file: set --history-file or HOME".to_string(),
+        ));
+    };
+    let snapshots = load_snapshots(path)?;
+    if snapshots.is_empty() {
+        return Err(CliError(format!(
+            "no snapshots in {} (run with --save-history to record one)",
+            path.display()
+        )));
+    }
+
+    match action {
+        HistoryCommand::Diff { from, to } => {
+            let clock = DefaultClock;
+            let old = select_snapshot(&snapshots, *from, &clock)?;
+            let new = select_snapshot(&snapshots, *to, &clock)?;
+            print_diff(old, new, settings);
+        }
+        HistoryCommand::Show { repo } => {
+            let timelines = repo_timelines(&snapshots, repo);
+            if timelines.is_empty() {
+                return Err(CliError(format!("no recorded snapshot mentions '{repo}'")));
+            }
+            if settings.output == OutputFormat::Tab {
+                println!(
+                    "{}",
+                    format_history_timeline(
+                        repo,
+                        &timelines,
+                        settings.tab_style,
+                        settings.tab_colors
+                    )
+                );
+            } else {
+                let roots: Vec<_> = timelines
+                    .iter()
+                    .map(|timeline| {
+                        let entries: Vec<_> = timeline
+                            .entries
+                            .iter()
+                            .map(|(timestamp, state)| {
+                                json!({"timestamp": timestamp, "state": state})
+                            })
+                            .collect();
+                        json!({
+                            "root": timeline.root,
+                            "root_display": timeline.root_display,
+                            "entries": entries,
+                        })
+                    })
+                    .collect();
+                println!("{}", json!(roots));
+            }
+        }
+    }
+    Ok(0)
+}
+
+fn print_diff(old: &Snapshot, new: &Snapshot, settings: &Settings) {
+    let changes = diff_snapshots(old, new);
+    if settings.output == OutputFormat::Tab {
+        let range = (old.timestamp, new.timestamp);
+        println!(
+            "{}",
+            format_history_diff(&changes, range, settings.tab_style, settings.tab_colors)
+        );
+        return;
+    }
+    let changes: Vec<_> = changes
+        .iter()
+        .map(|change| {
+            let details = match change {
+                RepoChange::Changed { changes, .. } => changes.clone(),
+                RepoChange::Appeared(_) | RepoChange::Disappeared(_) => Vec::new(),
+            };
+            json!({
+                "repo": &change.state().repo,
+                "root": &change.state().root,
+                "change": change.label(),
+                "details": details,
+                "state": change.state(),
+            })
+        })
+        .collect();
+    let out = json!({
+        "from": old.timestamp,
+        "to": new.timestamp,
+        "changes": changes,
+    });
+    println!("{out}");
+}
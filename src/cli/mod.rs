@@ -0,0 +1,416 @@
+//! Command-line interface of the `uncommitted` binary.
+
+mod error;
+mod history;
+mod push;
+mod settings;
+mod status;
+mod tui;
+mod watch;
+
+use std::path::PathBuf;
+
+use clap::{Args as ClapArgs, Parser, Subcommand, ValueEnum};
+use uncommitted::{
+    Depth,
+    check::FailCondition,
+    history::SnapshotRef,
+    output::{Section, TabStyle},
+    status::DEFAULT_STATUS_FORMAT,
+};
+
+pub(crate) use error::CliError;
+pub(crate) use history::run_history;
+pub(crate) use push::run_push;
+pub(crate) use settings::Settings;
+pub(crate) use status::{cached_status, finish_status};
+pub(crate) use tui::run_tui;
+pub(crate) use watch::run_watch;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, ValueEnum)]
+pub(crate) enum OutputFormat {
+    Tab,
+    Json,
+    Csv,
+    Tsv,
+    Html,
+    Template,
+}
+
+impl OutputFormat {
+    /// Fail for the formats only the report itself can be written as.
+    pub(crate) fn require_tab_or_json(self, command: &str) -> Result<(), CliError> {
+        if matches!(self, OutputFormat::Tab | OutputFormat::Json) {
+            Ok(())
+        } else {
+            Err(CliError(format!(
+                "`{command}` only supports --output tab or json"
+            )))
+        }
+    }
+}
+
+/// When `--output tab` and templates use ANSI colors.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, ValueEnum)]
+pub(crate) enum ColorChoice {
+    /// Color when stdout is a terminal and `NO_COLOR` is unset
+    Auto,
+    Always,
+    Never,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, ValueEnum)]
+pub(crate) enum Backend {
+    /// Spawn the `git` binary for every query
+    Git,
+    /// Answer queries in-process with gitoxide (requires the `native` feature)
+    Native,
+}
+
+/// Options left unset fall back to the config file (see `uncommitted config show`), then to
+/// the documented defaults.
+#[derive(Parser, Debug)]
+#[command(
+    version,
+    about = "Report git repo states under roots.",
+    subcommand_precedence_over_arg = true,
+    after_help = EXIT_CODES_HELP
+)]
+pub(crate) struct Args {
+    /// Root directories to scan (default: ~/src)
+    #[arg(global = true)]
+    pub(crate) roots: Vec<PathBuf>,
+
+    /// Directory depth to search (0 = only root itself, 1 = one level of children, etc.), or
+    /// `auto` to search any depth without descending into repositories [default: 1]
+    #[arg(long, global = true, value_name = "N|auto")]
+    pub(crate) depth: Option<Depth>,
+
+    #[command(flatten)]
+    pub(crate) scan: ScanFlags,
+
+    /// Output format [default: tab]
+    #[arg(long, value_enum, global = true)]
+    pub(crate) output: Option<OutputFormat>,
+
+    /// Table style to use with --output tab [default: rounded]
+    #[arg(long, value_enum, global = true)]
+    pub(crate) tab_style: Option<TabStyle>,
+
+    /// Color tables and templates [default: auto]
+    #[arg(long, value_enum, global = true, value_name = "WHEN")]
+    pub(crate) color: Option<ColorChoice>,
+
+    /// Highlight rows whose earliest commit is older than this many days [default: 7]
+    #[arg(long, global = true, value_name = "DAYS")]
+    pub(crate) highlight_age: Option<u64>,
+
+    /// Highlight rows changing more than this many lines [default: 500]
+    #[arg(long, global = true, value_name = "N")]
+    pub(crate) highlight_lines: Option<u64>,
+
+    /// Path to git rewrite configuration TOML
+    #[arg(long)]
+    pub(crate) git_rewrite_toml: Option<PathBuf>,
+
+    /// Path to `git_rewrite` binary
+    #[arg(long)]
+    pub(crate) git_rewrite_path: Option<PathBuf>,
+
+    /// Config file to read instead of `$UNCOMMITTED_CONFIG` or ~/.config/uncommitted/config.toml
+    #[arg(long, global = true, value_name = "PATH")]
+    pub(crate) config: Option<PathBuf>,
+
+    /// History file to use instead of `$UNCOMMITTED_HISTORY` or
+    /// ~/.local/share/uncommitted/history.jsonl
+    #[arg(long, global = true, value_name = "PATH")]
+    pub(crate) history_file: Option<PathBuf>,
+
+    /// Record every git invocation and its output to a fixture file, e.g. for a bug report
+    #[arg(
+        long,
+        global = true,
+        hide = true,
+        value_name = "FILE",
+        conflicts_with = "replay"
+    )]
+    pub(crate) record: Option<PathBuf>,
+
+    /// Answer git invocations from a fixture written by --record instead of running git
+    #[arg(long, global = true, hide = true, value_name = "FILE")]
+    pub(crate) replay: Option<PathBuf>,
+
+    #[command(flatten)]
+    pub(crate) output_flags: OutputFlags,
+
+    #[command(subcommand)]
+    pub(crate) command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum Command {
+    /// Show help for `git_rewrite` TOML config fields
+    Toml,
+    /// Print the JSON Schema of `--output json` reports
+    Schema,
+    /// Inspect the uncommitted config file
+    Config {
+        #[command(subcommand)]
+        action: ConfigCommand,
+    },
+    /// Keep the report on screen and redraw it when repositories change
+    Watch {
+        /// Milliseconds without further changes to wait before rescanning
+        #[arg(long, default_value_t = 300, value_name = "MS")]
+        debounce: u64,
+    },
+    /// Browse the report interactively and run git actions on the selected repository
+    Tui,
+    /// Push pushable branches to their upstreams after confirmation (exits 128 when a push
+    /// fails)
+    Push(PushArgs),
+    /// Compare runs recorded with --save-history
+    History {
+        #[command(subcommand)]
+        action: HistoryCommand,
+    },
+    /// Print a compact summary like `U3 S1 P5 R2` for shell prompts, cached between runs
+    Status(StatusArgs),
+}
+
+/// Which pushable branches `push` publishes and how.
+#[derive(ClapArgs, Debug)]
+pub(crate) struct PushArgs {
+    /// Only push branches under a root matching this glob (repeatable)
+    #[arg(long, value_name = "GLOB")]
+    pub(crate) root: Vec<String>,
+
+    /// Only push branches of repositories matching this glob (repeatable)
+    #[arg(long, value_name = "GLOB")]
+    pub(crate) repo: Vec<String>,
+
+    /// Only push branches matching this glob (repeatable)
+    #[arg(long, value_name = "GLOB")]
+    pub(crate) branch: Vec<String>,
+
+    /// Push without asking for confirmation
+    #[arg(long, short = 'y')]
+    pub(crate) yes: bool,
+
+    /// Print the git commands that would run and push nothing
+    #[arg(long)]
+    pub(crate) dry_run: bool,
+}
+
+/// How `status` formats its summary and how long it trusts the cache.
+#[derive(ClapArgs, Debug)]
+pub(crate) struct StatusArgs {
+    /// Summary template; parts whose counters are all 0 are left out. Counters:
+    /// {uncommitted}, {staged}, {pushable}, {pullable}, {unpublished}, {stashes}, {errors}
+    #[arg(long, default_value = DEFAULT_STATUS_FORMAT, value_name = "FORMAT")]
+    pub(crate) format: String,
+
+    /// Seconds a cached summary is printed without rescanning
+    #[arg(long, default_value_t = 60, value_name = "SECS")]
+    pub(crate) ttl: u64,
+
+    /// Print a stale summary right away and rescan in a detached process
+    #[arg(long)]
+    pub(crate) background: bool,
+
+    /// Rescan and update the cache without printing (used by --background)
+    #[arg(long, hide = true)]
+    pub(crate) refresh: bool,
+}
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum ConfigCommand {
+    /// Print the effective configuration after merging the config file and flags
+    Show,
+}
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum HistoryCommand {
+    /// Show repos that appeared, disappeared or changed between two snapshots
+    Diff {
+        /// Older snapshot: runs before the latest (0 = latest) or a minimum age like 12h, 1d, 1w
+        #[arg(long, default_value = "1", value_name = "SNAPSHOT")]
+        from: SnapshotRef,
+
+        /// Newer snapshot, in the same form as --from
+        #[arg(long, default_value = "0", value_name = "SNAPSHOT")]
+        to: SnapshotRef,
+    },
+    /// Show the recorded state of one repository over time
+    Show {
+        /// Repository name as shown in the report
+        repo: String,
+    },
+}
+
+#[allow(clippy::struct_excessive_bools)]
+#[derive(ClapArgs, Debug)]
+pub(crate) struct ScanFlags {
+    /// Ignore untracked files for 'uncommitted'
+    #[arg(long, global = true, overrides_with = "untracked")]
+    no_untracked: bool,
+
+    /// Count untracked files for 'uncommitted', overriding `no_untracked` in the config
+    #[arg(long, global = true, overrides_with = "no_untracked")]
+    untracked: bool,
+
+    /// With --depth auto, also look inside repositories for nested repositories
+    #[arg(long, global = true, overrides_with = "no_nested")]
+    nested: bool,
+
+    /// Do not look inside repositories, overriding `nested` in the config
+    #[arg(long, global = true, overrides_with = "nested")]
+    no_nested: bool,
+
+    /// Report submodules as child rows and flag submodule commits that are not pushed
+    #[arg(long, global = true, overrides_with = "no_submodules")]
+    submodules: bool,
+
+    /// Do not report submodules, overriding `submodules` in the config
+    #[arg(long, global = true, overrides_with = "submodules")]
+    no_submodules: bool,
+
+    /// Print debug info while scanning
+    #[arg(long, global = true)]
+    pub(crate) debug: bool,
+
+    /// Refresh remote tracking refs before computing pushables
+    #[arg(long, global = true, overrides_with = "no_refresh_remotes")]
+    refresh_remotes: bool,
+
+    /// Use the remote tracking refs as they are, overriding `refresh_remotes` in the config
+    #[arg(long, global = true, overrides_with = "refresh_remotes")]
+    no_refresh_remotes: bool,
+
+    /// Seconds a fetch attempt may take before it is killed, 0 for no limit [default: 30]
+    #[arg(long, global = true, value_name = "SECS")]
+    pub(crate) fetch_timeout: Option<u64>,
+
+    /// Further attempts after a fetch fails or times out [default: 1]
+    #[arg(long, global = true, value_name = "N")]
+    pub(crate) fetch_retries: Option<u32>,
+
+    /// Number of repositories to inspect in parallel (default: number of CPUs)
+    #[arg(long, short = 'j', global = true, value_name = "N")]
+    pub(crate) jobs: Option<usize>,
+
+    /// Skip directories matching this gitignore-style pattern (repeatable; adds to the
+    /// config file and each root's .uncommittedignore)
+    #[arg(long, global = true, value_name = "GLOB")]
+    pub(crate) exclude: Vec<String>,
+
+    /// Scan directories matching this pattern even when an exclude pattern matched them
+    /// (repeatable)
+    #[arg(long, global = true, value_name = "GLOB")]
+    pub(crate) include: Vec<String>,
+
+    /// Git backend used to inspect repositories [default: git]
+    #[arg(long, value_enum, global = true)]
+    pub(crate) backend: Option<Backend>,
+
+    /// Seconds any git command may take before it is killed and reported as an error, 0 for
+    /// no limit [default: 120]
+    #[arg(long, global = true, value_name = "SECS")]
+    pub(crate) git_timeout: Option<u64>,
+}
+
+#[allow(clippy::struct_excessive_bools)]
+#[derive(ClapArgs, Debug)]
+pub(crate) struct OutputFlags {
+    /// Hide repos whose commits and revs columns are 0
+    #[arg(long, overrides_with = "no_omit_non_actionable")]
+    omit_non_actionable: bool,
+
+    /// Show every repo, overriding `omit_non_actionable` in the config
+    #[arg(long, overrides_with = "omit_non_actionable")]
+    no_omit_non_actionable: bool,
+
+    /// Exit with status 2 when any repository could not be inspected (same as --fail-on errors)
+    #[arg(long)]
+    pub(crate) fail_on_error: bool,
+
+    /// Exit non-zero when any of these conditions match (comma-separated)
+    #[arg(long, value_enum, value_delimiter = ',', value_name = "CONDITION")]
+    pub(crate) fail_on: Vec<FailCondition>,
+
+    /// Emit only this section with --output csv or tsv
+    #[arg(long, value_enum, value_name = "SECTION")]
+    pub(crate) section: Option<Section>,
+
+    /// Template for --output template: a built-in (slack, markdown, email, prompt) or a
+    /// minijinja template file; implies --output template
+    #[arg(long, value_name = "NAME|FILE")]
+    pub(crate) template: Option<String>,
+
+    /// Print a one-line summary instead of the report
+    #[arg(long)]
+    pub(crate) summary: bool,
+
+    /// Append this run to the history store read by `uncommitted history`
+    #[arg(long, overrides_with = "no_save_history")]
+    save_history: bool,
+
+    /// Do not record this run, overriding `save_history` in the config
+    #[arg(long, overrides_with = "save_history")]
+    no_save_history: bool,
+}
+
+impl ScanFlags {
+    pub(crate) fn no_untracked(&self) -> Option<bool> {
+        toggle(self.no_untracked, self.untracked)
+    }
+
+    pub(crate) fn nested(&self) -> Option<bool> {
+        toggle(self.nested, self.no_nested)
+    }
+
+    pub(crate) fn submodules(&self) -> Option<bool> {
+        toggle(self.submodules, self.no_submodules)
+    }
+
+    pub(crate) fn refresh_remotes(&self) -> Option<bool> {
+        toggle(self.refresh_remotes, self.no_refresh_remotes)
+    }
+}
+
+impl OutputFlags {
+    pub(crate) fn omit_non_actionable(&self) -> Option<bool> {
+        toggle(self.omit_non_actionable, self.no_omit_non_actionable)
+    }
+
+    pub(crate) fn save_history(&self) -> Option<bool> {
+        toggle(self.save_history, self.no_save_history)
+    }
+}
+
+/// The value of a `--x`/`--no-x` flag pair, `None` when neither was given. The pair overrides
+/// each other, so only the last one given is set.
+fn toggle(on: bool, off: bool) -> Option<bool> {
+    if on {
+        Some(true)
+    } else if off {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+const EXIT_CODES_HELP: &str = "\
+Exit codes:
+  0   nothing selected with --fail-on matched
+  1   the command itself failed (bad arguments, unreadable config)
+  2   errors: a repository could not be inspected
+  4   uncommitted: a repository has uncommitted changes
+  8   staged: a repository has staged changes
+  16  pushable: a branch has unpushed commits
+  32  untracked: a repository is missing from the git_rewrite config
+  64  git-rewrite: a git_rewrite pair has pending commits
+  128 push: a branch could not be pushed
+  130 interrupted by Ctrl-C
+Codes 2-64 are bits and are added together when several conditions match. 128 and 130 are
+never combined with them: 130 is not 128 plus the errors bit.";
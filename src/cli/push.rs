@@ -0,0 +1,136 @@
+use std::io::{BufRead, IsTerminal, Write};
+
+use serde_json::{Value, json};
+use uncommitted::{
+    GitRunner, ReportData,
+    actions::{PushFilter, PushPlan, PushResult, PushTarget, plan_push, push_all},
+    output::{format_push_plan, format_push_results},
+};
+
+use super::{CliError, OutputFormat, PushArgs, Settings};
+
+/// Exit status when a push fails, above every sum of the `--fail-on` bits.
+const PUSH_FAILED_EXIT_CODE: i32 = 128;
+
+/// Push the pushable branches of `data` selected by `args`, after confirmation unless
+/// `--yes` or `--dry-run` was given.
+pub(crate) fn run_push(
+    args: &PushArgs,
+    settings: &Settings,
+    data: &ReportData,
+    git: &dyn GitRunner,
+) -> Result<i32, CliError> {
+    settings.output.require_tab_or_json("push")?;
+    let filter = PushFilter::new(&args.root, &args.repo, &args.branch)
+        .map_err(|err| CliError(err.to_string()))?;
+    let plan = plan_push(data, &filter, git);
+    for skip in &plan.skipped {
+        eprintln!(
+            "warning: skipping {} {}: {}",
+            skip.repo, skip.branch, skip.reason
+        );
+    }
+
+    if args.dry_run {
+        print_dry_run(&plan, settings);
+        return Ok(0);
+    }
+    if settings.output == OutputFormat::Tab {
+        println!(
+            "{}",
+            format_push_plan(&plan.targets, settings.tab_style, settings.tab_colors)
+        );
+    }
+    if plan.targets.is_empty() {
+        if settings.output == OutputFormat::Json {
+            println!("{}", push_json(&plan, &[]));
+        }
+        return Ok(0);
+    }
+    if !args.yes && !confirm(plan.targets.len())? {
+        eprintln!("aborted, nothing pushed");
+        return Ok(1);
+    }
+
+    let results = push_all(&plan.targets, git, settings.scan.jobs);
+    if settings.output == OutputFormat::Tab {
+        println!(
+            "{}",
+            format_push_results(&results, settings.tab_style, settings.tab_colors)
+        );
+    } else {
+        println!("{}", push_json(&plan, &results));
+    }
+    let failed = results.iter().any(|result| result.error.is_some());
+    Ok(if failed { PUSH_FAILED_EXIT_CODE } else { 0 })
+}
+
+fn print_dry_run(plan: &PushPlan, settings: &Settings) {
+    if settings.output == OutputFormat::Tab {
+        println!(
+            "{}",
+            format_push_plan(&plan.targets, settings.tab_style, settings.tab_colors)
+        );
+        for target in &plan.targets {
+            println!("{}", target.command_line());
+        }
+    } else {
+        let out = json!({
+            "dry_run": true,
+            "plan": plan.targets.iter().map(target_json).collect::<Vec<_>>(),
+            "skipped": &plan.skipped,
+        });
+        println!("{out}");
+    }
+}
+
+/// Ask on stderr whether to push; only an explicit yes confirms.
+fn confirm(count: usize) -> Result<bool, CliError> {
+    let stdin = std::io::stdin();
+    if !stdin.is_terminal() {
+        return Err(CliError(
+            "refusing to push without confirmation: stdin is not a terminal (pass --yes)"
+                .to_string(),
+        ));
+    }
+    let branches = if count == 1 { "branch" } else { "branches" };
+    eprint!("Push {count} {branches}? [y/N] ");
+    std::io::stderr()
+        .flush()
+        .map_err(|err| CliError(format!("failed to write prompt: {err}")))?;
+    let mut answer = String::new();
+    stdin
+        .lock()
+        .read_line(&mut answer)
+        .map_err(|err| CliError(format!("failed to read answer: {err}")))?;
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes" | "YES" | "Yes"))
+}
+
+fn push_json(plan: &PushPlan, results: &[PushResult]) -> Value {
+    let results: Vec<Value> = results
+        .iter()
+        .map(|result| {
+            let mut entry = target_json(&result.target);
+            entry["status"] = json!(if result.error.is_some() {
+                "failed"
+            } else {
+                "pushed"
+            });
+            entry["duration_ms"] = json!(result.duration.as_millis());
+            entry["error"] = json!(result.error);
+            entry
+        })
+        .collect();
+    json!({
+        "plan": plan.targets.iter().map(target_json).collect::<Vec<_>>(),
+        "skipped": &plan.skipped,
+        "results": results,
+    })
+}
+
+fn target_json(target: &PushTarget) -> Value {
+    let mut entry = json!(target);
+    entry["upstream"] = json!(target.upstream());
+    entry["command"] = json!(target.command_line());
+    entry
+}
@@ -0,0 +1,344 @@
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use clap::ValueEnum;
+use uncommitted::{
+    DefaultFsOps, Depth, FsOps, Options, ReportData, RootSettings,
+    check::FailCondition,
+    config::{Config, ConfigLocation, RootConfig},
+    history::locate_history,
+    output::{ReportTemplate, Section, TabColors, TabStyle},
+    validate_pattern,
+};
+
+use super::{Args, Backend, CliError, ColorChoice, OutputFormat};
+
+const DEFAULT_DEPTH: Depth = Depth::Levels(1);
+const DEFAULT_FETCH_TIMEOUT_SECS: u64 = 30;
+const DEFAULT_FETCH_RETRIES: u32 = 1;
+const DEFAULT_GIT_TIMEOUT_SECS: u64 = 120;
+const SECS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Effective settings after layering command-line flags over the config file.
+///
+/// Precedence is flag > per-root `[root."<path>"]` section > top-level config key > default;
+/// a `--depth`, `--no-untracked` or `--untracked` flag therefore also overrides per-root
+/// sections. Each boolean key has a flag pair, so a flag can also switch a config `true` off.
+#[derive(Debug)]
+pub(crate) struct Settings {
+    pub(crate) config: Option<ConfigLocation>,
+    pub(crate) scan: Options,
+    pub(crate) backend: Backend,
+    /// Limit for every git command; `None` lets commands run until they finish.
+    pub(crate) git_timeout: Option<Duration>,
+    pub(crate) output: OutputFormat,
+    pub(crate) tab_style: TabStyle,
+    pub(crate) color: ColorChoice,
+    /// Colors for tables, enabled when `color` resolved to on for this run.
+    pub(crate) tab_colors: TabColors,
+    /// Only section written by the csv and tsv outputs.
+    pub(crate) section: Option<Section>,
+    /// Built-in template name or template file used by `--output template`.
+    pub(crate) template: Option<String>,
+    pub(crate) omit_non_actionable: bool,
+    pub(crate) fail_on: Vec<FailCondition>,
+    pub(crate) summary: bool,
+    pub(crate) save_history: bool,
+    pub(crate) history_file: Option<PathBuf>,
+}
+
+impl Settings {
+    pub(crate) fn resolve(
+        args: &Args,
+        location: Option<ConfigLocation>,
+        config: Config,
+    ) -> Result<Self, CliError> {
+        let mut fail_on = if args.output_flags.fail_on.is_empty() {
+            parse_values(config.fail_on.as_deref().unwrap_or_default(), "fail_on")?
+        } else {
+            args.output_flags.fail_on.clone()
+        };
+        if args.output_flags.fail_on_error && !fail_on.contains(&FailCondition::Errors) {
+            fail_on.push(FailCondition::Errors);
+        }
+
+        let template = args
+            .output_flags
+            .template
+            .clone()
+            .or(config.template.clone());
+        // A --template flag asks for template output unless --output says otherwise.
+        let output_flag = args.output.or(args
+            .output_flags
+            .template
+            .as_ref()
+            .map(|_| OutputFormat::Template));
+        let output =
+            pick(output_flag, config.output.as_deref(), "output")?.unwrap_or(OutputFormat::Tab);
+        if output == OutputFormat::Template && template.is_none() {
+            return Err(CliError(
+                "--output template requires --template NAME|FILE".to_string(),
+            ));
+        }
+        let section = args.output_flags.section;
+        if section.is_some() && !matches!(output, OutputFormat::Csv | OutputFormat::Tsv) {
+            return Err(CliError(
+                "--section requires --output csv or --output tsv".to_string(),
+            ));
+        }
+
+        let color =
+            pick(args.color, config.color.as_deref(), "color")?.unwrap_or(ColorChoice::Auto);
+        let tab_colors = TabColors {
+            enabled: color_enabled(color),
+            age_threshold: args
+                .highlight_age
+                .or(config.highlight_age)
+                .map_or(TabColors::DEFAULT_AGE_THRESHOLD, |days| {
+                    Duration::from_secs(days.saturating_mul(SECS_PER_DAY))
+                }),
+            lines_threshold: args
+                .highlight_lines
+                .or(config.highlight_lines)
+                .unwrap_or(TabColors::DEFAULT_LINES_THRESHOLD),
+        };
+
+        Ok(Settings {
+            config: location,
+            backend: pick(args.scan.backend, config.backend.as_deref(), "backend")?
+                .unwrap_or(Backend::Git),
+            git_timeout: seconds(
+                args.scan
+                    .git_timeout
+                    .or(config.git_timeout)
+                    .unwrap_or(DEFAULT_GIT_TIMEOUT_SECS),
+            ),
+            output,
+            tab_style: pick(args.tab_style, config.tab_style.as_deref(), "tab_style")?
+                .unwrap_or(TabStyle::Rounded),
+            color,
+            tab_colors,
+            section,
+            template,
+            omit_non_actionable: args
+                .output_flags
+                .omit_non_actionable()
+                .or(config.omit_non_actionable)
+                .unwrap_or(false),
+            fail_on,
+            summary: args.output_flags.summary,
+            save_history: args
+                .output_flags
+                .save_history()
+                .or(config.save_history)
+                .unwrap_or(false),
+            history_file: locate_history(
+                args.history_file
+                    .as_deref()
+                    .or(config.history_file.as_deref()),
+            ),
+            scan: scan_options(args, config)?,
+        })
+    }
+
+    /// Render `data` with the `--template` of `--output template`, coloring as `--color` says.
+    pub(crate) fn render_template(&self, data: &ReportData) -> Result<String, CliError> {
+        let spec = self.template.as_deref().ok_or_else(|| {
+            CliError("--output template requires --template NAME|FILE".to_string())
+        })?;
+        Ok(ReportTemplate::load(spec)?.render(data, self.tab_colors.enabled)?)
+    }
+
+    /// The settings as a fully populated config file, for `config show`.
+    pub(crate) fn to_config(&self) -> Config {
+        let scan = &self.scan;
+        Config {
+            roots: if scan.roots.is_empty() {
+                vec![PathBuf::from("~/src")]
+            } else {
+                scan.roots.clone()
+            },
+            depth: Some(scan.depth),
+            no_untracked: Some(scan.no_untracked),
+            nested: Some(scan.nested),
+            submodules: Some(scan.submodules),
+            refresh_remotes: Some(scan.refresh_remotes),
+            fetch_timeout: Some(scan.fetch_timeout.map_or(0, |timeout| timeout.as_secs())),
+            fetch_retries: Some(scan.fetch_retries),
+            jobs: scan.jobs,
+            exclude: scan.exclude.clone(),
+            include: scan.include.clone(),
+            backend: Some(value_name(&self.backend)),
+            git_timeout: Some(self.git_timeout.map_or(0, |timeout| timeout.as_secs())),
+            output: Some(value_name(&self.output)),
+            tab_style: Some(value_name(&self.tab_style)),
+            color: Some(value_name(&self.color)),
+            highlight_age: Some(self.tab_colors.age_threshold.as_secs() / SECS_PER_DAY),
+            highlight_lines: Some(self.tab_colors.lines_threshold),
+            template: self.template.clone(),
+            omit_non_actionable: Some(self.omit_non_actionable),
+            fail_on: Some(self.fail_on.iter().map(value_name).collect()),
+            save_history: Some(self.save_history),
+            history_file: self.history_file.clone(),
+            git_rewrite_toml: scan.git_rewrite_toml.clone(),
+            git_rewrite_path: scan.git_rewrite_path.clone(),
+            root_settings: scan
+                .root_settings
+                .iter()
+                .map(|root| {
+                    let settings = RootConfig {
+                        depth: root.depth,
+                        no_untracked: root.no_untracked,
+                    };
+                    (root.root.to_string_lossy().to_string(), settings)
+                })
+                .collect(),
+        }
+    }
+}
+
+/// `auto` colors a terminal unless `NO_COLOR` is set to anything (<https://no-color.org>).
+fn color_enabled(choice: ColorChoice) -> bool {
+    match choice {
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+        ColorChoice::Auto => {
+            std::env::var_os("NO_COLOR").is_none_or(|value| value.is_empty())
+                && std::io::stdout().is_terminal()
+        }
+    }
+}
+
+/// A timeout in seconds, where 0 means no limit.
+fn seconds(secs: u64) -> Option<Duration> {
+    (secs > 0).then(|| Duration::from_secs(secs))
+}
+
+fn scan_options(args: &Args, config: Config) -> Result<Options, CliError> {
+    let fs = DefaultFsOps;
+    let git_rewrite_toml = args.git_rewrite_toml.clone().or(config.git_rewrite_toml);
+    let git_rewrite_path = args.git_rewrite_path.clone().or(config.git_rewrite_path);
+    if git_rewrite_toml.is_some() != git_rewrite_path.is_some() {
+        return Err(CliError(
+            "git_rewrite_toml and git_rewrite_path must be set together".to_string(),
+        ));
+    }
+
+    let exclude = merge_patterns(config.exclude, &args.scan.exclude)?;
+    let include = merge_patterns(config.include, &args.scan.include)?;
+
+    let root_settings = config
+        .root_settings
+        .into_iter()
+        .map(|(root, settings)| RootSettings {
+            root: PathBuf::from(root),
+            depth: settings.depth.filter(|_| args.depth.is_none()),
+            no_untracked: settings
+                .no_untracked
+                .filter(|_| args.scan.no_untracked().is_none()),
+        })
+        .filter(|root| root.depth.is_some() || root.no_untracked.is_some())
+        .collect();
+
+    Ok(Options {
+        roots: if args.roots.is_empty() {
+            config.roots
+        } else {
+            args.roots.clone()
+        },
+        depth: args.depth.or(config.depth).unwrap_or(DEFAULT_DEPTH),
+        no_untracked: args
+            .scan
+            .no_untracked()
+            .or(config.no_untracked)
+            .unwrap_or(false),
+        nested: args.scan.nested().or(config.nested).unwrap_or(false),
+        submodules: args
+            .scan
+            .submodules()
+            .or(config.submodules)
+            .unwrap_or(false),
+        debug: args.scan.debug,
+        refresh_remotes: args
+            .scan
+            .refresh_remotes()
+            .or(config.refresh_remotes)
+            .unwrap_or(false),
+        fetch_timeout: seconds(
+            args.scan
+                .fetch_timeout
+                .or(config.fetch_timeout)
+                .unwrap_or(DEFAULT_FETCH_TIMEOUT_SECS),
+        ),
+        fetch_retries: args
+            .scan
+            .fetch_retries
+            .or(config.fetch_retries)
+            .unwrap_or(DEFAULT_FETCH_RETRIES),
+        jobs: args.scan.jobs.or(config.jobs),
+        git_rewrite_toml: git_rewrite_toml
+            .map(|path| resolve_path(&fs, &path))
+            .transpose()?,
+        git_rewrite_path: git_rewrite_path
+            .map(|path| resolve_path(&fs, &path))
+            .transpose()?,
+        root_settings,
+        exclude,
+        include,
+    })
+}
+
+/// Config patterns first, then the flags, so a flag wins when both match a directory.
+fn merge_patterns(config: Vec<String>, flags: &[String]) -> Result<Vec<String>, CliError> {
+    let patterns: Vec<String> = config.into_iter().chain(flags.iter().cloned()).collect();
+    for pattern in &patterns {
+        validate_pattern(pattern)
+            .map_err(|e| CliError(format!("invalid pattern '{pattern}': {e}")))?;
+    }
+    Ok(patterns)
+}
+
+/// Prefer the flag, otherwise parse the config value with the flag's spelling.
+fn pick<T: ValueEnum>(
+    flag: Option<T>,
+    config: Option<&str>,
+    key: &str,
+) -> Result<Option<T>, CliError> {
+    match (flag, config) {
+        (Some(value), _) => Ok(Some(value)),
+        (None, Some(raw)) => parse_value(raw, key).map(Some),
+        (None, None) => Ok(None),
+    }
+}
+
+fn parse_values<T: ValueEnum>(raw: &[String], key: &str) -> Result<Vec<T>, CliError> {
+    raw.iter().map(|value| parse_value(value, key)).collect()
+}
+
+fn parse_value<T: ValueEnum>(raw: &str, key: &str) -> Result<T, CliError> {
+    T::from_str(raw, true).map_err(|_| {
+        let allowed: Vec<String> = T::value_variants().iter().map(value_name).collect();
+        CliError(format!(
+            "invalid {key} '{raw}' in config (expected one of: {})",
+            allowed.join(", ")
+        ))
+    })
+}
+
+fn value_name<T: ValueEnum>(value: &T) -> String {
+    value
+        .to_possible_value()
+        .map(|value| value.get_name().to_string())
+        .unwrap_or_default()
+}
+
+fn resolve_path(fs: &DefaultFsOps, path: &Path) -> Result<PathBuf, CliError> {
+    let expanded = fs.expand_tilde(path);
+    if expanded.is_absolute() {
+        return Ok(expanded);
+    }
+    let cwd = std::env::current_dir()
+        .map_err(|e| CliError(format!("failed to resolve current directory: {e}")))?;
+    Ok(cwd.join(expanded))
+}
@@ -0,0 +1,114 @@
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::time::{Duration, SystemTime};
+
+use uncommitted::{
+    ReportData,
+    status::{
+        StatusCounts, claim_refresh, format_status, load_status, locate_status_dir,
+        new_status_cache, release_refresh, save_status, status_cache_path,
+    },
+};
+
+use super::{CliError, Settings, StatusArgs};
+
+/// Answer `status` from the cache when it can: a fresh cache is printed as is, and with
+/// `--background` a stale one is printed while a detached `status --refresh` rescans.
+/// Returns `false` when the caller has to scan.
+pub(crate) fn cached_status(args: &StatusArgs, settings: &Settings) -> Result<bool, CliError> {
+    format_status(&args.format, &StatusCounts::default())?;
+    let Some(path) = cache_path(settings) else {
+        return Ok(false);
+    };
+    // An unreadable cache is as good as none: the next scan overwrites it.
+    let cache = load_status(&path).ok().flatten();
+    let now = SystemTime::now();
+    let key = cache_key(settings);
+    if let Some(cache) = &cache
+        && cache.is_fresh(&key, now, Duration::from_secs(args.ttl))
+        && !args.refresh
+    {
+        print_status(&args.format, &cache.counts)?;
+        return Ok(true);
+    }
+    if args.refresh || !args.background {
+        return Ok(false);
+    }
+    if let Some(cache) = cache.filter(|cache| cache.key == key) {
+        print_status(&args.format, &cache.counts)?;
+    }
+    if claim_refresh(&path, now)
+        && let Err(err) = spawn_refresh()
+    {
+        release_refresh(&path);
+        eprintln!("warning: failed to start background status refresh: {err}");
+    }
+    Ok(true)
+}
+
+/// Cache the counts of a fresh scan and print them, unless this is a background refresh.
+pub(crate) fn finish_status(
+    args: &StatusArgs,
+    settings: &Settings,
+    data: &ReportData,
+) -> Result<i32, CliError> {
+    let counts = StatusCounts::from_report(data);
+    if let Some(path) = cache_path(settings) {
+        let cache = new_status_cache(&cache_key(settings), SystemTime::now(), counts);
+        let saved = save_status(&path, &cache);
+        if args.refresh {
+            release_refresh(&path);
+        }
+        // A prompt should still show counts when the cache directory is not writable.
+        if let Err(err) = saved {
+            eprintln!("warning: {err}");
+        }
+    }
+    if !args.refresh {
+        print_status(&args.format, &counts)?;
+    }
+    Ok(0)
+}
+
+fn print_status(format: &str, counts: &StatusCounts) -> Result<(), CliError> {
+    let line = format_status(format, counts)?;
+    if !line.is_empty() {
+        println!("{line}");
+    }
+    Ok(())
+}
+
+/// Caches are kept per set of scan options, so prompts scanning different roots do not
+/// overwrite each other. Relative roots mean something else in every directory, so they
+/// also key on the working directory.
+fn cache_key(settings: &Settings) -> String {
+    let options = serde_json::to_string(&settings.scan).unwrap_or_default();
+    let relative = settings.scan.roots.iter().any(|root| root.is_relative());
+    match std::env::current_dir() {
+        Ok(cwd) if relative => format!("{}\n{options}", cwd.display()),
+        _ => options,
+    }
+}
+
+fn cache_path(settings: &Settings) -> Option<PathBuf> {
+    locate_status_dir().map(|dir| status_cache_path(&dir, &cache_key(settings)))
+}
+
+/// Re-run this command line with `--refresh`, detached from the terminal and the prompt.
+fn spawn_refresh() -> std::io::Result<()> {
+    let exe = std::env::current_exe()?;
+    let mut command = std::process::Command::new(exe);
+    command
+        .args(std::env::args_os().skip(1))
+        .arg("--refresh")
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        // Its own process group keeps the refresh alive through Ctrl-C at the prompt.
+        command.process_group(0);
+    }
+    command.spawn().map(drop)
+}
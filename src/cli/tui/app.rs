@@ -0,0 +1,176 @@
+use std::path::{Path, PathBuf};
+
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use uncommitted::{
+    ReportData,
+    actions::{RepoAction, RepoDetails},
+};
+
+use super::rows::{Row, Section, section_rows};
+
+/// Whether keys move around the report or type a commit message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum Mode {
+    Browse,
+    Commit(String),
+}
+
+/// Work a key press asks the event loop to do outside the app state.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum Effect {
+    None,
+    Quit,
+    Shell(PathBuf),
+    Run(PathBuf, RepoAction),
+    RescanAll,
+}
+
+pub(super) struct App {
+    pub(super) data: ReportData,
+    pub(super) tab: usize,
+    selected: [usize; Section::ALL.len()],
+    pub(super) rows: Vec<Row>,
+    /// Details of the repository they were collected for.
+    pub(super) details: Option<(PathBuf, RepoDetails)>,
+    pub(super) mode: Mode,
+    pub(super) status: String,
+}
+
+impl App {
+    pub(super) fn new(data: ReportData) -> Self {
+        let mut app = App {
+            data: ReportData::default(),
+            tab: 0,
+            selected: [0; Section::ALL.len()],
+            rows: Vec::new(),
+            details: None,
+            mode: Mode::Browse,
+            status: String::new(),
+        };
+        app.set_data(data);
+        app
+    }
+
+    /// Replace the report, keeping each tab's selection in range.
+    pub(super) fn set_data(&mut self, data: ReportData) {
+        self.data = data;
+        for (index, section) in Section::ALL.iter().enumerate() {
+            let len = section_rows(&self.data, *section).len();
+            self.selected[index] = self.selected[index].min(len.saturating_sub(1));
+        }
+        self.rows = section_rows(&self.data, self.section());
+        self.details = None;
+    }
+
+    pub(super) fn section(&self) -> Section {
+        Section::ALL[self.tab]
+    }
+
+    pub(super) fn selected(&self) -> usize {
+        self.selected[self.tab]
+    }
+
+    pub(super) fn selected_path(&self) -> Option<&Path> {
+        self.rows.get(self.selected()).map(|row| row.path.as_path())
+    }
+
+    pub(super) fn on_key(&mut self, key: KeyEvent) -> Effect {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            return Effect::Quit;
+        }
+        match &mut self.mode {
+            Mode::Commit(message) => match key.code {
+                KeyCode::Esc => {
+                    self.mode = Mode::Browse;
+                    Effect::None
+                }
+                KeyCode::Enter => {
+                    let message = std::mem::take(message);
+                    self.mode = Mode::Browse;
+                    if message.trim().is_empty() {
+                        self.status = "commit aborted: empty message".to_string();
+                        return Effect::None;
+                    }
+                    self.action(RepoAction::Commit(message))
+                }
+                KeyCode::Backspace => {
+                    message.pop();
+                    Effect::None
+                }
+                KeyCode::Char(c) => {
+                    message.push(c);
+                    Effect::None
+                }
+                _ => Effect::None,
+            },
+            Mode::Browse => self.browse_key(key.code),
+        }
+    }
+
+    fn browse_key(&mut self, code: KeyCode) -> Effect {
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => return Effect::Quit,
+            KeyCode::Tab | KeyCode::Right | KeyCode::Char('l') => {
+                self.switch_tab((self.tab + 1) % Section::ALL.len());
+            }
+            KeyCode::BackTab | KeyCode::Left | KeyCode::Char('h') => {
+                self.switch_tab((self.tab + Section::ALL.len() - 1) % Section::ALL.len());
+            }
+            KeyCode::Down | KeyCode::Char('j') => self.move_selection(1),
+            KeyCode::Up | KeyCode::Char('k') => self.move_selection(-1),
+            KeyCode::Home | KeyCode::Char('g') => self.selected[self.tab] = 0,
+            KeyCode::End | KeyCode::Char('G') => {
+                self.selected[self.tab] = self.rows.len().saturating_sub(1);
+            }
+            KeyCode::Char('r') => return Effect::RescanAll,
+            KeyCode::Char('s') => {
+                if let Some(path) = self.target() {
+                    return Effect::Shell(path);
+                }
+            }
+            KeyCode::Char('a') => return self.action(RepoAction::StageAll),
+            KeyCode::Char('c') if self.target().is_some() => {
+                self.mode = Mode::Commit(String::new());
+            }
+            KeyCode::Char('p') => {
+                let branch = self.rows.get(self.selected()).map(|row| row.branch.clone());
+                match branch {
+                    Some(Some(branch)) => return self.action(RepoAction::Push(branch)),
+                    Some(None) => self.status = "no branch to push for this row".to_string(),
+                    None => self.status = "nothing selected".to_string(),
+                }
+            }
+            KeyCode::Char('z') => return self.action(RepoAction::Stash),
+            KeyCode::Char('f') => return self.action(RepoAction::Fetch),
+            _ => {}
+        }
+        Effect::None
+    }
+
+    fn switch_tab(&mut self, tab: usize) {
+        self.tab = tab;
+        self.rows = section_rows(&self.data, self.section());
+    }
+
+    fn move_selection(&mut self, delta: isize) {
+        let last = self.rows.len().saturating_sub(1);
+        let selected = &mut self.selected[self.tab];
+        *selected = selected.saturating_add_signed(delta).min(last);
+    }
+
+    fn action(&mut self, action: RepoAction) -> Effect {
+        match self.target() {
+            Some(path) => Effect::Run(path, action),
+            None => Effect::None,
+        }
+    }
+
+    /// The selected row's repository, or a status message when nothing is selected.
+    fn target(&mut self) -> Option<PathBuf> {
+        let path = self.selected_path().map(Path::to_path_buf);
+        if path.is_none() {
+            self.status = "nothing selected".to_string();
+        }
+        path
+    }
+}
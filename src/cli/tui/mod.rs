@@ -0,0 +1,138 @@
+//! Interactive report: one tab per section, a detail pane for the selected repository and
+//! keys that run git actions on it.
+
+mod app;
+mod rows;
+mod view;
+
+use std::path::Path;
+use std::process::Command;
+
+use ratatui::{
+    DefaultTerminal,
+    crossterm::event::{self, Event, KeyEventKind},
+};
+use uncommitted::{
+    DefaultClock, DefaultFsOps, GitRunner, LiveReport, Options,
+    actions::{repo_details, run_action},
+};
+
+use super::{CliError, Settings};
+use app::{App, Effect};
+
+/// Show the report until the user quits, refreshing a repository's rows after every action
+/// run on it.
+pub(crate) fn run_tui(settings: &Settings, git: &dyn GitRunner) -> Result<i32, CliError> {
+    let fs = DefaultFsOps;
+    let clock = DefaultClock;
+    let live = LiveReport::scan(&settings.scan, &fs, git, &clock);
+    let mut app = App::new(live.report());
+    let mut session = Session {
+        // Rescans after an action reflect local changes; fetching is an explicit action.
+        rescan: Options {
+            refresh_remotes: false,
+            ..settings.scan.clone()
+        },
+        git,
+        live,
+    };
+
+    let mut terminal = ratatui::init();
+    let result = session.run(&mut terminal, &mut app);
+    ratatui::restore();
+    result.map(|()| 0)
+}
+
+struct Session<'a> {
+    rescan: Options,
+    git: &'a dyn GitRunner,
+    live: LiveReport,
+}
+
+impl Session<'_> {
+    fn run(&mut self, terminal: &mut DefaultTerminal, app: &mut App) -> Result<(), CliError> {
+        loop {
+            self.load_details(app);
+            draw(terminal, app)?;
+            let Event::Key(key) = event::read().map_err(|err| terminal_error(&err))? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match app.on_key(key) {
+                Effect::None => {}
+                Effect::Quit => return Ok(()),
+                Effect::Shell(path) => {
+                    ratatui::restore();
+                    app.status = open_shell(&path);
+                    *terminal = ratatui::init();
+                    self.refresh(app, &path);
+                }
+                Effect::Run(path, action) => {
+                    app.status = format!("running {} in {}...", action.label(), path.display());
+                    draw(terminal, app)?;
+                    app.status = match run_action(&path, self.git, &action) {
+                        Ok(()) => format!("{} done in {}", action.label(), path.display()),
+                        Err(err) => format!("{} failed: {err}", action.label()),
+                    };
+                    self.refresh(app, &path);
+                }
+                Effect::RescanAll => {
+                    app.status = "rescanning...".to_string();
+                    draw(terminal, app)?;
+                    self.live =
+                        LiveReport::scan(&self.rescan, &DefaultFsOps, self.git, &DefaultClock);
+                    app.set_data(self.live.report());
+                    app.status = "rescanned every repository".to_string();
+                }
+            }
+        }
+    }
+
+    /// Inspect the repository owning `path` again and show the updated report.
+    fn refresh(&mut self, app: &mut App, path: &Path) {
+        self.live.refresh(
+            &[path.to_path_buf()],
+            &self.rescan,
+            &DefaultFsOps,
+            self.git,
+            &DefaultClock,
+        );
+        app.set_data(self.live.report());
+    }
+
+    fn load_details(&self, app: &mut App) {
+        let Some(path) = app.selected_path() else {
+            return;
+        };
+        if app.details.as_ref().is_some_and(|(shown, _)| shown == path) {
+            return;
+        }
+        let path = path.to_path_buf();
+        let details = repo_details(&path, self.git);
+        app.details = Some((path, details));
+    }
+}
+
+fn draw(terminal: &mut DefaultTerminal, app: &App) -> Result<(), CliError> {
+    terminal
+        .draw(|frame| view::draw(frame, app))
+        .map(drop)
+        .map_err(|err| terminal_error(&err))
+}
+
+/// Run the user's shell in `dir` on the normal screen; returns the status line to show after.
+fn open_shell(dir: &Path) -> String {
+    let shell = std::env::var("SHELL").unwrap_or_else(|_| "sh".to_string());
+    println!("{} (exit the shell to return)", dir.display());
+    match Command::new(&shell).current_dir(dir).status() {
+        Ok(status) if status.success() => format!("back from {shell} in {}", dir.display()),
+        Ok(status) => format!("{shell} exited with {status}"),
+        Err(err) => format!("cannot start {shell}: {err}"),
+    }
+}
+
+fn terminal_error(err: &std::io::Error) -> CliError {
+    CliError(format!("terminal error: {err}"))
+}
@@ -0,0 +1,219 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use uncommitted::{ReportData, humanize_age_public, repo_label};
+
+/// A report section shown as one tab.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Section {
+    Uncommitted,
+    Staged,
+    Pushable,
+    Pullable,
+    Unpublished,
+    Stashes,
+    Submodules,
+    Errors,
+}
+
+impl Section {
+    pub(super) const ALL: [Section; 8] = [
+        Section::Uncommitted,
+        Section::Staged,
+        Section::Pushable,
+        Section::Pullable,
+        Section::Unpublished,
+        Section::Stashes,
+        Section::Submodules,
+        Section::Errors,
+    ];
+
+    pub(super) fn title(self) -> &'static str {
+        match self {
+            Section::Uncommitted => "Uncommitted",
+            Section::Staged => "Staged",
+            Section::Pushable => "Pushable",
+            Section::Pullable => "Pullable",
+            Section::Unpublished => "Unpublished",
+            Section::Stashes => "Stashes",
+            Section::Submodules => "Submodules",
+            Section::Errors => "Errors",
+        }
+    }
+
+    pub(super) fn header(self) -> &'static [&'static str] {
+        match self {
+            Section::Uncommitted | Section::Staged => {
+                &["Repo", "Branch", "Lines", "Files", "Untracked"]
+            }
+            Section::Pushable => &["Repo", "Branch", "Commits", "Earliest", "Latest"],
+            Section::Pullable => &["Repo", "Branch", "Upstream", "Behind", "Ahead"],
+            Section::Unpublished => &["Repo", "Branch", "Base", "Commits", "Latest"],
+            Section::Stashes => &["Repo", "Stash", "Branch", "Message", "Age"],
+            Section::Submodules => &["Repo", "Submodule", "Commit", "Revs"],
+            Section::Errors => &["Repo", "Command", "Error"],
+        }
+    }
+}
+
+/// One table row and the directory actions on it run in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct Row {
+    pub(super) cells: Vec<String>,
+    pub(super) path: PathBuf,
+    /// Branch the row is about, pushed by the push action; `None` for rows such as stashes.
+    pub(super) branch: Option<String>,
+}
+
+pub(super) fn section_rows(data: &ReportData, section: Section) -> Vec<Row> {
+    match section {
+        Section::Uncommitted => uncommitted_rows(data),
+        Section::Staged => staged_rows(data),
+        Section::Pushable => pushable_rows(data),
+        Section::Pullable => pullable_rows(data),
+        Section::Unpublished => unpublished_rows(data),
+        Section::Stashes => stash_rows(data),
+        Section::Submodules => submodule_rows(data),
+        Section::Errors => error_rows(data),
+    }
+}
+
+fn uncommitted_rows(data: &ReportData) -> Vec<Row> {
+    data.uncommitted
+        .iter()
+        .map(|e| Row {
+            cells: vec![
+                repo_label(&e.repo, e.worktree.as_deref()),
+                e.branch.clone(),
+                e.lines.to_string(),
+                e.files.to_string(),
+                e.untracked.to_string(),
+            ],
+            path: e.path.clone(),
+            branch: Some(e.branch.clone()),
+        })
+        .collect()
+}
+
+fn staged_rows(data: &ReportData) -> Vec<Row> {
+    data.staged
+        .iter()
+        .map(|e| Row {
+            cells: vec![
+                repo_label(&e.repo, e.worktree.as_deref()),
+                e.branch.clone(),
+                e.lines.to_string(),
+                e.files.to_string(),
+                e.untracked.to_string(),
+            ],
+            path: e.path.clone(),
+            branch: Some(e.branch.clone()),
+        })
+        .collect()
+}
+
+fn pushable_rows(data: &ReportData) -> Vec<Row> {
+    data.pushable
+        .iter()
+        .map(|e| Row {
+            cells: vec![
+                e.repo.clone(),
+                e.branch.clone(),
+                e.revs.to_string(),
+                age(e.earliest_secs),
+                age(e.latest_secs),
+            ],
+            path: e.path.clone(),
+            branch: Some(e.branch.clone()),
+        })
+        .collect()
+}
+
+fn pullable_rows(data: &ReportData) -> Vec<Row> {
+    data.pullable
+        .iter()
+        .map(|e| Row {
+            cells: vec![
+                e.repo.clone(),
+                e.branch.clone(),
+                e.upstream.clone(),
+                e.behind.to_string(),
+                e.ahead.to_string(),
+            ],
+            path: e.path.clone(),
+            branch: Some(e.branch.clone()),
+        })
+        .collect()
+}
+
+fn unpublished_rows(data: &ReportData) -> Vec<Row> {
+    data.unpublished
+        .iter()
+        .map(|e| Row {
+            cells: vec![
+                e.repo.clone(),
+                e.branch.clone(),
+                e.base.clone().unwrap_or_else(|| "-".to_string()),
+                e.revs.to_string(),
+                age(e.latest_secs),
+            ],
+            path: e.path.clone(),
+            branch: Some(e.branch.clone()),
+        })
+        .collect()
+}
+
+fn stash_rows(data: &ReportData) -> Vec<Row> {
+    data.stashes
+        .iter()
+        .map(|e| Row {
+            cells: vec![
+                e.repo.clone(),
+                e.stash.clone(),
+                e.branch.clone(),
+                e.message.clone(),
+                age(e.age_secs),
+            ],
+            path: e.path.clone(),
+            branch: None,
+        })
+        .collect()
+}
+
+fn submodule_rows(data: &ReportData) -> Vec<Row> {
+    data.submodules
+        .iter()
+        .map(|e| Row {
+            cells: vec![
+                e.repo.clone(),
+                e.path.clone(),
+                e.commit.clone(),
+                e.revs.to_string(),
+            ],
+            path: e.repo_path.join(&e.path),
+            branch: None,
+        })
+        .collect()
+}
+
+fn error_rows(data: &ReportData) -> Vec<Row> {
+    data.errors
+        .iter()
+        .map(|e| Row {
+            cells: vec![
+                repo_label(&e.repo, e.worktree.as_deref()),
+                e.command.clone(),
+                e.stderr.clone(),
+            ],
+            path: e.path.clone(),
+            branch: None,
+        })
+        .collect()
+}
+
+fn age(secs: Option<u64>) -> String {
+    secs.map_or_else(
+        || "n/a".to_string(),
+        |secs| humanize_age_public(Duration::from_secs(secs)),
+    )
+}
@@ -0,0 +1,113 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Rect},
+    style::{Modifier, Style},
+    text::Line,
+    widgets::{Block, Borders, Cell, Paragraph, Row as TableRow, Table, TableState, Tabs},
+};
+
+use super::app::{App, Mode};
+use super::rows::{Section, section_rows};
+
+const KEYS: &str = "tab/h/l section  j/k move  s shell  a stage all  c commit  p push  \
+                    z stash  f fetch  r rescan  q quit";
+
+pub(super) fn draw(frame: &mut Frame<'_>, app: &App) {
+    let [tabs, body, footer] = Layout::vertical([
+        Constraint::Length(1),
+        Constraint::Min(0),
+        Constraint::Length(2),
+    ])
+    .areas(frame.area());
+    let [list, details] =
+        Layout::vertical([Constraint::Percentage(50), Constraint::Percentage(50)]).areas(body);
+
+    draw_tabs(frame, app, tabs);
+    draw_rows(frame, app, list);
+    draw_details(frame, app, details);
+    draw_footer(frame, app, footer);
+}
+
+fn draw_tabs(frame: &mut Frame<'_>, app: &App, area: Rect) {
+    let titles = Section::ALL.iter().map(|section| {
+        let count = section_rows(&app.data, *section).len();
+        format!("{} ({count})", section.title())
+    });
+    let tabs = Tabs::new(titles)
+        .select(app.tab)
+        .highlight_style(Style::new().add_modifier(Modifier::BOLD | Modifier::REVERSED));
+    frame.render_widget(tabs, area);
+}
+
+fn draw_rows(frame: &mut Frame<'_>, app: &App, area: Rect) {
+    let header = app.section().header();
+    let block = Block::new()
+        .borders(Borders::ALL)
+        .title(format!(" {} ", app.section().title()));
+    if app.rows.is_empty() {
+        frame.render_widget(Paragraph::new("(none)").block(block), area);
+        return;
+    }
+    let rows = app
+        .rows
+        .iter()
+        .map(|row| TableRow::new(row.cells.iter().map(|cell| Cell::from(cell.as_str()))));
+    let widths = (0..header.len()).map(|column| {
+        let width = app
+            .rows
+            .iter()
+            .map(|row| row.cells[column].chars().count())
+            .chain(std::iter::once(header[column].len()))
+            .max()
+            .unwrap_or_default();
+        Constraint::Length(u16::try_from(width).unwrap_or(u16::MAX))
+    });
+    let table = Table::new(rows, widths)
+        .header(
+            TableRow::new(header.iter().copied()).style(Style::new().add_modifier(Modifier::BOLD)),
+        )
+        .block(block)
+        .row_highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+    let mut state = TableState::default().with_selected(Some(app.selected()));
+    frame.render_stateful_widget(table, area, &mut state);
+}
+
+fn draw_details(frame: &mut Frame<'_>, app: &App, area: Rect) {
+    let (title, lines) = match (&app.details, app.selected_path()) {
+        (Some((path, details)), Some(_)) => {
+            let mut lines: Vec<Line<'_>> = Vec::new();
+            for (heading, body) in [
+                ("status", &details.status),
+                ("diffstat", &details.diffstat),
+                ("commits on no remote", &details.unpushed),
+            ] {
+                lines.push(Line::styled(
+                    heading,
+                    Style::new().add_modifier(Modifier::BOLD),
+                ));
+                if body.is_empty() {
+                    lines.push(Line::from("  (none)"));
+                }
+                lines.extend(body.iter().map(|line| Line::from(format!("  {line}"))));
+            }
+            (format!(" {} ", path.display()), lines)
+        }
+        _ => (
+            " Details ".to_string(),
+            vec![Line::from("(no repository selected)")],
+        ),
+    };
+    let block = Block::new().borders(Borders::ALL).title(title);
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+fn draw_footer(frame: &mut Frame<'_>, app: &App, area: Rect) {
+    let prompt = match &app.mode {
+        Mode::Browse => KEYS.to_string(),
+        Mode::Commit(message) => {
+            format!("commit message (enter to commit, esc to cancel): {message}")
+        }
+    };
+    let lines = vec![Line::from(app.status.as_str()), Line::from(prompt)];
+    frame.render_widget(Paragraph::new(lines), area);
+}
@@ -0,0 +1,293 @@
+use std::collections::HashSet;
+use std::path::{Component, Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+use chrono::Local;
+use notify::{EventKind, RecursiveMode, Watcher};
+use uncommitted::{
+    DefaultClock, DefaultFsOps, GitRunner, LiveReport, Options, Refresh,
+    output::{Delimiter, format_tab, to_delimited, to_html, to_json},
+};
+use walkdir::WalkDir;
+
+use super::{CliError, OutputFormat, Settings};
+
+/// Longest a steady stream of changes can postpone a rescan when it exceeds the debounce.
+const MAX_DEBOUNCE_WAIT: Duration = Duration::from_secs(2);
+
+/// Redraw the report whenever a watched repository changes, until interrupted.
+///
+/// Repositories and their linked worktrees are watched recursively, except for directories
+/// the exclude patterns and `.uncommittedignore` files skip; the roots and the directories
+/// leading to each repository are watched on their own so new repositories are noticed. Only
+/// repositories owning a changed path that is not excluded are inspected again.
+pub(crate) fn run_watch(
+    settings: &Settings,
+    git: &dyn GitRunner,
+    debounce: Duration,
+) -> Result<i32, CliError> {
+    let fs = DefaultFsOps;
+    let clock = DefaultClock;
+    let mut live = LiveReport::scan(&settings.scan, &fs, git, &clock);
+    // Fetching on every rescan would write refs and trigger another rescan.
+    let rescan = Options {
+        refresh_remotes: false,
+        ..settings.scan.clone()
+    };
+
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx)
+        .map_err(|e| CliError(format!("failed to start file watcher: {e}")))?;
+    let mut watch_set = WatchSet::default();
+    watch_set.sync(&mut watcher, &live, settings.scan.debug);
+    draw(&live, settings, None);
+
+    while let Ok(event) = rx.recv() {
+        let mut changed = Vec::new();
+        collect_paths(event, &mut changed);
+        let deadline = Instant::now() + debounce.max(MAX_DEBOUNCE_WAIT);
+        loop {
+            let left = deadline.saturating_duration_since(Instant::now());
+            if left.is_zero() {
+                break;
+            }
+            match rx.recv_timeout(debounce.min(left)) {
+                Ok(event) => collect_paths(event, &mut changed),
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(RecvTimeoutError::Disconnected) => return Ok(0),
+            }
+        }
+        changed.sort();
+        changed.dedup();
+        let resync = watch_set.misses(&changed, &live);
+        changed.retain(|path| !live.is_excluded(path));
+        if changed.is_empty() {
+            if resync {
+                watch_set.sync(&mut watcher, &live, settings.scan.debug);
+            }
+            continue;
+        }
+        if settings.scan.debug {
+            for path in &changed {
+                eprintln!("[debug] changed: {}", path.display());
+            }
+        }
+        let refresh = live.refresh(&changed, &rescan, &fs, git, &clock);
+        if refresh.rediscovered || resync {
+            watch_set.sync(&mut watcher, &live, settings.scan.debug);
+        }
+        draw(&live, settings, Some(&refresh));
+    }
+    Ok(0)
+}
+
+#[derive(Default)]
+struct WatchSet {
+    recursive: HashSet<PathBuf>,
+    shallow: HashSet<PathBuf>,
+    /// Directories above an excluded one, watched on their own; a directory created in one of
+    /// them is not watched until the next sync.
+    split: HashSet<PathBuf>,
+}
+
+impl WatchSet {
+    /// Watch the directories `live` currently depends on and drop watches it no longer needs.
+    fn sync(&mut self, watcher: &mut impl Watcher, live: &LiveReport, debug: bool) {
+        let mut recursive = HashSet::new();
+        let mut split = HashSet::new();
+        for dir in live.repo_dirs() {
+            let excluded = excluded_dirs(&dir, live);
+            plan_tree(&dir, &excluded, &mut recursive, &mut split);
+        }
+        let shallow: HashSet<PathBuf> = live
+            .discovery_dirs()
+            .into_iter()
+            .chain(split.iter().cloned())
+            .collect();
+        update(
+            watcher,
+            &mut self.recursive,
+            recursive,
+            RecursiveMode::Recursive,
+            debug,
+        );
+        update(
+            watcher,
+            &mut self.shallow,
+            shallow,
+            RecursiveMode::NonRecursive,
+            debug,
+        );
+        self.split = split;
+    }
+
+    /// Whether a directory appeared that the watches do not account for: one created where
+    /// only its parent is watched, or an excluded one inside a recursive watch.
+    fn misses(&self, changed: &[PathBuf], live: &LiveReport) -> bool {
+        changed.iter().any(|path| {
+            let Some(parent) = path.parent() else {
+                return false;
+            };
+            path.is_dir()
+                && if self.split.contains(parent) {
+                    !live.is_excluded(path)
+                } else {
+                    live.is_excluded(path)
+                        && !live.is_excluded(parent)
+                        && self.recursive.iter().any(|dir| parent.starts_with(dir))
+                }
+        })
+    }
+}
+
+/// Excluded directories below `dir`, without descending into them or into `.git`.
+fn excluded_dirs(dir: &Path, live: &LiveReport) -> HashSet<PathBuf> {
+    let mut excluded = HashSet::new();
+    let mut walker = WalkDir::new(dir)
+        .min_depth(1)
+        .follow_links(false)
+        .into_iter();
+    while let Some(entry) = walker.next() {
+        let Ok(entry) = entry else {
+            continue;
+        };
+        if !entry.file_type().is_dir() {
+            continue;
+        }
+        if entry.file_name() == ".git" {
+            walker.skip_current_dir();
+        } else if live.is_excluded(entry.path()) {
+            excluded.insert(entry.path().to_path_buf());
+            walker.skip_current_dir();
+        }
+    }
+    excluded
+}
+
+/// Watch `dir` recursively when nothing below it is excluded; otherwise watch it on its own
+/// and plan each subdirectory that is not excluded the same way.
+fn plan_tree(
+    dir: &Path,
+    excluded: &HashSet<PathBuf>,
+    recursive: &mut HashSet<PathBuf>,
+    split: &mut HashSet<PathBuf>,
+) {
+    if !excluded.iter().any(|path| path.starts_with(dir)) {
+        recursive.insert(dir.to_path_buf());
+        return;
+    }
+    split.insert(dir.to_path_buf());
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if entry.file_type().is_ok_and(|kind| kind.is_dir()) && !excluded.contains(&path) {
+            plan_tree(&path, excluded, recursive, split);
+        }
+    }
+}
+
+fn update(
+    watcher: &mut impl Watcher,
+    current: &mut HashSet<PathBuf>,
+    wanted: HashSet<PathBuf>,
+    mode: RecursiveMode,
+    debug: bool,
+) {
+    for path in current.difference(&wanted) {
+        let _ = watcher.unwatch(path);
+    }
+    for path in wanted.difference(current) {
+        if let Err(err) = watcher.watch(path, mode) {
+            eprintln!("warning: cannot watch {}: {err}", path.display());
+        } else if debug {
+            eprintln!("[debug] watching {} ({mode:?})", path.display());
+        }
+    }
+    *current = wanted;
+}
+
+fn collect_paths(event: notify::Result<notify::Event>, changed: &mut Vec<PathBuf>) {
+    let Ok(event) = event else {
+        return;
+    };
+    if matches!(event.kind, EventKind::Access(_)) {
+        return;
+    }
+    changed.extend(event.paths.into_iter().filter(|path| !is_noise(path)));
+}
+
+/// Git writes objects, reflogs and lock files while it works; none of them changes the report
+/// without a ref, index or working tree change that is reported on its own.
+fn is_noise(path: &Path) -> bool {
+    if path
+        .extension()
+        .is_some_and(|extension| extension == "lock")
+    {
+        return true;
+    }
+    let mut components = path.components();
+    while let Some(component) = components.next() {
+        if component == Component::Normal(".git".as_ref()) {
+            return matches!(
+                components.next(),
+                Some(Component::Normal(name)) if name == "objects" || name == "logs"
+            );
+        }
+    }
+    false
+}
+
+fn draw(live: &LiveReport, settings: &Settings, refresh: Option<&Refresh>) {
+    let data = live.report();
+    let omit = settings.omit_non_actionable;
+    match settings.output {
+        OutputFormat::Tab => {
+            // Clear the screen and move the cursor home so the report is redrawn in place.
+            print!("\x1b[2J\x1b[H");
+            let updated = Local::now().format("%H:%M:%S");
+            let status = match refresh {
+                None => String::new(),
+                Some(refresh) if refresh.rescanned.is_empty() => ", nothing rescanned".to_string(),
+                Some(refresh) => format!(", rescanned {}", names(&refresh.rescanned)),
+            };
+            println!(
+                "watching {} repos, updated {updated}{status} (Ctrl-C to stop)",
+                data.repos.len()
+            );
+            println!(
+                "{}",
+                format_tab(&data, settings.tab_style, omit, settings.tab_colors)
+            );
+        }
+        OutputFormat::Json => println!("{}", to_json(&data)),
+        OutputFormat::Csv => {
+            let out = to_delimited(&data, Delimiter::Comma, settings.section, omit);
+            println!("{out}");
+        }
+        OutputFormat::Tsv => {
+            let out = to_delimited(&data, Delimiter::Tab, settings.section, omit);
+            println!("{out}");
+        }
+        OutputFormat::Html => print!("{}", to_html(&data, omit)),
+        OutputFormat::Template => match settings.render_template(&data) {
+            Ok(out) => println!("{out}"),
+            Err(err) => eprintln!("error: {err}"),
+        },
+    }
+}
+
+fn names(paths: &[PathBuf]) -> String {
+    paths
+        .iter()
+        .map(|path| {
+            path.file_name().map_or_else(
+                || path.display().to_string(),
+                |n| n.to_string_lossy().to_string(),
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -0,0 +1,240 @@
+//! User configuration for `uncommitted` itself, read from `config.toml`.
+//!
+//! The file is looked up at `--config`, then `$UNCOMMITTED_CONFIG`, then
+//! `$XDG_CONFIG_HOME/uncommitted/config.toml` (falling back to `~/.config`). Every field is
+//! optional and only supplies defaults: command-line flags win over per-root `[root."<path>"]`
+//! sections, which win over top-level keys, which win over built-in defaults.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::Depth;
+
+/// Environment variable naming a config file to use instead of the XDG location.
+pub const CONFIG_ENV: &str = "UNCOMMITTED_CONFIG";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub roots: Vec<PathBuf>,
+    /// Directory levels to search, or `"auto"`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub depth: Option<Depth>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub no_untracked: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nested: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub submodules: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub refresh_remotes: Option<bool>,
+    /// Seconds a fetch attempt may take before it is killed; 0 disables the limit.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fetch_timeout: Option<u64>,
+    /// Further attempts after a fetch fails or times out.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fetch_retries: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jobs: Option<usize>,
+    /// Gitignore-style directory patterns to skip, added to any `--exclude` flags.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub exclude: Vec<String>,
+    /// Patterns re-including excluded directories, added to any `--include` flags.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub include: Vec<String>,
+    /// `git` or `native`, spelled as on the command line.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backend: Option<String>,
+    /// Seconds any git command may take before it is killed; 0 disables the limit.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub git_timeout: Option<u64>,
+    /// `tab`, `json`, `csv`, `tsv`, `html` or `template`, spelled as on the command line.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+    /// Any `--tab-style` value, e.g. `ascii` or `modern-rounded`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tab_style: Option<String>,
+    /// `auto`, `always` or `never`, like `--color`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    /// Days after which a commit's row is highlighted in tables.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub highlight_age: Option<u64>,
+    /// Changed lines above which a row is highlighted in tables.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub highlight_lines: Option<u64>,
+    /// Built-in template name or template file for `output = "template"`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub omit_non_actionable: Option<bool>,
+    /// `--fail-on` conditions, e.g. `["uncommitted", "pushable"]`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fail_on: Option<Vec<String>>,
+    /// Record every run in the history store, like `--save-history`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub save_history: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub history_file: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub git_rewrite_toml: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub git_rewrite_path: Option<PathBuf>,
+    /// Settings for individual roots, keyed by the root path as written in `roots` or on the
+    /// command line (`~` is expanded before matching).
+    #[serde(rename = "root", skip_serializing_if = "BTreeMap::is_empty")]
+    pub root_settings: BTreeMap<String, RootConfig>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RootConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub depth: Option<Depth>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub no_untracked: Option<bool>,
+}
+
+/// Where the config was looked for and whether it was required to exist.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigLocation {
+    pub path: PathBuf,
+    /// Set when the path came from `--config` or the environment, so a missing file is an error.
+    pub explicit: bool,
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Read {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    Parse {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+}
+
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigError::Read { path, source } => {
+                write!(f, "failed to read config {}: {source}", path.display())
+            }
+            ConfigError::Parse { path, source } => {
+                write!(f, "failed to parse config {}: {source}", path.display())
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// Resolve the config path from `--config`, the environment and the XDG base directories.
+#[must_use]
+pub fn locate_config(cli: Option<&Path>) -> Option<ConfigLocation> {
+    if let Some(path) = cli {
+        return Some(ConfigLocation {
+            path: path.to_path_buf(),
+            explicit: true,
+        });
+    }
+    if let Some(path) = non_empty_env(CONFIG_ENV) {
+        return Some(ConfigLocation {
+            path: PathBuf::from(path),
+            explicit: true,
+        });
+    }
+    let base = non_empty_env("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+        .or_else(|| non_empty_env("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(ConfigLocation {
+        path: base.join("uncommitted").join("config.toml"),
+        explicit: false,
+    })
+}
+
+/// Load the config at `location`; a missing implicit (XDG) file yields the empty config.
+///
+/// # Errors
+/// Returns an error if the file cannot be read (or is missing although it was requested
+/// explicitly) or is not valid config TOML.
+pub fn load_config(location: &ConfigLocation) -> Result<Config, ConfigError> {
+    let text = match std::fs::read_to_string(&location.path) {
+        Ok(text) => text,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound && !location.explicit => {
+            return Ok(Config::default());
+        }
+        Err(source) => {
+            return Err(ConfigError::Read {
+                path: location.path.clone(),
+                source,
+            });
+        }
+    };
+    toml::from_str(&text).map_err(|source| ConfigError::Parse {
+        path: location.path.clone(),
+        source,
+    })
+}
+
+fn non_empty_env(key: &str) -> Option<std::ffi::OsString> {
+    std::env::var_os(key).filter(|value| !value.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_top_level_and_per_root_settings() {
+        let config: Config = toml::from_str(
+            r#"
+roots = ["~/src", "~/work"]
+depth = 2
+tab_style = "ascii"
+fail_on = ["uncommitted", "pushable"]
+
+[root."~/work"]
+depth = "auto"
+no_untracked = true
+"#,
+        )
+        .unwrap();
+        assert_eq!(config.roots.len(), 2);
+        assert_eq!(config.depth, Some(Depth::Levels(2)));
+        assert_eq!(config.tab_style.as_deref(), Some("ascii"));
+        assert_eq!(
+            config.root_settings["~/work"],
+            RootConfig {
+                depth: Some(Depth::Auto),
+                no_untracked: Some(true),
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_keys() {
+        assert!(toml::from_str::<Config>("dpeth = 3").is_err());
+        assert!(toml::from_str::<Config>("[root.\"~/src\"]\ntab_style = \"ascii\"").is_err());
+    }
+
+    #[test]
+    fn missing_implicit_config_is_empty_but_explicit_is_an_error() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut location = ConfigLocation {
+            path: dir.path().join("config.toml"),
+            explicit: false,
+        };
+        assert_eq!(load_config(&location).unwrap(), Config::default());
+        location.explicit = true;
+        assert!(matches!(
+            load_config(&location),
+            Err(ConfigError::Read { .. })
+        ));
+    }
+}
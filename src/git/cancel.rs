@@ -0,0 +1,46 @@
+//! Process-wide cancellation of running git commands, e.g. on Ctrl-C.
+
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+static CANCELLED: AtomicBool = AtomicBool::new(false);
+static RUNNING: AtomicUsize = AtomicUsize::new(0);
+
+/// How often a waiting runner checks for cancellation.
+pub(crate) const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Ask every running git command to be killed and refuse to start new ones, then wait up to
+/// `grace` for the running commands to exit.
+///
+/// Cancellation is permanent for the life of the process; callers are expected to exit soon
+/// after.
+pub fn cancel_git_commands(grace: Duration) {
+    CANCELLED.store(true, Ordering::SeqCst);
+    let deadline = Instant::now() + grace;
+    while RUNNING.load(Ordering::SeqCst) > 0 && Instant::now() < deadline {
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// Whether [`cancel_git_commands`] has been called.
+#[must_use]
+pub fn git_cancelled() -> bool {
+    CANCELLED.load(Ordering::SeqCst)
+}
+
+/// Counts a spawned git process as running until dropped.
+pub(crate) struct Running(());
+
+impl Running {
+    pub(crate) fn start() -> Self {
+        RUNNING.fetch_add(1, Ordering::SeqCst);
+        Running(())
+    }
+}
+
+impl Drop for Running {
+    fn drop(&mut self) {
+        RUNNING.fetch_sub(1, Ordering::SeqCst);
+    }
+}
@@ -0,0 +1,400 @@
+//! Capture and replay of git exchanges, for reproducible bug reports and regression tests.
+//!
+//! [`RecordingGitRunner`] wraps any runner and keeps every `(repo, args)` invocation with its
+//! exit status and output; [`GitFixture::save`] writes them as pretty JSON. A
+//! [`ReplayGitRunner`] later answers the same invocations from the file without running git.
+//! Output that is not UTF-8 is stored lossily.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{ExitStatus, Output};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use super::{GitRunner, exit_status};
+
+/// Fixture format written by this version.
+const FIXTURE_VERSION: u32 = 1;
+
+/// Every git invocation of one run, in the order they finished.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GitFixture {
+    pub version: u32,
+    pub exchanges: Vec<Exchange>,
+}
+
+/// One git invocation and what it returned.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Exchange {
+    pub repo: PathBuf,
+    pub args: Vec<String>,
+    /// Exit code, or `None` when git was killed by a signal or could not be run.
+    #[serde(default)]
+    pub status: Option<i32>,
+    #[serde(default)]
+    pub stdout: String,
+    #[serde(default)]
+    pub stderr: String,
+    /// Set when the runner returned an error instead of git's output.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<RecordedError>,
+}
+
+/// An error a runner returned, e.g. a timeout or a failed spawn.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedError {
+    /// `timed_out`, `interrupted`, `not_found` or `other`.
+    pub kind: String,
+    pub message: String,
+}
+
+impl Exchange {
+    fn new(repo: &Path, args: &[&str], result: &io::Result<Output>) -> Self {
+        let mut exchange = Exchange {
+            repo: repo.to_path_buf(),
+            args: args.iter().map(|arg| (*arg).to_string()).collect(),
+            status: None,
+            stdout: String::new(),
+            stderr: String::new(),
+            error: None,
+        };
+        match result {
+            Ok(out) => {
+                exchange.status = out.status.code();
+                exchange.stdout = String::from_utf8_lossy(&out.stdout).into_owned();
+                exchange.stderr = String::from_utf8_lossy(&out.stderr).into_owned();
+            }
+            Err(err) => {
+                let kind = match err.kind() {
+                    io::ErrorKind::TimedOut => "timed_out",
+                    io::ErrorKind::Interrupted => "interrupted",
+                    io::ErrorKind::NotFound => "not_found",
+                    _ => "other",
+                };
+                exchange.error = Some(RecordedError {
+                    kind: kind.to_string(),
+                    message: err.to_string(),
+                });
+            }
+        }
+        exchange
+    }
+
+    fn replay(&self) -> io::Result<Output> {
+        if let Some(error) = &self.error {
+            let kind = match error.kind.as_str() {
+                "timed_out" => io::ErrorKind::TimedOut,
+                "interrupted" => io::ErrorKind::Interrupted,
+                "not_found" => io::ErrorKind::NotFound,
+                _ => io::ErrorKind::Other,
+            };
+            return Err(io::Error::new(kind, error.message.clone()));
+        }
+        Ok(Output {
+            status: self.status.map_or_else(killed_status, exit_status),
+            stdout: self.stdout.clone().into_bytes(),
+            stderr: self.stderr.clone().into_bytes(),
+        })
+    }
+}
+
+#[cfg(unix)]
+fn killed_status() -> ExitStatus {
+    use std::os::unix::process::ExitStatusExt as _;
+    // SIGKILL, so `code()` is `None` as it was when recording.
+    ExitStatus::from_raw(9)
+}
+
+#[cfg(windows)]
+fn killed_status() -> ExitStatus {
+    exit_status(1)
+}
+
+impl GitFixture {
+    /// Read a fixture written by [`GitFixture::save`].
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be read, is not a fixture, or was written by a
+    /// newer version.
+    pub fn load(path: &Path) -> Result<Self, FixtureError> {
+        let text = fs::read_to_string(path).map_err(|source| FixtureError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let fixture: GitFixture =
+            serde_json::from_str(&text).map_err(|source| FixtureError::Parse {
+                path: path.to_path_buf(),
+                source,
+            })?;
+        if fixture.version > FIXTURE_VERSION {
+            return Err(FixtureError::Version {
+                path: path.to_path_buf(),
+                version: fixture.version,
+            });
+        }
+        Ok(fixture)
+    }
+
+    /// Write the fixture as pretty JSON, creating its directory when needed.
+    ///
+    /// # Errors
+    /// Returns an error if the directory or file cannot be written.
+    pub fn save(&self, path: &Path) -> Result<(), FixtureError> {
+        let io_err = |source| FixtureError::Io {
+            path: path.to_path_buf(),
+            source,
+        };
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir).map_err(io_err)?;
+        }
+        let mut text = serde_json::to_string_pretty(self).map_err(|e| io_err(e.into()))?;
+        text.push('\n');
+        fs::write(path, text).map_err(io_err)
+    }
+
+    /// Distinct repository directories the exchanges ran in, in first-use order.
+    #[must_use]
+    pub fn repos(&self) -> Vec<&Path> {
+        let mut repos: Vec<&Path> = Vec::new();
+        for exchange in &self.exchanges {
+            if !repos.contains(&exchange.repo.as_path()) {
+                repos.push(&exchange.repo);
+            }
+        }
+        repos
+    }
+}
+
+#[derive(Debug)]
+pub enum FixtureError {
+    Io {
+        path: PathBuf,
+        source: io::Error,
+    },
+    Parse {
+        path: PathBuf,
+        source: serde_json::Error,
+    },
+    /// The fixture was written by a newer, incompatible version.
+    Version {
+        path: PathBuf,
+        version: u32,
+    },
+}
+
+impl std::fmt::Display for FixtureError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FixtureError::Io { path, source } => {
+                write!(
+                    f,
+                    "failed to access git fixture {}: {source}",
+                    path.display()
+                )
+            }
+            FixtureError::Parse { path, source } => {
+                write!(f, "invalid git fixture {}: {source}", path.display())
+            }
+            FixtureError::Version { path, version } => write!(
+                f,
+                "git fixture {} has version {version}; this build reads up to {FIXTURE_VERSION}",
+                path.display()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for FixtureError {}
+
+/// Runs git through another runner and records every exchange.
+pub struct RecordingGitRunner {
+    inner: Box<dyn GitRunner>,
+    exchanges: Mutex<Vec<Exchange>>,
+}
+
+impl RecordingGitRunner {
+    #[must_use]
+    pub fn new(inner: Box<dyn GitRunner>) -> Self {
+        Self {
+            inner,
+            exchanges: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// The exchanges recorded so far.
+    #[must_use]
+    pub fn fixture(&self) -> GitFixture {
+        let exchanges = self.exchanges.lock().map_or_else(
+            |poisoned| poisoned.into_inner().clone(),
+            |exchanges| exchanges.clone(),
+        );
+        GitFixture {
+            version: FIXTURE_VERSION,
+            exchanges,
+        }
+    }
+
+    fn record(&self, repo: &Path, args: &[&str], result: io::Result<Output>) -> io::Result<Output> {
+        let exchange = Exchange::new(repo, args, &result);
+        match self.exchanges.lock() {
+            Ok(mut exchanges) => exchanges.push(exchange),
+            Err(poisoned) => poisoned.into_inner().push(exchange),
+        }
+        result
+    }
+}
+
+impl GitRunner for RecordingGitRunner {
+    fn run_git(&self, repo: &Path, args: &[&str]) -> io::Result<Output> {
+        self.record(repo, args, self.inner.run_git(repo, args))
+    }
+
+    fn run_git_timeout(&self, repo: &Path, args: &[&str], timeout: Duration) -> io::Result<Output> {
+        self.record(repo, args, self.inner.run_git_timeout(repo, args, timeout))
+    }
+}
+
+/// Answers git invocations from a [`GitFixture`] instead of running git.
+///
+/// Repeated invocations get their recorded answers in order, then the last one again. An
+/// invocation that was never recorded fails with [`io::ErrorKind::NotFound`].
+pub struct ReplayGitRunner {
+    answers: HashMap<(PathBuf, Vec<String>), Vec<Exchange>>,
+    served: Mutex<HashMap<(PathBuf, Vec<String>), usize>>,
+    relocations: Vec<(PathBuf, PathBuf)>,
+}
+
+impl ReplayGitRunner {
+    #[must_use]
+    pub fn new(fixture: GitFixture) -> Self {
+        let mut answers: HashMap<_, Vec<Exchange>> = HashMap::new();
+        for exchange in fixture.exchanges {
+            answers
+                .entry((exchange.repo.clone(), exchange.args.clone()))
+                .or_default()
+                .push(exchange);
+        }
+        Self {
+            answers,
+            served: Mutex::new(HashMap::new()),
+            relocations: Vec::new(),
+        }
+    }
+
+    /// Serve repositories under `to` with the exchanges recorded under `from`, e.g. when a
+    /// test recreates a captured tree in a temporary directory.
+    #[must_use]
+    pub fn relocate(mut self, from: &Path, to: &Path) -> Self {
+        self.relocations
+            .push((from.to_path_buf(), to.to_path_buf()));
+        self
+    }
+
+    fn recorded_path(&self, repo: &Path) -> PathBuf {
+        self.relocations
+            .iter()
+            .find_map(|(from, to)| repo.strip_prefix(to).ok().map(|rest| from.join(rest)))
+            .unwrap_or_else(|| repo.to_path_buf())
+    }
+}
+
+impl GitRunner for ReplayGitRunner {
+    fn run_git(&self, repo: &Path, args: &[&str]) -> io::Result<Output> {
+        let key = (
+            self.recorded_path(repo),
+            args.iter()
+                .map(|arg| (*arg).to_string())
+                .collect::<Vec<_>>(),
+        );
+        let Some(answers) = self.answers.get(&key) else {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!(
+                    "no recorded exchange for `git {}` in {}",
+                    args.join(" "),
+                    key.0.display()
+                ),
+            ));
+        };
+        let index = match self.served.lock() {
+            Ok(mut served) => next_index(&mut served, key, answers.len()),
+            Err(poisoned) => next_index(&mut poisoned.into_inner(), key, answers.len()),
+        };
+        answers[index].replay()
+    }
+}
+
+fn next_index(
+    served: &mut HashMap<(PathBuf, Vec<String>), usize>,
+    key: (PathBuf, Vec<String>),
+    len: usize,
+) -> usize {
+    let count = served.entry(key).or_default();
+    let index = (*count).min(len - 1);
+    *count += 1;
+    index
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Scripted;
+
+    impl GitRunner for Scripted {
+        fn run_git(&self, _repo: &Path, args: &[&str]) -> io::Result<Output> {
+            match args {
+                ["status"] => Ok(Output {
+                    status: exit_status(0),
+                    stdout: b"clean\n".to_vec(),
+                    stderr: Vec::new(),
+                }),
+                ["fetch"] => Err(io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    "timed out after 1s",
+                )),
+                _ => Ok(Output {
+                    status: exit_status(128),
+                    stdout: Vec::new(),
+                    stderr: b"fatal: nope\n".to_vec(),
+                }),
+            }
+        }
+    }
+
+    #[test]
+    fn replay_answers_what_was_recorded() -> Result<(), Box<dyn std::error::Error>> {
+        let recorder = RecordingGitRunner::new(Box::new(Scripted));
+        let repo = Path::new("/src/app");
+        recorder.run_git(repo, &["status"])?;
+        let _ = recorder.run_git(repo, &["fetch"]);
+        recorder.run_git(repo, &["log"])?;
+
+        let dir = tempfile::TempDir::new()?;
+        let path = dir.path().join("capture.json");
+        recorder.fixture().save(&path)?;
+        let fixture = GitFixture::load(&path)?;
+        assert_eq!(fixture.exchanges.len(), 3);
+        assert_eq!(fixture.repos(), [repo]);
+
+        let replay = ReplayGitRunner::new(fixture).relocate(repo, Path::new("/tmp/x/app"));
+        let moved = Path::new("/tmp/x/app");
+        let out = replay.run_git(moved, &["status"])?;
+        assert!(out.status.success());
+        assert_eq!(out.stdout, b"clean\n");
+        let Err(err) = replay.run_git(moved, &["fetch"]) else {
+            panic!("timeout not replayed");
+        };
+        assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+        assert_eq!(replay.run_git(moved, &["log"])?.status.code(), Some(128));
+        let Err(err) = replay.run_git(moved, &["diff"]) else {
+            panic!("unrecorded exchange answered");
+        };
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+        Ok(())
+    }
+}
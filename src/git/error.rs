@@ -0,0 +1,124 @@
+use std::fmt;
+use std::path::Path;
+use std::process::Output;
+use std::time::Duration;
+
+use super::GitRunner;
+
+/// Longest stderr excerpt kept for a failed command.
+const STDERR_EXCERPT_CHARS: usize = 240;
+
+/// A git invocation that could not be spawned or exited with an unexpected status.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct GitCommandError {
+    /// The command line, e.g. `git diff --quiet`.
+    pub(crate) command: String,
+    /// Exit code, or `None` when git could not be spawned or was killed by a signal.
+    pub(crate) status: Option<i32>,
+    /// First lines of stderr, or the spawn error.
+    pub(crate) stderr: String,
+    /// Set when the command was killed for exceeding its timeout.
+    pub(crate) timed_out: bool,
+}
+
+impl GitCommandError {
+    /// A command that succeeded but printed something other than what was asked for.
+    pub(crate) fn unexpected_output(args: &[&str], stdout: &[u8]) -> Self {
+        GitCommandError {
+            command: format!("git {}", args.join(" ")),
+            status: Some(0),
+            stderr: format!("unexpected output: {}", stderr_excerpt(stdout)),
+            timed_out: false,
+        }
+    }
+}
+
+impl fmt::Display for GitCommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.status {
+            Some(code) => write!(f, "`{}` exited with {code}", self.command)?,
+            None => write!(f, "`{}` failed", self.command)?,
+        }
+        if !self.stderr.is_empty() {
+            write!(f, ": {}", self.stderr)?;
+        }
+        Ok(())
+    }
+}
+
+/// Run `git args` in `repo`, treating any exit code other than 0 and `allowed` as an error.
+pub(crate) fn run_checked(
+    repo: &Path,
+    git: &dyn GitRunner,
+    args: &[&str],
+    allowed: &[i32],
+) -> Result<Output, GitCommandError> {
+    checked(args, git.run_git(repo, args), allowed)
+}
+
+/// Like [`run_checked`] with no allowed failures, giving up after `timeout`.
+pub(crate) fn run_checked_timeout(
+    repo: &Path,
+    git: &dyn GitRunner,
+    args: &[&str],
+    timeout: Duration,
+) -> Result<Output, GitCommandError> {
+    checked(args, git.run_git_timeout(repo, args, timeout), &[])
+}
+
+fn checked(
+    args: &[&str],
+    result: std::io::Result<Output>,
+    allowed: &[i32],
+) -> Result<Output, GitCommandError> {
+    let command = format!("git {}", args.join(" "));
+    let out = result.map_err(|err| GitCommandError {
+        command: command.clone(),
+        status: None,
+        stderr: err.to_string(),
+        timed_out: err.kind() == std::io::ErrorKind::TimedOut,
+    })?;
+    let code = out.status.code();
+    if out.status.success() || code.is_some_and(|code| allowed.contains(&code)) {
+        return Ok(out);
+    }
+    Err(GitCommandError {
+        command,
+        status: code,
+        stderr: stderr_excerpt(&out.stderr),
+        timed_out: false,
+    })
+}
+
+fn stderr_excerpt(stderr: &[u8]) -> String {
+    let text = String::from_utf8_lossy(stderr);
+    let joined = text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    if joined.chars().count() <= STDERR_EXCERPT_CHARS {
+        return joined;
+    }
+    let mut excerpt: String = joined.chars().take(STDERR_EXCERPT_CHARS).collect();
+    excerpt.push_str("...");
+    excerpt
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stderr_excerpt_joins_lines_and_truncates() {
+        assert_eq!(
+            stderr_excerpt(b"fatal: not a git repository\n\n  hint: check\n"),
+            "fatal: not a git repository hint: check"
+        );
+        let long = "x".repeat(STDERR_EXCERPT_CHARS + 10);
+        let excerpt = stderr_excerpt(long.as_bytes());
+        assert_eq!(excerpt.chars().count(), STDERR_EXCERPT_CHARS + 3);
+        assert!(excerpt.ends_with("..."));
+    }
+}
@@ -0,0 +1,67 @@
+use std::path::Path;
+use std::time::Duration;
+
+use super::GitRunner;
+use super::error::{GitCommandError, run_checked, run_checked_timeout};
+
+/// Remotes that at least one local branch of `repo` tracks, in first-seen order. Branches
+/// tracking another local branch (remote `.`) are left out.
+pub(crate) fn upstream_remotes(
+    repo: &Path,
+    git: &dyn GitRunner,
+) -> Result<Vec<String>, GitCommandError> {
+    let out = run_checked(
+        repo,
+        git,
+        &[
+            "for-each-ref",
+            "--format=%(upstream:remotename)",
+            "refs/heads",
+        ],
+        &[],
+    )?;
+    let mut remotes: Vec<String> = Vec::new();
+    for remote in String::from_utf8_lossy(&out.stdout).lines().map(str::trim) {
+        if !remote.is_empty() && remote != "." && !remotes.iter().any(|r| r == remote) {
+            remotes.push(remote.to_string());
+        }
+    }
+    Ok(remotes)
+}
+
+/// Fetch `remote` into `repo`, pruning deleted branches and skipping tags.
+pub(crate) fn fetch_remote(
+    repo: &Path,
+    git: &dyn GitRunner,
+    remote: &str,
+    timeout: Duration,
+) -> Result<(), GitCommandError> {
+    run_checked_timeout(
+        repo,
+        git,
+        &["fetch", "--prune", "--no-tags", remote],
+        timeout,
+    )
+    .map(drop)
+}
+
+/// Update the tracking refs of `remote` in `repo` from the tracking refs of `peer_remote` in
+/// the local repository `peer`, which was just fetched from the same URL.
+pub(crate) fn fetch_from_peer(
+    repo: &Path,
+    git: &dyn GitRunner,
+    remote: &str,
+    peer: &Path,
+    peer_remote: &str,
+    timeout: Duration,
+) -> Result<(), GitCommandError> {
+    let peer = peer.to_string_lossy();
+    let refspec = format!("+refs/remotes/{peer_remote}/*:refs/remotes/{remote}/*");
+    run_checked_timeout(
+        repo,
+        git,
+        &["fetch", "--prune", "--no-tags", &peer, &refspec],
+        timeout,
+    )
+    .map(drop)
+}
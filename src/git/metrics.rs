@@ -1,6 +1,7 @@
 use std::path::Path;
 
 use super::GitRunner;
+use super::error::{GitCommandError, run_checked};
 
 #[derive(Debug, Default, Clone, Copy)]
 pub(crate) struct ChangeMetrics {
@@ -9,86 +10,115 @@ pub(crate) struct ChangeMetrics {
     pub(crate) untracked: u64,
 }
 
+const UNTRACKED_FILES: &[&str] = &["ls-files", "--others", "--exclude-standard"];
+
+/// How diffs treat submodules.
+///
+/// By default submodules are ignored entirely. With `Moved`, a gitlink that points at a
+/// different commit counts as a change of the superproject, while edits inside the submodule
+/// are left to the submodule's own rows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SubmoduleDiff {
+    Ignore,
+    Moved,
+}
+
+impl SubmoduleDiff {
+    fn flag(self) -> &'static str {
+        match self {
+            SubmoduleDiff::Ignore => "--ignore-submodules",
+            SubmoduleDiff::Moved => "--ignore-submodules=dirty",
+        }
+    }
+}
+
 pub(crate) fn uncommitted_metrics(
     repo: &Path,
     include_untracked: bool,
+    submodules: SubmoduleDiff,
     git: &dyn GitRunner,
-) -> ChangeMetrics {
-    let mut metrics = ChangeMetrics::default();
-    if let Ok(out) = git.run_git(
+) -> Result<ChangeMetrics, GitCommandError> {
+    let out = run_checked(
         repo,
-        &["diff", "--numstat", "--ignore-submodules", "--", "."],
-    ) {
-        let text = String::from_utf8_lossy(&out.stdout);
-        let (lines, files) = parse_numstat(&text);
-        metrics.lines = lines;
-        metrics.files = files;
-    }
-    if include_untracked
-        && let Ok(out) = git.run_git(repo, &["ls-files", "--others", "--exclude-standard"])
-    {
-        metrics.untracked = count_lines(&String::from_utf8_lossy(&out.stdout));
-    }
-    metrics
+        git,
+        &["diff", "--numstat", submodules.flag(), "--", "."],
+        &[],
+    )?;
+    let (lines, files) = parse_numstat(&String::from_utf8_lossy(&out.stdout));
+    let untracked = if include_untracked {
+        untracked_count(repo, git)?
+    } else {
+        0
+    };
+    Ok(ChangeMetrics {
+        lines,
+        files,
+        untracked,
+    })
 }
 
-pub(crate) fn staged_metrics(repo: &Path, git: &dyn GitRunner) -> ChangeMetrics {
-    let mut metrics = ChangeMetrics::default();
-    if let Ok(out) = git.run_git(
+pub(crate) fn staged_metrics(
+    repo: &Path,
+    submodules: SubmoduleDiff,
+    git: &dyn GitRunner,
+) -> Result<ChangeMetrics, GitCommandError> {
+    let out = run_checked(
         repo,
+        git,
         &[
             "diff",
             "--cached",
             "--numstat",
-            "--ignore-submodules",
+            submodules.flag(),
             "--",
             ".",
         ],
-    ) {
-        let text = String::from_utf8_lossy(&out.stdout);
-        let (lines, files) = parse_numstat(&text);
-        metrics.lines = lines;
-        metrics.files = files;
-    }
-    if let Ok(out) = git.run_git(repo, &["ls-files", "--others", "--exclude-standard"]) {
-        metrics.untracked = count_lines(&String::from_utf8_lossy(&out.stdout));
-    }
-    metrics
+        &[],
+    )?;
+    let (lines, files) = parse_numstat(&String::from_utf8_lossy(&out.stdout));
+    Ok(ChangeMetrics {
+        lines,
+        files,
+        untracked: untracked_count(repo, git)?,
+    })
 }
 
-pub(crate) fn has_uncommitted(repo: &Path, include_untracked: bool, git: &dyn GitRunner) -> bool {
-    if let Ok(out) = git.run_git(repo, &["diff", "--quiet", "--ignore-submodules", "--", "."]) {
-        if !out.status.success() {
-            return true;
-        }
-    } else {
-        return false;
-    }
-
-    if include_untracked
-        && let Ok(out) = git.run_git(repo, &["ls-files", "--others", "--exclude-standard"])
-        && !String::from_utf8_lossy(&out.stdout).trim().is_empty()
-    {
-        return true;
+/// Whether the worktree differs from the index. `git diff --quiet` exits 1 for "yes".
+pub(crate) fn has_uncommitted(
+    repo: &Path,
+    include_untracked: bool,
+    submodules: SubmoduleDiff,
+    git: &dyn GitRunner,
+) -> Result<bool, GitCommandError> {
+    let out = run_checked(
+        repo,
+        git,
+        &["diff", "--quiet", submodules.flag(), "--", "."],
+        &[1],
+    )?;
+    if !out.status.success() {
+        return Ok(true);
     }
-    false
+    Ok(include_untracked && untracked_count(repo, git)? > 0)
 }
 
-pub(crate) fn has_staged(repo: &Path, git: &dyn GitRunner) -> bool {
-    if let Ok(out) = git.run_git(
+pub(crate) fn has_staged(
+    repo: &Path,
+    submodules: SubmoduleDiff,
+    git: &dyn GitRunner,
+) -> Result<bool, GitCommandError> {
+    let out = run_checked(
         repo,
-        &[
-            "diff",
-            "--cached",
-            "--quiet",
-            "--ignore-submodules",
-            "--",
-            ".",
-        ],
-    ) {
-        return !out.status.success();
-    }
-    false
+        git,
+        &["diff", "--cached", "--quiet", submodules.flag(), "--", "."],
+        &[1],
+    )?;
+    Ok(!out.status.success())
+}
+
+fn untracked_count(repo: &Path, git: &dyn GitRunner) -> Result<u64, GitCommandError> {
+    let out = run_checked(repo, git, UNTRACKED_FILES, &[])?;
+    Ok(count_lines(&String::from_utf8_lossy(&out.stdout)))
 }
 
 fn parse_numstat(s: &str) -> (u64, u64) {
@@ -1,11 +1,35 @@
+mod cancel;
+mod capture;
+mod error;
+mod fetch;
 mod metrics;
+#[cfg(feature = "native")]
+mod native;
 mod refs;
 mod runner;
+mod stash;
+mod submodule;
+mod worktree;
 
-pub use runner::{DefaultGitRunner, GitRunner};
+pub use cancel::{cancel_git_commands, git_cancelled};
+pub use capture::{
+    Exchange, FixtureError, GitFixture, RecordedError, RecordingGitRunner, ReplayGitRunner,
+};
+#[cfg(feature = "native")]
+pub use native::NativeGitRunner;
+pub use runner::{DefaultGitRunner, GitRunner, TimeoutGitRunner};
+
+pub(crate) use runner::exit_status;
 
-pub(crate) use metrics::{has_staged, has_uncommitted, staged_metrics, uncommitted_metrics};
+pub(crate) use error::{GitCommandError, run_checked};
+pub(crate) use fetch::{fetch_from_peer, fetch_remote, upstream_remotes};
+pub(crate) use metrics::{
+    SubmoduleDiff, has_staged, has_uncommitted, staged_metrics, uncommitted_metrics,
+};
 pub(crate) use refs::{
-    ahead_count_for_ref_pair, commit_age_bounds_for_ref_pair, current_branch, fetch_remote,
-    list_local_branches_with_upstream, upstream_remote_url,
+    ahead_count_for_ref_pair, commit_age_bounds, commit_age_bounds_for_ref_pair, commit_count,
+    current_branch, default_base_ref, list_local_branches, remote_url, upstream_remote_url,
 };
+pub(crate) use stash::list_stashes;
+pub(crate) use submodule::{Submodule, list_submodules, unpushed_commit_count};
+pub(crate) use worktree::{LinkedWorktree, list_linked_worktrees};
@@ -0,0 +1,205 @@
+use std::fmt::Write as _;
+use std::path::Path;
+use std::process::Output;
+
+use gix::bstr::{BStr, ByteSlice as _};
+use gix::diff::blob::{Algorithm, diff as diff_lines, intern::InternedInput, sink::Counter};
+use gix::status::index_worktree::Item;
+use gix::status::plumbing::index_as_worktree::{Change, EntryStatus};
+
+use super::{NativeResult, exit_code, success};
+
+/// How many leading bytes `git` inspects for NUL when deciding a blob is binary.
+const BINARY_SNIFF_LEN: usize = 8000;
+
+/// The supported `git diff [--cached] (--numstat|--quiet) --ignore-submodules -- .` variants.
+pub(super) struct DiffRequest {
+    cached: bool,
+    quiet: bool,
+}
+
+impl DiffRequest {
+    pub(super) fn parse(args: &[&str]) -> Option<Self> {
+        let (cached, rest) = match args {
+            ["--cached", rest @ ..] => (true, rest),
+            rest => (false, rest),
+        };
+        let quiet = match rest {
+            ["--numstat", "--ignore-submodules", "--", "."] => false,
+            ["--quiet", "--ignore-submodules", "--", "."] => true,
+            _ => return None,
+        };
+        Some(Self { cached, quiet })
+    }
+}
+
+pub(super) fn diff(repo: &gix::Repository, request: &DiffRequest) -> NativeResult<Output> {
+    let changes = if request.cached {
+        staged_changes(repo)?
+    } else {
+        worktree_changes(repo)?
+    };
+    if request.quiet {
+        return Ok(exit_code(i32::from(!changes.is_empty())));
+    }
+    let mut stdout = String::new();
+    for change in &changes {
+        match change.lines {
+            Some((added, removed)) => {
+                let _ = writeln!(stdout, "{added}\t{removed}\t{}", change.path);
+            }
+            None => {
+                let _ = writeln!(stdout, "-\t-\t{}", change.path);
+            }
+        }
+    }
+    Ok(success(stdout))
+}
+
+/// Equivalent of `git ls-files --others --exclude-standard`.
+pub(super) fn untracked(repo: &gix::Repository) -> NativeResult<Output> {
+    let iter = repo
+        .status(gix::progress::Discard)?
+        .index_worktree_submodules(None)
+        .untracked_files(gix::status::UntrackedFiles::Files)
+        .into_index_worktree_iter(Vec::new())?;
+    let mut paths = Vec::new();
+    for item in iter {
+        if let Item::DirectoryContents { entry, .. } = item?
+            && entry.status == gix::dir::entry::Status::Untracked
+        {
+            paths.push(entry.rela_path.to_string());
+        }
+    }
+    paths.sort();
+    Ok(success(paths.into_iter().map(|path| path + "\n").collect()))
+}
+
+/// A changed path with its added/removed line counts, or `None` for binary content.
+struct FileChange {
+    path: String,
+    lines: Option<(u32, u32)>,
+}
+
+impl FileChange {
+    fn new(path: &BStr, old: &[u8], new: &[u8]) -> Self {
+        Self {
+            path: path.to_string(),
+            lines: count_lines(old, new),
+        }
+    }
+}
+
+fn worktree_changes(repo: &gix::Repository) -> NativeResult<Vec<FileChange>> {
+    let workdir = repo
+        .workdir()
+        .ok_or("this operation must be run in a work tree")?
+        .to_path_buf();
+    let iter = repo
+        .status(gix::progress::Discard)?
+        .index_worktree_submodules(None)
+        .untracked_files(gix::status::UntrackedFiles::None)
+        .into_index_worktree_iter(Vec::new())?;
+    let mut changes = Vec::new();
+    for item in iter {
+        let Item::Modification {
+            entry,
+            rela_path,
+            status,
+            ..
+        } = item?
+        else {
+            continue;
+        };
+        if entry.mode == gix::index::entry::Mode::COMMIT {
+            continue;
+        }
+        let old = blob_data(repo, &entry.id)?;
+        let new = match status {
+            EntryStatus::Change(Change::Removed) => Vec::new(),
+            EntryStatus::Change(Change::SubmoduleModification(_))
+            | EntryStatus::Conflict { .. }
+            | EntryStatus::NeedsUpdate(_) => continue,
+            EntryStatus::Change(_) | EntryStatus::IntentToAdd => {
+                read_worktree_file(&workdir.join(gix::path::from_bstr(rela_path.as_bstr())))?
+            }
+        };
+        if old == new {
+            continue;
+        }
+        changes.push(FileChange::new(rela_path.as_bstr(), &old, &new));
+    }
+    changes.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(changes)
+}
+
+fn staged_changes(repo: &gix::Repository) -> NativeResult<Vec<FileChange>> {
+    let tree_id = repo.head_tree_id_or_empty()?;
+    let index = repo.index_or_empty()?;
+    let mut changes = Vec::new();
+    let mut failure = None;
+    repo.tree_index_status(
+        &tree_id,
+        &index,
+        None,
+        gix::status::tree_index::TrackRenames::AsConfigured,
+        |change, _, _| {
+            use gix::diff::index::{Action, ChangeRef};
+            if change.entry_mode() == gix::index::entry::Mode::COMMIT {
+                return Ok::<_, std::convert::Infallible>(Action::Continue);
+            }
+            let (old, new) = match &change {
+                ChangeRef::Addition { id, .. } => (Ok(Vec::new()), blob_data(repo, id)),
+                ChangeRef::Deletion { id, .. } => (blob_data(repo, id), Ok(Vec::new())),
+                ChangeRef::Modification {
+                    previous_id, id, ..
+                } => (blob_data(repo, previous_id), blob_data(repo, id)),
+                ChangeRef::Rewrite { source_id, id, .. } => {
+                    (blob_data(repo, source_id), blob_data(repo, id))
+                }
+            };
+            match old.and_then(|old| new.map(|new| (old, new))) {
+                Ok((old, new)) => {
+                    changes.push(FileChange::new(change.location(), &old, &new));
+                    Ok(Action::Continue)
+                }
+                Err(err) => {
+                    failure = Some(err);
+                    Ok(Action::Cancel)
+                }
+            }
+        },
+    )?;
+    if let Some(err) = failure {
+        return Err(err);
+    }
+    changes.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(changes)
+}
+
+fn blob_data(repo: &gix::Repository, id: &gix::oid) -> NativeResult<Vec<u8>> {
+    Ok(repo.find_blob(id)?.take_data())
+}
+
+/// Read a worktree path the way git hashes it: symlinks contribute their target.
+fn read_worktree_file(path: &Path) -> NativeResult<Vec<u8>> {
+    let meta = std::fs::symlink_metadata(path)?;
+    if meta.file_type().is_symlink() {
+        let target = std::fs::read_link(path)?;
+        return Ok(gix::path::into_bstr(target).into_owned().into());
+    }
+    Ok(std::fs::read(path)?)
+}
+
+fn count_lines(old: &[u8], new: &[u8]) -> Option<(u32, u32)> {
+    if is_binary(old) || is_binary(new) {
+        return None;
+    }
+    let input = InternedInput::new(old, new);
+    let counter = diff_lines(Algorithm::Myers, &input, Counter::default());
+    Some((counter.insertions, counter.removals))
+}
+
+fn is_binary(data: &[u8]) -> bool {
+    data[..data.len().min(BINARY_SNIFF_LEN)].contains(&0)
+}
@@ -0,0 +1,45 @@
+use std::fmt::Write as _;
+use std::process::Output;
+
+use super::{NativeResult, success};
+
+pub(super) fn rev_list_count(repo: &gix::Repository, range: &str) -> NativeResult<Output> {
+    let mut count = 0u64;
+    for info in walk(repo, range)? {
+        info?;
+        count = count.saturating_add(1);
+    }
+    Ok(success(format!("{count}\n")))
+}
+
+/// Committer timestamps (`%ct`) of every commit in `range`.
+pub(super) fn log_commit_times(repo: &gix::Repository, range: &str) -> NativeResult<Output> {
+    let mut stdout = String::new();
+    for info in walk(repo, range)? {
+        let commit = repo.find_commit(info?.id)?;
+        let _ = writeln!(stdout, "{}", commit.time()?.seconds);
+    }
+    Ok(success(stdout))
+}
+
+/// Walk `tip` or the `hidden..tip` range the same way `git rev-list` would.
+fn walk<'repo>(
+    repo: &'repo gix::Repository,
+    range: &str,
+) -> NativeResult<gix::revision::Walk<'repo>> {
+    let (hidden, tip) = match range.split_once("..") {
+        Some((hidden, tip)) => (Some(hidden), tip),
+        None => (None, range),
+    };
+    let tip = commit_id(repo, tip)?;
+    let mut platform = repo.rev_walk([tip]);
+    if let Some(hidden) = hidden {
+        platform = platform.with_hidden([commit_id(repo, hidden)?]);
+    }
+    Ok(platform.all()?)
+}
+
+fn commit_id(repo: &gix::Repository, spec: &str) -> NativeResult<gix::ObjectId> {
+    let spec = if spec.is_empty() { "HEAD" } else { spec };
+    Ok(repo.rev_parse_single(spec)?.object()?.peel_to_commit()?.id)
+}
@@ -0,0 +1,159 @@
+//! In-process git backend built on gitoxide, enabled with the `native` cargo feature.
+//!
+//! [`NativeGitRunner`] answers the queries the report issues (current branch, upstreams, ahead
+//! counts, commit timestamps, diff numstat and untracked listings) without spawning `git`. The
+//! answers are rendered in the same textual form `git` prints so every parser in
+//! [`crate::git`] works unchanged; commands it does not understand fall back to the `git`
+//! binary.
+
+mod diff;
+mod history;
+mod refs;
+
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use std::process::Output;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use super::{DefaultGitRunner, GitRunner, exit_status, git_cancelled};
+
+type NativeResult<T> = Result<T, Box<dyn std::error::Error + Send + Sync>>;
+
+pub struct NativeGitRunner {
+    fallback: DefaultGitRunner,
+    repos: Mutex<HashMap<PathBuf, gix::ThreadSafeRepository>>,
+}
+
+impl NativeGitRunner {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            fallback: DefaultGitRunner,
+            repos: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn open(&self, repo: &Path) -> NativeResult<gix::Repository> {
+        let mut repos = self
+            .repos
+            .lock()
+            .map_err(|_| "native repository cache poisoned")?;
+        if let Some(found) = repos.get(repo) {
+            return Ok(found.to_thread_local());
+        }
+        let opened = gix::discover(repo)?.into_sync();
+        let local = opened.to_thread_local();
+        repos.insert(repo.to_path_buf(), opened);
+        Ok(local)
+    }
+}
+
+impl Default for NativeGitRunner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GitRunner for NativeGitRunner {
+    fn run_git(&self, repo: &Path, args: &[&str]) -> std::io::Result<Output> {
+        let Some(command) = Command::parse(args) else {
+            return self.fallback.run_git(repo, args);
+        };
+        if git_cancelled() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::Interrupted,
+                "cancelled",
+            ));
+        }
+        let output = self
+            .open(repo)
+            .and_then(|repository| command.run(&repository))
+            .unwrap_or_else(|err| failure(&err));
+        Ok(output)
+    }
+
+    /// Only commands handed to the `git` binary honour the timeout; queries answered in
+    /// process run to completion.
+    fn run_git_timeout(
+        &self,
+        repo: &Path,
+        args: &[&str],
+        timeout: Duration,
+    ) -> std::io::Result<Output> {
+        if Command::parse(args).is_none() {
+            return self.fallback.run_git_timeout(repo, args, timeout);
+        }
+        self.run_git(repo, args)
+    }
+}
+
+/// The subset of git invocations the native backend can answer itself.
+enum Command<'a> {
+    LocalBranches,
+    Verify(&'a str),
+    SymbolicRefShort(&'a str),
+    ConfigGet(&'a str),
+    RevListCount(&'a str),
+    LogCommitTimes(&'a str),
+    Diff(diff::DiffRequest),
+    Untracked,
+}
+
+impl<'a> Command<'a> {
+    fn parse(args: &[&'a str]) -> Option<Self> {
+        Some(match args {
+            [
+                "for-each-ref",
+                "--format=%(refname:short) %(upstream:short)",
+                "refs/heads",
+            ] => Command::LocalBranches,
+            ["rev-parse", "--verify", "--quiet", name] => Command::Verify(name),
+            ["symbolic-ref", "--quiet", "--short", name] => Command::SymbolicRefShort(name),
+            ["config", "--get", key] => Command::ConfigGet(key),
+            ["rev-list", "--count", range] => Command::RevListCount(range),
+            ["log", "--format=%ct", range] => Command::LogCommitTimes(range),
+            ["diff", rest @ ..] => Command::Diff(diff::DiffRequest::parse(rest)?),
+            ["ls-files", "--others", "--exclude-standard"] => Command::Untracked,
+            _ => return None,
+        })
+    }
+
+    fn run(&self, repo: &gix::Repository) -> NativeResult<Output> {
+        match self {
+            Command::LocalBranches => refs::local_branches(repo),
+            Command::Verify(name) => Ok(refs::verify(repo, name)),
+            Command::SymbolicRefShort(name) => refs::symbolic_ref_short(repo, name),
+            Command::ConfigGet(key) => Ok(refs::config_get(repo, key)),
+            Command::RevListCount(range) => history::rev_list_count(repo, range),
+            Command::LogCommitTimes(range) => history::log_commit_times(repo, range),
+            Command::Diff(request) => diff::diff(repo, request),
+            Command::Untracked => diff::untracked(repo),
+        }
+    }
+}
+
+fn success(stdout: String) -> Output {
+    Output {
+        status: exit_status(0),
+        stdout: stdout.into_bytes(),
+        stderr: Vec::new(),
+    }
+}
+
+fn exit_code(code: i32) -> Output {
+    Output {
+        status: exit_status(code),
+        stdout: Vec::new(),
+        stderr: Vec::new(),
+    }
+}
+
+fn failure(err: &dyn Display) -> Output {
+    Output {
+        status: exit_status(128),
+        stdout: Vec::new(),
+        stderr: format!("fatal: {err}\n").into_bytes(),
+    }
+}
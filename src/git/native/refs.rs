@@ -0,0 +1,51 @@
+use std::process::Output;
+
+use gix::remote::Direction;
+
+use super::{NativeResult, exit_code, success};
+
+pub(super) fn local_branches(repo: &gix::Repository) -> NativeResult<Output> {
+    let references = repo.references()?;
+    let mut lines = Vec::new();
+    for reference in references.local_branches()? {
+        let reference = reference?;
+        let name = reference.name();
+        let upstream = upstream_short(repo, name).unwrap_or_default();
+        lines.push((
+            name.as_bstr().to_string(),
+            format!("{} {upstream}\n", name.shorten()),
+        ));
+    }
+    lines.sort();
+    Ok(success(lines.into_iter().map(|(_, line)| line).collect()))
+}
+
+pub(super) fn verify(repo: &gix::Repository, name: &str) -> Output {
+    match repo.rev_parse_single(name) {
+        Ok(id) => success(format!("{id}\n")),
+        Err(_) => exit_code(1),
+    }
+}
+
+pub(super) fn symbolic_ref_short(repo: &gix::Repository, name: &str) -> NativeResult<Output> {
+    let Some(reference) = repo.try_find_reference(name)? else {
+        return Ok(exit_code(1));
+    };
+    Ok(match reference.target().try_name() {
+        Some(target) => success(format!("{}\n", target.shorten())),
+        None => exit_code(1),
+    })
+}
+
+pub(super) fn config_get(repo: &gix::Repository, key: &str) -> Output {
+    match repo.config_snapshot().string(key) {
+        Some(value) => success(format!("{value}\n")),
+        None => exit_code(1),
+    }
+}
+
+fn upstream_short(repo: &gix::Repository, branch: &gix::refs::FullNameRef) -> Option<String> {
+    repo.branch_remote_tracking_ref_name(branch, Direction::Fetch)
+        .and_then(Result::ok)
+        .map(|name| name.shorten().to_string())
+}
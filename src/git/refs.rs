@@ -4,101 +4,141 @@ use std::time::{Duration, UNIX_EPOCH};
 use crate::system::Clock;
 
 use super::GitRunner;
+use super::error::{GitCommandError, run_checked};
 
-#[must_use]
-pub(crate) fn current_branch(repo: &Path, git: &dyn GitRunner) -> Option<String> {
-    let out = git
-        .run_git(repo, &["rev-parse", "--abbrev-ref", "HEAD"])
-        .ok()?;
-    if !out.status.success() {
-        return None;
-    }
-    let s = String::from_utf8_lossy(&out.stdout).trim().to_string();
-    if s.is_empty() { None } else { Some(s) }
+/// The checked-out branch, also when it has no commits yet, or `HEAD` when detached.
+pub(crate) fn current_branch(repo: &Path, git: &dyn GitRunner) -> Result<String, GitCommandError> {
+    // `symbolic-ref --quiet` exits 1 for a detached HEAD and fails loudly for anything else.
+    let out = run_checked(
+        repo,
+        git,
+        &["symbolic-ref", "--quiet", "--short", "HEAD"],
+        &[1],
+    )?;
+    let branch = stdout_line(&out.stdout);
+    Ok(if out.status.success() && !branch.is_empty() {
+        branch
+    } else {
+        "HEAD".to_string()
+    })
 }
 
-#[must_use]
-pub(crate) fn list_local_branches_with_upstream(
+/// List local branches paired with their configured upstream, if any.
+///
+/// An upstream that no longer exists, such as a remote branch deleted and then pruned, is
+/// reported as `None`: the branch's commits are compared against the base ref instead.
+pub(crate) fn list_local_branches(
     repo: &Path,
     git: &dyn GitRunner,
-) -> Vec<(String, String)> {
-    let out = git
-        .run_git(
-            repo,
-            &[
-                "for-each-ref",
-                "--format=%(refname:short) %(upstream:short)",
-                "refs/heads",
-            ],
-        )
-        .ok();
+) -> Result<Vec<(String, Option<String>)>, GitCommandError> {
+    let out = run_checked(
+        repo,
+        git,
+        &[
+            "for-each-ref",
+            "--format=%(refname:short) %(upstream:short)",
+            "refs/heads",
+        ],
+        &[],
+    )?;
     let mut branches = Vec::new();
-    if let Some(out) = out
-        && out.status.success()
+    let text = String::from_utf8_lossy(&out.stdout);
+    for line in text.lines() {
+        let mut parts = line.split_whitespace();
+        if let Some(branch) = parts.next() {
+            let upstream = match parts.next() {
+                Some(upstream) if ref_exists(repo, git, upstream)? => Some(upstream.to_string()),
+                _ => None,
+            };
+            branches.push((branch.to_string(), upstream));
+        }
+    }
+    Ok(branches)
+}
+
+/// Resolve the ref unpublished branches are compared against: the remote default branch
+/// (`origin/HEAD`) when known, otherwise the first existing local default branch.
+pub(crate) fn default_base_ref(
+    repo: &Path,
+    git: &dyn GitRunner,
+) -> Result<Option<String>, GitCommandError> {
+    let out = run_checked(
+        repo,
+        git,
+        &[
+            "symbolic-ref",
+            "--quiet",
+            "--short",
+            "refs/remotes/origin/HEAD",
+        ],
+        &[1],
+    )?;
+    let remote_head = stdout_line(&out.stdout);
+    if out.status.success() && !remote_head.is_empty() {
+        return Ok(Some(remote_head));
+    }
+
+    let configured = config_value(repo, git, "init.defaultBranch")?;
+    for name in configured
+        .into_iter()
+        .chain(["main".to_string(), "master".to_string()])
     {
-        let text = String::from_utf8_lossy(&out.stdout);
-        for line in text.lines() {
-            let mut parts = line.split_whitespace();
-            if let Some(branch) = parts.next()
-                && let Some(upstream) = parts.next()
-                && !upstream.trim().is_empty()
-            {
-                branches.push((branch.to_string(), upstream.to_string()));
-            }
+        if local_branch_exists(repo, git, &name)? {
+            return Ok(Some(name));
         }
     }
-    branches
+    Ok(None)
 }
 
-pub(crate) fn fetch_remote(repo: &Path, git: &dyn GitRunner, remote: &str) -> bool {
-    git.run_git(repo, &["fetch", "--prune", "--no-tags", remote])
-        .map(|o| o.status.success())
-        .unwrap_or(false)
+fn local_branch_exists(
+    repo: &Path,
+    git: &dyn GitRunner,
+    name: &str,
+) -> Result<bool, GitCommandError> {
+    ref_exists(repo, git, &format!("refs/heads/{name}"))
 }
 
-#[must_use]
-pub(crate) fn upstream_remote_url(repo: &Path, git: &dyn GitRunner) -> Option<String> {
-    if let Some(remote) = upstream_remote_name(repo, git)
-        && let Some(url) = remote_url(repo, git, &remote)
+fn ref_exists(repo: &Path, git: &dyn GitRunner, name: &str) -> Result<bool, GitCommandError> {
+    // `rev-parse --verify --quiet` exits 1 when the ref does not resolve.
+    let out = run_checked(repo, git, &["rev-parse", "--verify", "--quiet", name], &[1])?;
+    Ok(out.status.success())
+}
+
+/// URL of the remote `branch` tracks, falling back to `origin`.
+pub(crate) fn upstream_remote_url(
+    repo: &Path,
+    git: &dyn GitRunner,
+    branch: &str,
+) -> Result<Option<String>, GitCommandError> {
+    if let Some(remote) = config_value(repo, git, &format!("branch.{branch}.remote"))?
+        && let Some(url) = remote_url(repo, git, &remote)?
     {
-        return Some(url);
+        return Ok(Some(url));
     }
     remote_url(repo, git, "origin")
 }
 
-fn upstream_remote_name(repo: &Path, git: &dyn GitRunner) -> Option<String> {
-    let out = git
-        .run_git(
-            repo,
-            &["rev-parse", "--abbrev-ref", "--symbolic-full-name", "@{u}"],
-        )
-        .ok()?;
-    if !out.status.success() {
-        return None;
-    }
-    let upstream = String::from_utf8_lossy(&out.stdout).trim().to_string();
-    if upstream.is_empty() {
-        return None;
-    }
-    let (remote, _rest) = upstream.split_once('/')?;
+pub(crate) fn remote_url(
+    repo: &Path,
+    git: &dyn GitRunner,
+    remote: &str,
+) -> Result<Option<String>, GitCommandError> {
     if remote.is_empty() {
-        None
-    } else {
-        Some(remote.to_string())
+        return Ok(None);
     }
+    config_value(repo, git, &format!("remote.{remote}.url"))
 }
 
-fn remote_url(repo: &Path, git: &dyn GitRunner, remote: &str) -> Option<String> {
-    if remote.is_empty() {
-        return None;
-    }
-    let key = format!("remote.{remote}.url");
-    let out = git.run_git(repo, &["config", "--get", &key]).ok()?;
-    if !out.status.success() {
-        return None;
-    }
-    let url = String::from_utf8_lossy(&out.stdout).trim().to_string();
-    if url.is_empty() { None } else { Some(url) }
+/// The value of a config `key`, `None` when it is unset or empty.
+fn config_value(
+    repo: &Path,
+    git: &dyn GitRunner,
+    key: &str,
+) -> Result<Option<String>, GitCommandError> {
+    // `config --get` exits 1 when the key is not set.
+    let out = run_checked(repo, git, &["config", "--get", key], &[1])?;
+    let value = stdout_line(&out.stdout);
+    Ok((out.status.success() && !value.is_empty()).then_some(value))
 }
 
 pub(crate) fn ahead_count_for_ref_pair(
@@ -106,20 +146,21 @@ pub(crate) fn ahead_count_for_ref_pair(
     git: &dyn GitRunner,
     branch: &str,
     upstream: &str,
-) -> Option<u64> {
-    let count = git
-        .run_git(
-            repo,
-            &["rev-list", "--count", &format!("{upstream}..{branch}")],
-        )
-        .ok()?;
-    if !count.status.success() {
-        return None;
-    }
-    String::from_utf8_lossy(&count.stdout)
-        .trim()
-        .parse::<u64>()
-        .ok()
+) -> Result<u64, GitCommandError> {
+    commit_count(repo, git, &format!("{upstream}..{branch}"))
+}
+
+/// Count the commits selected by `range` (a revision or `a..b` range).
+pub(crate) fn commit_count(
+    repo: &Path,
+    git: &dyn GitRunner,
+    range: &str,
+) -> Result<u64, GitCommandError> {
+    let args = ["rev-list", "--count", range];
+    let out = run_checked(repo, git, &args, &[])?;
+    stdout_line(&out.stdout)
+        .parse()
+        .map_err(|_| GitCommandError::unexpected_output(&args, &out.stdout))
 }
 
 pub(crate) fn commit_age_bounds_for_ref_pair(
@@ -128,16 +169,18 @@ pub(crate) fn commit_age_bounds_for_ref_pair(
     clock: &dyn Clock,
     branch: &str,
     upstream: &str,
-) -> Option<(Option<Duration>, Option<Duration>)> {
-    let log = git
-        .run_git(
-            repo,
-            &["log", "--format=%ct", &format!("{upstream}..{branch}")],
-        )
-        .ok()?;
-    if !log.status.success() {
-        return None;
-    }
+) -> Result<(Option<Duration>, Option<Duration>), GitCommandError> {
+    commit_age_bounds(repo, git, clock, &format!("{upstream}..{branch}"))
+}
+
+/// Age of the oldest and newest commits selected by `range`, as `(earliest, latest)`.
+pub(crate) fn commit_age_bounds(
+    repo: &Path,
+    git: &dyn GitRunner,
+    clock: &dyn Clock,
+    range: &str,
+) -> Result<(Option<Duration>, Option<Duration>), GitCommandError> {
+    let log = run_checked(repo, git, &["log", "--format=%ct", range], &[])?;
     let now_secs = clock
         .now()
         .duration_since(UNIX_EPOCH)
@@ -158,5 +201,9 @@ pub(crate) fn commit_age_bounds_for_ref_pair(
             });
         }
     }
-    Some((max_age, min_age))
+    Ok((max_age, min_age))
+}
+
+fn stdout_line(stdout: &[u8]) -> String {
+    String::from_utf8_lossy(stdout).trim().to_string()
 }
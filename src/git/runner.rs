@@ -1,24 +1,230 @@
+use std::io::{self, Read};
 use std::path::Path;
-use std::process::{Command, Output, Stdio};
+use std::process::{Child, Command, ExitStatus, Output, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
 
-pub trait GitRunner {
+use wait_timeout::ChildExt;
+
+use super::cancel::{POLL_INTERVAL, Running, git_cancelled};
+
+pub trait GitRunner: Send + Sync {
     /// Run the `git` command within the given `repo` with `args`.
     ///
     /// # Errors
     /// Returns an error if the `git` process cannot be spawned or fails during execution.
     fn run_git(&self, repo: &Path, args: &[&str]) -> std::io::Result<Output>;
+
+    /// Like [`GitRunner::run_git`], but give up once `timeout` has elapsed.
+    ///
+    /// The default implementation ignores the timeout, which suits runners that never block
+    /// on the network.
+    ///
+    /// # Errors
+    /// Returns an error of kind [`io::ErrorKind::TimedOut`] when the command did not finish
+    /// in time, otherwise as [`GitRunner::run_git`].
+    fn run_git_timeout(
+        &self,
+        repo: &Path,
+        args: &[&str],
+        timeout: Duration,
+    ) -> std::io::Result<Output> {
+        let _ = timeout;
+        self.run_git(repo, args)
+    }
 }
 
+/// Runs the `git` binary non-interactively: stdin is closed and terminal and credential-manager
+/// prompts are disabled, so a command that wants input fails instead of waiting for it.
+///
+/// On Unix git runs in its own process group, so a timeout also kills the hooks, `ssh` and
+/// credential helpers it started. The group no longer receives the terminal's Ctrl-C; callers
+/// forward it with [`crate::cancel_git_commands`]. Calls without a timeout otherwise wait for
+/// git to finish.
 pub struct DefaultGitRunner;
 
-impl GitRunner for DefaultGitRunner {
-    fn run_git(&self, repo: &Path, args: &[&str]) -> std::io::Result<Output> {
-        Command::new("git")
+impl DefaultGitRunner {
+    fn command(repo: &Path, args: &[&str]) -> Command {
+        let mut command = Command::new("git");
+        command
             .arg("-C")
             .arg(repo)
             .args(args)
+            .env("GIT_TERMINAL_PROMPT", "0")
+            .env("GCM_INTERACTIVE", "never")
+            .stdin(Stdio::null())
             .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .output()
+            .stderr(Stdio::piped());
+        #[cfg(unix)]
+        std::os::unix::process::CommandExt::process_group(&mut command, 0);
+        command
+    }
+
+    /// Run git until it exits, `timeout` elapses or the run is cancelled. Output is drained on
+    /// helper threads so a chatty command cannot block on a full pipe while we wait.
+    fn run(repo: &Path, args: &[&str], timeout: Option<Duration>) -> std::io::Result<Output> {
+        if git_cancelled() {
+            return Err(cancelled());
+        }
+        let _running = Running::start();
+        let mut child = Self::command(repo, args).spawn()?;
+        let stdout = drain(child.stdout.take());
+        let stderr = drain(child.stderr.take());
+        let status = match wait(&mut child, timeout) {
+            Ok(status) => status,
+            Err(err) => {
+                kill(&mut child);
+                return Err(err);
+            }
+        };
+        Ok(Output {
+            status,
+            stdout: stdout.join().unwrap_or_default(),
+            stderr: stderr.join().unwrap_or_default(),
+        })
+    }
+}
+
+impl GitRunner for DefaultGitRunner {
+    fn run_git(&self, repo: &Path, args: &[&str]) -> std::io::Result<Output> {
+        Self::run(repo, args, None)
+    }
+
+    fn run_git_timeout(
+        &self,
+        repo: &Path,
+        args: &[&str],
+        timeout: Duration,
+    ) -> std::io::Result<Output> {
+        Self::run(repo, args, Some(timeout))
+    }
+}
+
+/// Applies a timeout to every command of the wrapped runner.
+///
+/// Calls that bring their own timeout, such as fetches, keep it instead.
+pub struct TimeoutGitRunner {
+    inner: Box<dyn GitRunner>,
+    timeout: Duration,
+}
+
+impl TimeoutGitRunner {
+    #[must_use]
+    pub fn new(inner: Box<dyn GitRunner>, timeout: Duration) -> Self {
+        Self { inner, timeout }
+    }
+}
+
+impl GitRunner for TimeoutGitRunner {
+    fn run_git(&self, repo: &Path, args: &[&str]) -> std::io::Result<Output> {
+        self.inner.run_git_timeout(repo, args, self.timeout)
+    }
+
+    fn run_git_timeout(
+        &self,
+        repo: &Path,
+        args: &[&str],
+        timeout: Duration,
+    ) -> std::io::Result<Output> {
+        self.inner.run_git_timeout(repo, args, timeout)
+    }
+}
+
+/// Wait in short slices so cancellation is noticed while git is still running.
+fn wait(child: &mut Child, timeout: Option<Duration>) -> std::io::Result<ExitStatus> {
+    let started = Instant::now();
+    loop {
+        if git_cancelled() {
+            return Err(cancelled());
+        }
+        let slice = match timeout {
+            Some(timeout) => {
+                let left = timeout.saturating_sub(started.elapsed());
+                if left.is_zero() {
+                    return Err(io::Error::new(
+                        io::ErrorKind::TimedOut,
+                        format!("timed out after {}s", timeout.as_secs_f64()),
+                    ));
+                }
+                left.min(POLL_INTERVAL)
+            }
+            None => POLL_INTERVAL,
+        };
+        if let Some(status) = child.wait_timeout(slice)? {
+            return Ok(status);
+        }
+    }
+}
+
+/// The status of a process that exited with `code`.
+#[cfg(unix)]
+pub(crate) fn exit_status(code: i32) -> ExitStatus {
+    use std::os::unix::process::ExitStatusExt as _;
+    ExitStatus::from_raw(code << 8)
+}
+
+#[cfg(windows)]
+pub(crate) fn exit_status(code: i32) -> ExitStatus {
+    use std::os::windows::process::ExitStatusExt as _;
+    ExitStatus::from_raw(code.cast_unsigned())
+}
+
+fn cancelled() -> io::Error {
+    io::Error::new(io::ErrorKind::Interrupted, "cancelled")
+}
+
+fn drain(pipe: Option<impl Read + Send + 'static>) -> thread::JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut buf = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut buf);
+        }
+        buf
+    })
+}
+
+fn kill(child: &mut Child) {
+    #[cfg(unix)]
+    if let Ok(pid) = i32::try_from(child.id()) {
+        use nix::sys::signal::{Signal, killpg};
+        let _ = killpg(nix::unistd::Pid::from_raw(pid), Signal::SIGKILL);
+    }
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn alias(command: &str) -> String {
+        format!("alias.probe=!{command}")
+    }
+
+    #[test]
+    fn timeout_kills_a_hung_command() -> std::io::Result<()> {
+        let dir = tempfile::TempDir::new()?;
+        let started = Instant::now();
+        let Err(err) = DefaultGitRunner.run_git_timeout(
+            dir.path(),
+            &["-c", &alias("sleep 5"), "probe"],
+            Duration::from_millis(200),
+        ) else {
+            panic!("hung command finished");
+        };
+        assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+        assert!(started.elapsed() < Duration::from_secs(4));
+        Ok(())
+    }
+
+    #[test]
+    fn commands_run_without_prompts_or_stdin() -> std::io::Result<()> {
+        let dir = tempfile::TempDir::new()?;
+        let probe = alias(r#"printf %s "$GIT_TERMINAL_PROMPT"; cat"#);
+        let out = TimeoutGitRunner::new(Box::new(DefaultGitRunner), Duration::from_secs(10))
+            .run_git(dir.path(), &["-c", &probe, "probe"])?;
+        assert!(out.status.success());
+        assert_eq!(String::from_utf8_lossy(&out.stdout), "0");
+        Ok(())
     }
 }
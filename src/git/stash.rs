@@ -0,0 +1,92 @@
+use std::path::Path;
+use std::time::{Duration, UNIX_EPOCH};
+
+use crate::system::Clock;
+
+use super::GitRunner;
+use super::error::{GitCommandError, run_checked};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct StashInfo {
+    pub(crate) reference: String,
+    pub(crate) branch: String,
+    pub(crate) message: String,
+    pub(crate) age: Option<Duration>,
+}
+
+/// List the entries of `refs/stash`, newest first.
+pub(crate) fn list_stashes(
+    repo: &Path,
+    git: &dyn GitRunner,
+    clock: &dyn Clock,
+) -> Result<Vec<StashInfo>, GitCommandError> {
+    let out = run_checked(
+        repo,
+        git,
+        &["stash", "list", "--format=%gd%x1f%ct%x1f%gs"],
+        &[],
+    )?;
+    let now_secs = clock
+        .now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or(Duration::from_secs(0))
+        .as_secs();
+    Ok(String::from_utf8_lossy(&out.stdout)
+        .lines()
+        .filter_map(|line| parse_stash_line(line, now_secs))
+        .collect())
+}
+
+fn parse_stash_line(line: &str, now_secs: u64) -> Option<StashInfo> {
+    let mut parts = line.splitn(3, '\u{1f}');
+    let reference = parts.next()?.trim();
+    if reference.is_empty() {
+        return None;
+    }
+    let age = parts
+        .next()
+        .and_then(|ts| ts.trim().parse::<u64>().ok())
+        .map(|ts| Duration::from_secs(now_secs.saturating_sub(ts)));
+    let (branch, message) = split_subject(parts.next().unwrap_or_default());
+    Some(StashInfo {
+        reference: reference.to_string(),
+        branch,
+        message,
+        age,
+    })
+}
+
+/// Split a stash reflog subject such as `WIP on main: 1a2b3c4 msg` or `On main: msg`.
+fn split_subject(subject: &str) -> (String, String) {
+    let rest = subject
+        .strip_prefix("WIP on ")
+        .or_else(|| subject.strip_prefix("On "));
+    match rest.and_then(|rest| rest.split_once(": ")) {
+        Some((branch, message)) => (branch.to_string(), message.trim().to_string()),
+        None => (String::new(), subject.trim().to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_stash_line_extracts_branch_message_and_age() {
+        let wip = parse_stash_line(
+            "stash@{0}\u{1f}1000\u{1f}WIP on main: 1a2b3c4 fix parser",
+            4_600,
+        )
+        .expect("wip stash");
+        assert_eq!(wip.reference, "stash@{0}");
+        assert_eq!(wip.branch, "main");
+        assert_eq!(wip.message, "1a2b3c4 fix parser");
+        assert_eq!(wip.age, Some(Duration::from_hours(1)));
+
+        let named = parse_stash_line("stash@{1}\u{1f}bogus\u{1f}On feature/x: try: this", 0)
+            .expect("named stash");
+        assert_eq!(named.branch, "feature/x");
+        assert_eq!(named.message, "try: this");
+        assert_eq!(named.age, None);
+    }
+}
@@ -0,0 +1,108 @@
+use std::path::{Path, PathBuf};
+
+use super::GitRunner;
+use super::error::{GitCommandError, run_checked};
+
+/// Gitlink entry of a superproject: where the submodule lives and the commit it records.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Submodule {
+    /// Path relative to the superproject's working tree.
+    pub(crate) path: PathBuf,
+    pub(crate) commit: String,
+}
+
+/// List the submodules recorded in the index of `repo`; repositories without a
+/// `.gitmodules` file are not queried.
+pub(crate) fn list_submodules(
+    repo: &Path,
+    git: &dyn GitRunner,
+) -> Result<Vec<Submodule>, GitCommandError> {
+    if !repo.join(".gitmodules").is_file() {
+        return Ok(Vec::new());
+    }
+    // `-z` keeps git from quoting paths with spaces or non-ASCII characters.
+    let out = run_checked(repo, git, &["ls-files", "-z", "--stage"], &[])?;
+    Ok(parse_gitlinks(&String::from_utf8_lossy(&out.stdout)))
+}
+
+/// Commits reachable from `commit` that no remote-tracking ref of `submodule` contains, or
+/// `None` when the submodule does not have the commit at all, e.g. because the superproject
+/// was updated without `git submodule update`.
+pub(crate) fn unpushed_commit_count(
+    submodule: &Path,
+    git: &dyn GitRunner,
+    commit: &str,
+) -> Result<Option<u64>, GitCommandError> {
+    // `rev-parse --verify --quiet` exits 1 when the object is missing.
+    let present = run_checked(
+        submodule,
+        git,
+        &[
+            "rev-parse",
+            "--verify",
+            "--quiet",
+            &format!("{commit}^{{commit}}"),
+        ],
+        &[1],
+    )?;
+    if !present.status.success() {
+        return Ok(None);
+    }
+    let args = ["rev-list", "--count", commit, "--not", "--remotes"];
+    let out = run_checked(submodule, git, &args, &[])?;
+    String::from_utf8_lossy(&out.stdout)
+        .trim()
+        .parse()
+        .map(Some)
+        .map_err(|_| GitCommandError::unexpected_output(&args, &out.stdout))
+}
+
+/// `ls-files -z --stage` records look like `160000 <sha> <stage>\t<path>\0`; mode 160000 marks
+/// a gitlink.
+fn parse_gitlinks(text: &str) -> Vec<Submodule> {
+    text.split('\0')
+        .filter_map(|line| {
+            let (meta, path) = line.split_once('\t')?;
+            let mut fields = meta.split_whitespace();
+            if fields.next()? != "160000" {
+                return None;
+            }
+            Some(Submodule {
+                path: PathBuf::from(path),
+                commit: fields.next()?.to_string(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_gitlinks_keeps_only_submodule_entries() {
+        let text = "\
+100644 1111111111111111111111111111111111111111 0\t.gitmodules\0\
+160000 2222222222222222222222222222222222222222 0\tlibs/core\0\
+100644 3333333333333333333333333333333333333333 0\tREADME.md\0\
+160000 4444444444444444444444444444444444444444 0\tvendor/ui kit\0\
+160000 5555555555555555555555555555555555555555 0\tvendor/café\0";
+        assert_eq!(
+            parse_gitlinks(text),
+            vec![
+                Submodule {
+                    path: PathBuf::from("libs/core"),
+                    commit: "2222222222222222222222222222222222222222".to_string(),
+                },
+                Submodule {
+                    path: PathBuf::from("vendor/ui kit"),
+                    commit: "4444444444444444444444444444444444444444".to_string(),
+                },
+                Submodule {
+                    path: PathBuf::from("vendor/café"),
+                    commit: "5555555555555555555555555555555555555555".to_string(),
+                },
+            ]
+        );
+    }
+}
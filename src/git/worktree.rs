@@ -0,0 +1,87 @@
+use std::path::{Path, PathBuf};
+
+use super::GitRunner;
+use super::error::{GitCommandError, run_checked};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct LinkedWorktree {
+    pub(crate) path: PathBuf,
+    pub(crate) branch: String,
+}
+
+/// List the linked worktrees of `repo`, excluding the main worktree and entries whose
+/// directory no longer exists.
+pub(crate) fn list_linked_worktrees(
+    repo: &Path,
+    git: &dyn GitRunner,
+) -> Result<Vec<LinkedWorktree>, GitCommandError> {
+    let out = run_checked(repo, git, &["worktree", "list", "--porcelain"], &[])?;
+    Ok(parse_worktree_list(&String::from_utf8_lossy(&out.stdout))
+        .into_iter()
+        .skip(1)
+        .filter(|wt| wt.path.is_dir())
+        .collect())
+}
+
+fn parse_worktree_list(text: &str) -> Vec<LinkedWorktree> {
+    let mut worktrees = Vec::new();
+    let mut current: Option<LinkedWorktree> = None;
+    for line in text.lines() {
+        if let Some(path) = line.strip_prefix("worktree ") {
+            worktrees.extend(current.take());
+            current = Some(LinkedWorktree {
+                path: PathBuf::from(path),
+                branch: "HEAD".to_string(),
+            });
+        } else if let Some(branch) = line.strip_prefix("branch ")
+            && let Some(wt) = current.as_mut()
+        {
+            wt.branch = branch
+                .strip_prefix("refs/heads/")
+                .unwrap_or(branch)
+                .to_string();
+        }
+    }
+    worktrees.extend(current);
+    worktrees
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_worktree_list_reads_branches_and_detached_heads() {
+        let text = "\
+worktree /src/app
+HEAD 1111111111111111111111111111111111111111
+branch refs/heads/main
+
+worktree /src/app-feature
+HEAD 2222222222222222222222222222222222222222
+branch refs/heads/feature/login
+
+worktree /src/app-review
+HEAD 3333333333333333333333333333333333333333
+detached
+";
+        let parsed = parse_worktree_list(text);
+        assert_eq!(
+            parsed,
+            vec![
+                LinkedWorktree {
+                    path: PathBuf::from("/src/app"),
+                    branch: "main".to_string(),
+                },
+                LinkedWorktree {
+                    path: PathBuf::from("/src/app-feature"),
+                    branch: "feature/login".to_string(),
+                },
+                LinkedWorktree {
+                    path: PathBuf::from("/src/app-review"),
+                    branch: "HEAD".to_string(),
+                },
+            ]
+        );
+    }
+}
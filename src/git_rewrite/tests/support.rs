@@ -154,6 +154,8 @@ fn repo(args: &RepoArgs<'_>) -> RepoSummary {
         head_revs: args.head_revs,
         head_earliest_secs: args.head_earliest_secs,
         head_latest_secs: args.head_latest_secs,
+        bare: false,
+        worktrees: Vec::new(),
     }
 }
 
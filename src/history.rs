@@ -0,0 +1,559 @@
+//! Opt-in store of past reports, kept as JSON lines so runs can be compared over time.
+//!
+//! Every recorded run appends one [`Snapshot`] holding the per-repository counters of the
+//! report. The file lives at `--history-file`, `$UNCOMMITTED_HISTORY`, or
+//! `$XDG_DATA_HOME/uncommitted/history.jsonl` (falling back to `~/.local/share`).
+
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::system::Clock;
+use crate::types::ReportData;
+
+/// Environment variable naming a history file to use instead of the XDG location.
+pub const HISTORY_ENV: &str = "UNCOMMITTED_HISTORY";
+
+/// One recorded run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// Seconds since the Unix epoch.
+    pub timestamp: u64,
+    pub repos: Vec<RepoState>,
+}
+
+/// Counters of one repository at the time of a snapshot. Linked worktree rows are folded
+/// into their repository; submodule rows keep their `superproject/path` name.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RepoState {
+    pub repo: String,
+    pub root: String,
+    pub root_display: String,
+    pub uncommitted_files: u64,
+    pub uncommitted_lines: u64,
+    pub untracked: u64,
+    pub staged_files: u64,
+    pub staged_lines: u64,
+    /// Commits not pushed to the upstream, summed over branches.
+    pub unpushed: u64,
+    /// Upstream commits not pulled, summed over branches.
+    pub behind: u64,
+    /// Local branches without upstream that carry commits.
+    pub unpublished: u64,
+    pub stashes: u64,
+    pub errors: u64,
+}
+
+impl RepoState {
+    fn key(&self) -> (&str, &str) {
+        (&self.root, &self.repo)
+    }
+
+    /// Human-readable list of the counters that differ from `old`, e.g. `unpushed 0 -> 2`.
+    #[must_use]
+    pub fn changes_from(&self, old: &RepoState) -> Vec<String> {
+        self.counters()
+            .into_iter()
+            .zip(old.counters())
+            .filter(|((_, new), (_, old))| new != old)
+            .map(|((label, new), (_, old))| format!("{label} {old} -> {new}"))
+            .collect()
+    }
+
+    /// `(label, value)` pairs in display order.
+    #[must_use]
+    pub fn counters(&self) -> [(&'static str, u64); 10] {
+        [
+            ("uncommitted files", self.uncommitted_files),
+            ("uncommitted lines", self.uncommitted_lines),
+            ("untracked", self.untracked),
+            ("staged files", self.staged_files),
+            ("staged lines", self.staged_lines),
+            ("unpushed", self.unpushed),
+            ("behind", self.behind),
+            ("unpublished", self.unpublished),
+            ("stashes", self.stashes),
+            ("errors", self.errors),
+        ]
+    }
+
+    #[must_use]
+    pub fn is_dirty(&self) -> bool {
+        self.uncommitted_files > 0 || self.untracked > 0 || self.staged_files > 0
+    }
+}
+
+impl Snapshot {
+    /// Summarize `data` per repository, stamped with the current time of `clock`.
+    #[must_use]
+    pub fn from_report(data: &ReportData, clock: &dyn Clock) -> Self {
+        let mut repos = BTreeMap::new();
+        for e in &data.repos {
+            entry(&mut repos, &e.root_full, &e.root_display, &e.repo);
+        }
+        for e in &data.uncommitted {
+            let s = entry(&mut repos, &e.root_full, &e.root_display, &e.repo);
+            s.uncommitted_files += e.files;
+            s.uncommitted_lines += e.lines;
+            s.untracked += e.untracked;
+        }
+        for e in &data.staged {
+            let s = entry(&mut repos, &e.root_full, &e.root_display, &e.repo);
+            s.staged_files += e.files;
+            s.staged_lines += e.lines;
+        }
+        for e in &data.pushable {
+            entry(&mut repos, &e.root_full, &e.root_display, &e.repo).unpushed += e.revs;
+        }
+        for e in &data.pullable {
+            entry(&mut repos, &e.root_full, &e.root_display, &e.repo).behind += e.behind;
+        }
+        for e in &data.unpublished {
+            entry(&mut repos, &e.root_full, &e.root_display, &e.repo).unpublished += 1;
+        }
+        for e in &data.stashes {
+            entry(&mut repos, &e.root_full, &e.root_display, &e.repo).stashes += 1;
+        }
+        for e in &data.errors {
+            entry(&mut repos, &e.root_full, &e.root_display, &e.repo).errors += 1;
+        }
+        let timestamp = clock
+            .now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or(Duration::ZERO)
+            .as_secs();
+        Snapshot {
+            timestamp,
+            repos: repos.into_values().collect(),
+        }
+    }
+}
+
+fn entry<'a>(
+    repos: &'a mut BTreeMap<(String, String), RepoState>,
+    root: &str,
+    root_display: &str,
+    repo: &str,
+) -> &'a mut RepoState {
+    repos
+        .entry((root.to_string(), repo.to_string()))
+        .or_insert_with(|| RepoState {
+            repo: repo.to_string(),
+            root: root.to_string(),
+            root_display: root_display.to_string(),
+            ..RepoState::default()
+        })
+}
+
+#[derive(Debug)]
+pub enum HistoryError {
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    Parse {
+        path: PathBuf,
+        line: usize,
+        source: serde_json::Error,
+    },
+    /// A snapshot reference did not match any recorded snapshot.
+    NoSnapshot(String),
+}
+
+impl std::fmt::Display for HistoryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HistoryError::Io { path, source } => {
+                write!(f, "failed to access history {}: {source}", path.display())
+            }
+            HistoryError::Parse { path, line, source } => {
+                write!(f, "invalid snapshot at {}:{line}: {source}", path.display())
+            }
+            HistoryError::NoSnapshot(reference) => {
+                write!(f, "no recorded snapshot matches '{reference}'")
+            }
+        }
+    }
+}
+
+impl std::error::Error for HistoryError {}
+
+/// Resolve the history file from `--history-file`, the environment and the XDG base directories.
+#[must_use]
+pub fn locate_history(cli: Option<&Path>) -> Option<PathBuf> {
+    if let Some(path) = cli {
+        return Some(path.to_path_buf());
+    }
+    if let Some(path) = non_empty_env(HISTORY_ENV) {
+        return Some(PathBuf::from(path));
+    }
+    let base = non_empty_env("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+        .or_else(|| {
+            non_empty_env("HOME").map(|home| PathBuf::from(home).join(".local").join("share"))
+        })?;
+    Some(base.join("uncommitted").join("history.jsonl"))
+}
+
+/// Append `snapshot` as one line, creating the file and its directory when needed.
+///
+/// # Errors
+/// Returns an error if the directory or file cannot be created or written.
+pub fn append_snapshot(path: &Path, snapshot: &Snapshot) -> Result<(), HistoryError> {
+    let io_err = |source| HistoryError::Io {
+        path: path.to_path_buf(),
+        source,
+    };
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::create_dir_all(dir).map_err(io_err)?;
+    }
+    let mut line = serde_json::to_string(snapshot).map_err(|e| io_err(e.into()))?;
+    line.push('\n');
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut file| file.write_all(line.as_bytes()))
+        .map_err(io_err)
+}
+
+/// Read every snapshot, oldest first; a missing file holds no snapshots.
+///
+/// # Errors
+/// Returns an error if the file cannot be read or a line is not a valid snapshot.
+pub fn load_snapshots(path: &Path) -> Result<Vec<Snapshot>, HistoryError> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(source) => {
+            return Err(HistoryError::Io {
+                path: path.to_path_buf(),
+                source,
+            });
+        }
+    };
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| {
+            serde_json::from_str(line).map_err(|source| HistoryError::Parse {
+                path: path.to_path_buf(),
+                line: index + 1,
+                source,
+            })
+        })
+        .collect()
+}
+
+/// Which snapshot to compare: `N` runs before the latest (`0` is the latest), or the newest
+/// snapshot at least a duration old, written with an `m`, `h`, `d` or `w` suffix (`1d`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnapshotRef {
+    RunsAgo(usize),
+    OlderThan(Duration),
+}
+
+impl std::str::FromStr for SnapshotRef {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(runs) = s.parse() {
+            return Ok(SnapshotRef::RunsAgo(runs));
+        }
+        let invalid = || {
+            format!("invalid snapshot '{s}' (expected a run count like 1 or an age like 12h, 1d)")
+        };
+        let (amount, unit_secs) = [
+            ("m", 60),
+            ("h", 60 * 60),
+            ("d", 24 * 60 * 60),
+            ("w", 7 * 24 * 60 * 60),
+        ]
+        .into_iter()
+        .find_map(|(unit, secs)| Some((s.strip_suffix(unit)?, secs)))
+        .ok_or_else(invalid)?;
+        let amount: u64 = amount.parse().map_err(|_| invalid())?;
+        let secs = amount.checked_mul(unit_secs).ok_or_else(invalid)?;
+        Ok(SnapshotRef::OlderThan(Duration::from_secs(secs)))
+    }
+}
+
+impl std::fmt::Display for SnapshotRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SnapshotRef::RunsAgo(runs) => write!(f, "{runs}"),
+            SnapshotRef::OlderThan(age) => {
+                let secs = age.as_secs();
+                let (amount, unit) = [(7 * 24 * 60 * 60, "w"), (24 * 60 * 60, "d"), (60 * 60, "h")]
+                    .into_iter()
+                    .find(|(unit, _)| secs % unit == 0)
+                    .map_or((secs / 60, "m"), |(size, unit)| (secs / size, unit));
+                write!(f, "{amount}{unit}")
+            }
+        }
+    }
+}
+
+/// Find the snapshot `reference` points at in `snapshots` (oldest first).
+///
+/// # Errors
+/// Returns [`HistoryError::NoSnapshot`] when no snapshot matches.
+pub fn select_snapshot<'a>(
+    snapshots: &'a [Snapshot],
+    reference: SnapshotRef,
+    clock: &dyn Clock,
+) -> Result<&'a Snapshot, HistoryError> {
+    let found = match reference {
+        SnapshotRef::RunsAgo(runs) => snapshots.iter().rev().nth(runs),
+        SnapshotRef::OlderThan(age) => {
+            let now = clock
+                .now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or(Duration::ZERO)
+                .as_secs();
+            let cutoff = now.saturating_sub(age.as_secs());
+            snapshots.iter().rev().find(|s| s.timestamp <= cutoff)
+        }
+    };
+    found.ok_or_else(|| HistoryError::NoSnapshot(reference.to_string()))
+}
+
+/// How one repository differs between two snapshots.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RepoChange {
+    Appeared(RepoState),
+    Disappeared(RepoState),
+    Changed {
+        state: RepoState,
+        changes: Vec<String>,
+    },
+}
+
+impl RepoChange {
+    #[must_use]
+    pub fn state(&self) -> &RepoState {
+        match self {
+            RepoChange::Appeared(state)
+            | RepoChange::Disappeared(state)
+            | RepoChange::Changed { state, .. } => state,
+        }
+    }
+
+    #[must_use]
+    pub fn label(&self) -> &'static str {
+        match self {
+            RepoChange::Appeared(_) => "appeared",
+            RepoChange::Disappeared(_) => "disappeared",
+            RepoChange::Changed { .. } => "changed",
+        }
+    }
+}
+
+/// Repositories that appeared, disappeared or changed counters from `old` to `new`.
+#[must_use]
+pub fn diff_snapshots(old: &Snapshot, new: &Snapshot) -> Vec<RepoChange> {
+    let old_repos: BTreeMap<_, _> = old.repos.iter().map(|s| (s.key(), s)).collect();
+    let new_repos: BTreeMap<_, _> = new.repos.iter().map(|s| (s.key(), s)).collect();
+    let mut changes = Vec::new();
+    for (key, state) in &new_repos {
+        match old_repos.get(key) {
+            None => changes.push(RepoChange::Appeared((*state).clone())),
+            Some(previous) => {
+                let diff = state.changes_from(previous);
+                if !diff.is_empty() {
+                    changes.push(RepoChange::Changed {
+                        state: (*state).clone(),
+                        changes: diff,
+                    });
+                }
+            }
+        }
+    }
+    for (key, state) in &old_repos {
+        if !new_repos.contains_key(key) {
+            changes.push(RepoChange::Disappeared((*state).clone()));
+        }
+    }
+    changes.sort_by(|a, b| a.state().key().cmp(&b.state().key()));
+    changes
+}
+
+/// One repository's state in each snapshot, oldest first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RepoTimeline<'a> {
+    /// Expanded root the repository was found under.
+    pub root: &'a str,
+    pub root_display: &'a str,
+    /// `None` marks snapshots in which the repository was not seen.
+    pub entries: Vec<(u64, Option<&'a RepoState>)>,
+}
+
+/// A timeline for each root holding a repository named `repo`, ordered by root. Repositories
+/// sharing a name under different roots are different repositories, so they are kept apart.
+#[must_use]
+pub fn repo_timelines<'a>(snapshots: &'a [Snapshot], repo: &str) -> Vec<RepoTimeline<'a>> {
+    let mut roots: Vec<(&str, &str)> = snapshots
+        .iter()
+        .flat_map(|s| s.repos.iter())
+        .filter(|state| state.repo == repo)
+        .map(|state| (state.root.as_str(), state.root_display.as_str()))
+        .collect();
+    roots.sort_unstable();
+    roots.dedup_by_key(|(root, _)| *root);
+    roots
+        .into_iter()
+        .map(|(root, root_display)| RepoTimeline {
+            root,
+            root_display,
+            entries: snapshots
+                .iter()
+                .map(|snapshot| {
+                    let state = snapshot
+                        .repos
+                        .iter()
+                        .find(|state| state.repo == repo && state.root == root);
+                    (snapshot.timestamp, state)
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+/// Timestamp since which `matches` has held without interruption up to the latest entry.
+#[must_use]
+pub fn streak_start(
+    timeline: &[(u64, Option<&RepoState>)],
+    matches: impl Fn(&RepoState) -> bool,
+) -> Option<u64> {
+    let mut start = None;
+    for (timestamp, state) in timeline.iter().rev() {
+        if !state.is_some_and(&matches) {
+            break;
+        }
+        start = Some(*timestamp);
+    }
+    start
+}
+
+fn non_empty_env(key: &str) -> Option<std::ffi::OsString> {
+    std::env::var_os(key).filter(|value| !value.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(repo: &str, unpushed: u64) -> RepoState {
+        RepoState {
+            repo: repo.to_string(),
+            root: "/src".to_string(),
+            root_display: "~/src".to_string(),
+            unpushed,
+            ..RepoState::default()
+        }
+    }
+
+    fn snapshot(timestamp: u64, repos: Vec<RepoState>) -> Snapshot {
+        Snapshot { timestamp, repos }
+    }
+
+    #[test]
+    fn diff_reports_appeared_disappeared_and_changed_repos() {
+        let old = snapshot(100, vec![state("a", 0), state("b", 1), state("gone", 0)]);
+        let new = snapshot(200, vec![state("a", 2), state("b", 1), state("new", 0)]);
+        let changes = diff_snapshots(&old, &new);
+        let summary: Vec<(&str, &str)> = changes
+            .iter()
+            .map(|c| (c.state().repo.as_str(), c.label()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("a", "changed"),
+                ("gone", "disappeared"),
+                ("new", "appeared")
+            ]
+        );
+        assert_eq!(
+            changes[0],
+            RepoChange::Changed {
+                state: state("a", 2),
+                changes: vec!["unpushed 0 -> 2".to_string()],
+            }
+        );
+    }
+
+    #[test]
+    fn snapshots_round_trip_through_the_history_file() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("nested/history.jsonl");
+        assert!(load_snapshots(&path).unwrap().is_empty());
+        let first = snapshot(100, vec![state("a", 1)]);
+        let second = snapshot(200, vec![state("a", 3)]);
+        append_snapshot(&path, &first).unwrap();
+        append_snapshot(&path, &second).unwrap();
+        assert_eq!(load_snapshots(&path).unwrap(), vec![first, second]);
+    }
+
+    #[test]
+    fn streak_start_finds_when_unpushed_commits_began() {
+        let snapshots = vec![
+            snapshot(100, vec![state("a", 1)]),
+            snapshot(200, vec![state("a", 0)]),
+            snapshot(300, vec![state("a", 2)]),
+            snapshot(400, vec![state("a", 4)]),
+        ];
+        let timelines = repo_timelines(&snapshots, "a");
+        assert_eq!(timelines.len(), 1);
+        let timeline = &timelines[0].entries;
+        assert_eq!(streak_start(timeline, |s| s.unpushed > 0), Some(300));
+        assert_eq!(streak_start(timeline, RepoState::is_dirty), None);
+    }
+
+    #[test]
+    fn repo_timelines_keep_roots_apart() {
+        let elsewhere = |unpushed| RepoState {
+            root: "/srv".to_string(),
+            root_display: "/srv".to_string(),
+            ..state("a", unpushed)
+        };
+        let snapshots = vec![
+            snapshot(100, vec![state("a", 1), elsewhere(0)]),
+            snapshot(200, vec![state("a", 2)]),
+            snapshot(300, vec![state("a", 0), elsewhere(5)]),
+        ];
+        let timelines = repo_timelines(&snapshots, "a");
+        let roots: Vec<&str> = timelines.iter().map(|t| t.root).collect();
+        assert_eq!(roots, ["/src", "/srv"]);
+        let times: Vec<u64> = timelines[1].entries.iter().map(|(t, _)| *t).collect();
+        assert_eq!(times, [100, 200, 300]);
+        assert!(timelines[1].entries[1].1.is_none());
+        assert_eq!(
+            streak_start(&timelines[0].entries, |s| s.unpushed > 0),
+            None
+        );
+        assert_eq!(
+            streak_start(&timelines[1].entries, |s| s.unpushed > 0),
+            Some(300)
+        );
+    }
+
+    #[test]
+    fn snapshot_refs_parse_runs_and_ages() {
+        assert_eq!("2".parse(), Ok(SnapshotRef::RunsAgo(2)));
+        assert_eq!(
+            "1d".parse(),
+            Ok(SnapshotRef::OlderThan(Duration::from_hours(24)))
+        );
+        assert!("1y".parse::<SnapshotRef>().is_err());
+        assert!("d".parse::<SnapshotRef>().is_err());
+        assert!("1é".parse::<SnapshotRef>().is_err());
+        assert!("99999999999999999w".parse::<SnapshotRef>().is_err());
+    }
+}
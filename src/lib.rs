@@ -1,21 +1,36 @@
 #![forbid(unsafe_code)]
 #![deny(warnings, clippy::all, clippy::pedantic)]
 
+pub mod actions;
+pub mod check;
+pub mod config;
 mod git;
 mod git_rewrite;
+pub mod history;
 pub mod output;
 mod report;
 mod scan;
+pub mod status;
 mod system;
 mod types;
 
-pub use git::{DefaultGitRunner, GitRunner};
+#[cfg(feature = "native")]
+pub use git::NativeGitRunner;
+pub use git::{
+    DefaultGitRunner, Exchange, FixtureError, GitFixture, GitRunner, RecordedError,
+    RecordingGitRunner, ReplayGitRunner, TimeoutGitRunner, cancel_git_commands, git_cancelled,
+};
 pub use git_rewrite::{
     GitRewriteError, collect_git_rewrite_entries, collect_git_rewrite_untracked,
     git_rewrite_toml_help,
 };
-pub use report::{collect_report_data, generate_report, humanize_age_public};
+pub use report::{
+    LiveReport, Refresh, collect_report_data, generate_report, humanize_age_public, repo_label,
+};
+pub use scan::{IGNORE_FILE, validate_pattern};
 pub use system::{Clock, DefaultClock, DefaultFsOps, FsOps};
 pub use types::{
-    GitRewriteEntry, Options, PushableEntry, ReportData, StagedEntry, UncommittedEntry,
+    Depth, FetchEntry, FetchStatus, GitRewriteEntry, Options, PullableEntry, PushableEntry,
+    RepoError, ReportData, ReportMeta, RootSettings, StagedEntry, StashEntry, SubmoduleEntry,
+    UncommittedEntry, UnpublishedEntry,
 };
@@ -1,130 +1,155 @@
 #![forbid(unsafe_code)]
 #![deny(warnings, clippy::all, clippy::pedantic)]
 
-use clap::{Args as ClapArgs, Parser, Subcommand, ValueEnum};
-use std::path::{Path, PathBuf};
-use uncommitted::{
-    DefaultClock, DefaultFsOps, DefaultGitRunner, FsOps, Options, collect_git_rewrite_entries,
-    collect_git_rewrite_untracked, collect_report_data, git_rewrite_toml_help,
-    output::{TabStyle, format_tab, to_json},
-};
-
-#[derive(Copy, Clone, Debug, Eq, PartialEq, ValueEnum)]
-enum OutputFormat {
-    Tab,
-    Json,
-}
-
-#[derive(Parser, Debug)]
-#[command(version, about = "Report git repo states under roots.")]
-struct Args {
-    /// Root directories to scan (default: ~/src)
-    roots: Vec<PathBuf>,
-
-    /// Directory depth to search (0 = only root itself, 1 = one level of children, etc.)
-    #[arg(long, default_value_t = 1)]
-    depth: usize,
-
-    #[command(flatten)]
-    scan: ScanFlags,
-
-    /// Output format: tab (default) or json
-    #[arg(long, value_enum, default_value_t = OutputFormat::Tab)]
-    output: OutputFormat,
-
-    /// Table style to use with --output tab
-    #[arg(long, value_enum, default_value_t = TabStyle::Rounded)]
-    tab_style: TabStyle,
-
-    /// Path to git rewrite configuration TOML
-    #[arg(long, requires = "git_rewrite_path")]
-    git_rewrite_toml: Option<PathBuf>,
-
-    /// Path to `git_rewrite` binary
-    #[arg(long, requires = "git_rewrite_toml")]
-    git_rewrite_path: Option<PathBuf>,
+mod cli;
 
-    #[command(flatten)]
-    output_flags: OutputFlags,
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
 
-    #[command(subcommand)]
-    command: Option<Command>,
-}
-
-#[derive(Subcommand, Debug)]
-enum Command {
-    /// Show help for `git_rewrite` TOML config fields
-    Toml,
-}
-
-#[derive(ClapArgs, Debug)]
-struct ScanFlags {
-    /// Ignore untracked files for 'uncommitted'
-    #[arg(long)]
-    no_untracked: bool,
-
-    /// Print debug info while scanning
-    #[arg(long)]
-    debug: bool,
-
-    /// Refresh remote tracking refs before computing pushables
-    #[arg(long)]
-    refresh_remotes: bool,
-}
+use clap::Parser;
+use cli::{
+    Args, Backend, CliError, Command, ConfigCommand, OutputFormat, Settings, cached_status,
+    finish_status, run_history, run_push, run_tui, run_watch,
+};
+use uncommitted::{
+    DefaultClock, DefaultFsOps, DefaultGitRunner, GitFixture, GitRunner, RecordingGitRunner,
+    ReplayGitRunner, ReportData, TimeoutGitRunner, cancel_git_commands, check,
+    collect_git_rewrite_entries, collect_git_rewrite_untracked, collect_report_data,
+    config::{Config, load_config, locate_config},
+    git_cancelled, git_rewrite_toml_help,
+    history::{Snapshot, append_snapshot},
+    output::{Delimiter, format_tab, report_schema, to_delimited, to_html, to_json},
+};
 
-#[derive(ClapArgs, Debug)]
-struct OutputFlags {
-    /// Hide repos whose commits and revs columns are 0
-    #[arg(long)]
-    omit_non_actionable: bool,
-}
+/// How long Ctrl-C waits for killed git commands to exit.
+const CANCEL_GRACE: Duration = Duration::from_secs(2);
+/// Conventional status of a process stopped by SIGINT; returned alone, never with `--fail-on` bits.
+const INTERRUPTED_EXIT_CODE: i32 = 130;
 
 fn main() {
     if should_print_toml_help() {
         print_toml_help();
         return;
     }
-    let args = Args::parse();
-    if let Err(err) = run(&args) {
-        eprintln!("{err}");
-        std::process::exit(1);
+    // clap reports usage errors with status 2, which is the `errors` bit; keep them at 1.
+    let args = match Args::try_parse() {
+        Ok(args) => args,
+        Err(err) if err.use_stderr() => {
+            let _ = err.print();
+            std::process::exit(1);
+        }
+        Err(err) => err.exit(),
+    };
+    match run(&args) {
+        Ok(0) => {}
+        Ok(code) => std::process::exit(code),
+        Err(err) => {
+            eprintln!("{err}");
+            std::process::exit(1);
+        }
     }
 }
 
-fn run(args: &Args) -> Result<(), CliError> {
+fn run(args: &Args) -> Result<i32, CliError> {
     if matches!(args.command, Some(Command::Toml)) {
         print_toml_help();
+        return Ok(0);
+    }
+    if matches!(args.command, Some(Command::Schema)) {
+        println!("{}", report_schema());
+        return Ok(0);
+    }
+
+    let location = locate_config(args.config.as_deref());
+    let config = match &location {
+        Some(location) => load_config(location)?,
+        None => Config::default(),
+    };
+    let settings = Settings::resolve(args, location, config)?;
+
+    if let Some(Command::Config {
+        action: ConfigCommand::Show,
+    }) = &args.command
+    {
+        print_config(&settings)?;
+        return Ok(0);
+    }
+    if let Some(Command::History { action }) = &args.command {
+        return run_history(action, &settings);
+    }
+    if let Some(Command::Status(status)) = &args.command
+        && cached_status(status, &settings)?
+    {
+        return Ok(0);
+    }
+
+    let git = git_runner(args, &settings)?;
+    let Some(record) = &args.record else {
+        set_interrupt_handler(args, || {})?;
+        return run_with_git(args, &settings, git.as_ref());
+    };
+    // An interrupted recording still saves what it captured up to the Ctrl-C.
+    let recorder = Arc::new(RecordingGitRunner::new(git));
+    let interrupted = (Arc::clone(&recorder), record.clone());
+    set_interrupt_handler(args, move || {
+        if let Err(err) = save_fixture(&interrupted.0, &interrupted.1) {
+            eprintln!("{err}");
+        }
+    })?;
+    let result = run_with_git(args, &settings, recorder.as_ref());
+    save_fixture(&recorder, record)?;
+    result
+}
+
+fn save_fixture(recorder: &RecordingGitRunner, path: &Path) -> Result<(), CliError> {
+    recorder
+        .fixture()
+        .save(path)
+        .map_err(|err| CliError(err.to_string()))
+}
+
+/// The TUI reads Ctrl-C as a key press; everything else stops its git commands, runs
+/// `flush` and exits.
+fn set_interrupt_handler(args: &Args, flush: impl Fn() + Send + 'static) -> Result<(), CliError> {
+    if let Some(Command::Tui) = &args.command {
         return Ok(());
     }
+    ctrlc::set_handler(move || {
+        cancel_git_commands(CANCEL_GRACE);
+        flush();
+        std::process::exit(INTERRUPTED_EXIT_CODE);
+    })
+    .map_err(|err| CliError(format!("failed to install Ctrl-C handler: {err}")))
+}
 
+fn run_with_git(args: &Args, settings: &Settings, git: &dyn GitRunner) -> Result<i32, CliError> {
     let fs = DefaultFsOps;
-    let git = DefaultGitRunner;
     let clock = DefaultClock;
 
-    let git_rewrite_toml = match args.git_rewrite_toml.as_ref() {
-        Some(path) => Some(resolve_path(&fs, path)?),
-        None => None,
-    };
-    let git_rewrite_path = match args.git_rewrite_path.as_ref() {
-        Some(path) => Some(resolve_path(&fs, path)?),
-        None => None,
-    };
-
-    let opts = Options {
-        roots: args.roots.clone(),
-        depth: args.depth,
-        no_untracked: args.scan.no_untracked,
-        debug: args.scan.debug,
-        refresh_remotes: args.scan.refresh_remotes,
-        git_rewrite_toml: git_rewrite_toml.clone(),
-        git_rewrite_path: git_rewrite_path.clone(),
-    };
+    if let Some(Command::Tui) = &args.command {
+        return run_tui(settings, git);
+    }
+    if let Some(Command::Watch { debounce }) = &args.command {
+        return run_watch(settings, git, Duration::from_millis(*debounce));
+    }
 
-    let mut data = collect_report_data(&opts, &fs, &git, &clock);
+    let mut data = collect_report_data(&settings.scan, &fs, git, &clock);
+    // A report whose commands were killed by Ctrl-C is incomplete; print nothing.
+    if git_cancelled() {
+        return Ok(INTERRUPTED_EXIT_CODE);
+    }
+    if let Some(Command::Push(push)) = &args.command {
+        return run_push(push, settings, &data, git);
+    }
+    if let Some(Command::Status(status)) = &args.command {
+        return finish_status(status, settings, &data);
+    }
 
-    if let (Some(config_path), Some(binary_path)) =
-        (git_rewrite_toml.as_ref(), git_rewrite_path.as_ref())
-    {
+    if let (Some(config_path), Some(binary_path)) = (
+        settings.scan.git_rewrite_toml.as_ref(),
+        settings.scan.git_rewrite_path.as_ref(),
+    ) {
         data.untracked_enabled = true;
         let untracked = collect_git_rewrite_untracked(config_path, &data.repos)?;
         data.untracked_repos = untracked;
@@ -132,20 +157,98 @@ fn run(args: &Args) -> Result<(), CliError> {
         data.git_rewrite = Some(entries);
     }
 
-    match args.output {
+    if settings.save_history {
+        save_history(settings, &data, &clock);
+    }
+
+    let conditions = &settings.fail_on;
+    if settings.summary {
+        println!("{}", check::summary_line(&data, conditions));
+        return Ok(check::exit_code(&data, conditions));
+    }
+
+    let omit = settings.omit_non_actionable;
+    match settings.output {
         OutputFormat::Tab => {
-            let out = format_tab(&data, args.tab_style, args.output_flags.omit_non_actionable);
+            let out = format_tab(&data, settings.tab_style, omit, settings.tab_colors);
             println!("{out}");
         }
         OutputFormat::Json => {
             let out = to_json(&data);
             println!("{out}");
         }
+        OutputFormat::Csv => {
+            let out = to_delimited(&data, Delimiter::Comma, settings.section, omit);
+            print!("{out}");
+        }
+        OutputFormat::Tsv => {
+            let out = to_delimited(&data, Delimiter::Tab, settings.section, omit);
+            print!("{out}");
+        }
+        OutputFormat::Html => {
+            let out = to_html(&data, omit);
+            print!("{out}");
+        }
+        OutputFormat::Template => {
+            // An empty rendering (e.g. the prompt template with nothing to do) prints nothing.
+            let out = settings.render_template(&data)?;
+            if !out.is_empty() {
+                println!("{out}");
+            }
+        }
     }
 
+    Ok(check::exit_code(&data, conditions))
+}
+
+fn print_config(settings: &Settings) -> Result<(), CliError> {
+    match &settings.config {
+        Some(location) if location.path.is_file() => {
+            println!("# config file: {}", location.path.display());
+        }
+        Some(location) => println!("# config file: {} (not found)", location.path.display()),
+        None => println!("# config file: none"),
+    }
+    let text = toml::to_string_pretty(&settings.to_config())
+        .map_err(|e| CliError(format!("failed to render config: {e}")))?;
+    print!("{text}");
     Ok(())
 }
 
+/// Recording is best effort: a history write failure is reported but does not fail the run.
+fn save_history(settings: &Settings, data: &ReportData, clock: &DefaultClock) {
+    let Some(path) = &settings.history_file else {
+        eprintln!("warning: not saving history: no history file (set --history-file or HOME)");
+        return;
+    };
+    if let Err(err) = append_snapshot(path, &Snapshot::from_report(data, clock)) {
+        eprintln!("warning: {err}");
+    }
+}
+
+fn git_runner(args: &Args, settings: &Settings) -> Result<Box<dyn GitRunner>, CliError> {
+    if let Some(path) = &args.replay {
+        let fixture = GitFixture::load(path).map_err(|err| CliError(err.to_string()))?;
+        return Ok(Box::new(ReplayGitRunner::new(fixture)));
+    }
+    let runner: Box<dyn GitRunner> = match settings.backend {
+        Backend::Git => Box::new(DefaultGitRunner),
+        #[cfg(feature = "native")]
+        Backend::Native => Box::new(uncommitted::NativeGitRunner::new()),
+        #[cfg(not(feature = "native"))]
+        Backend::Native => {
+            return Err(CliError(
+                "--backend native requires uncommitted to be built with the `native` feature"
+                    .to_string(),
+            ));
+        }
+    };
+    Ok(match settings.git_timeout {
+        Some(timeout) => Box::new(TimeoutGitRunner::new(runner, timeout)),
+        None => runner,
+    })
+}
+
 fn print_toml_help() {
     print!("{}", git_rewrite_toml_help());
 }
@@ -164,30 +267,3 @@ fn should_print_toml_help() -> bool {
         Some(_) => false,
     }
 }
-
-fn resolve_path(fs: &DefaultFsOps, path: &Path) -> Result<PathBuf, CliError> {
-    let expanded = fs.expand_tilde(path);
-    if expanded.is_absolute() {
-        return Ok(expanded);
-    }
-    let cwd = std::env::current_dir()
-        .map_err(|e| CliError(format!("failed to resolve current directory: {e}")))?;
-    Ok(cwd.join(expanded))
-}
-
-#[derive(Debug)]
-struct CliError(String);
-
-impl std::fmt::Display for CliError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.0)
-    }
-}
-
-impl std::error::Error for CliError {}
-
-impl From<uncommitted::GitRewriteError> for CliError {
-    fn from(err: uncommitted::GitRewriteError) -> Self {
-        CliError(err.to_string())
-    }
-}
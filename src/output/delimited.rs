@@ -0,0 +1,490 @@
+//! CSV and TSV forms of a report, for spreadsheets and line-oriented tools.
+//!
+//! Each section is a block of a header row and one row per entry; blocks are separated by a
+//! blank line. The first column names the section, so `awk -F'\t' '$1 == "pushable"'` picks
+//! one out of the full output. Headers follow the tab tables, except that `Root` is always
+//! present and every humanized age is followed by its raw value in seconds.
+
+use std::borrow::Cow;
+use std::time::Duration;
+
+use clap::ValueEnum;
+
+use crate::types::{
+    FetchEntry, FetchStatus, GitRewriteEntry, PullableEntry, PushableEntry, RepoError, StagedEntry,
+    StashEntry, SubmoduleEntry, UncommittedEntry, UnpublishedEntry, UntrackedReason,
+    UntrackedRepoEntry,
+};
+use crate::{ReportData, humanize_age_public, report::repo_label};
+
+use super::tab::{apply_omit_filter, exit_label};
+
+/// Field separator of a delimited report.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Delimiter {
+    /// RFC 4180 CSV: fields holding a comma, quote or line break are double-quoted.
+    Comma,
+    /// TSV: tabs, line breaks and backslashes inside fields are backslash-escaped.
+    Tab,
+}
+
+/// A report section, as selected with `--section`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, ValueEnum)]
+pub enum Section {
+    Uncommitted,
+    Staged,
+    Pushable,
+    Pullable,
+    Unpublished,
+    Stashes,
+    Submodules,
+    Fetches,
+    Errors,
+    GitRewrite,
+    Other,
+}
+
+impl Section {
+    /// Name written in the first column of the section's rows.
+    #[must_use]
+    pub fn key(self) -> &'static str {
+        match self {
+            Section::Uncommitted => "uncommitted",
+            Section::Staged => "staged",
+            Section::Pushable => "pushable",
+            Section::Pullable => "pullable",
+            Section::Unpublished => "unpublished",
+            Section::Stashes => "stashes",
+            Section::Submodules => "submodules",
+            Section::Fetches => "fetches",
+            Section::Errors => "errors",
+            Section::GitRewrite => "git-rewrite",
+            Section::Other => "other",
+        }
+    }
+
+    fn header(self) -> &'static [&'static str] {
+        match self {
+            Section::Uncommitted => &[
+                "Root",
+                "Repo",
+                "Branch",
+                "Upstream",
+                "Lines",
+                "Files",
+                "Untracked",
+            ],
+            Section::Staged => &["Root", "Repo", "Branch", "Lines", "Files", "Untracked"],
+            Section::Pushable => &[
+                "Root",
+                "Repo",
+                "Branch",
+                "Commits",
+                "Earliest",
+                "Earliest Secs",
+                "Latest",
+                "Latest Secs",
+            ],
+            Section::Pullable => &[
+                "Root",
+                "Repo",
+                "Branch",
+                "Upstream",
+                "Behind",
+                "Ahead",
+                "Status",
+                "Earliest",
+                "Earliest Secs",
+                "Latest",
+                "Latest Secs",
+            ],
+            Section::Unpublished => &[
+                "Root",
+                "Repo",
+                "Branch",
+                "Base",
+                "Commits",
+                "Earliest",
+                "Earliest Secs",
+                "Latest",
+                "Latest Secs",
+            ],
+            Section::Stashes => &[
+                "Root", "Repo", "Stash", "Branch", "Message", "Age", "Age Secs",
+            ],
+            Section::Submodules => &["Root", "Repo", "Submodule", "Commit", "Revs"],
+            Section::Fetches => &[
+                "Root", "Repo", "Remote", "Status", "Time", "Time Ms", "Error",
+            ],
+            Section::Errors => &["Root", "Repo", "Command", "Exit", "Error"],
+            Section::GitRewrite => &[
+                "Source",
+                "Target",
+                "Commits",
+                "Earliest",
+                "Earliest Secs",
+                "Latest",
+                "Latest Secs",
+            ],
+            Section::Other => &[
+                "Source",
+                "Status",
+                "Commits",
+                "Earliest",
+                "Earliest Secs",
+                "Latest",
+                "Latest Secs",
+            ],
+        }
+    }
+}
+
+/// Render `data` as delimited blocks, one per section `format_tab` would show, or only
+/// `section` when given. A selected section is written even when it has no rows, so its
+/// header is always there to parse.
+#[must_use]
+pub fn to_delimited(
+    data: &ReportData,
+    delimiter: Delimiter,
+    section: Option<Section>,
+    omit_non_actionable: bool,
+) -> String {
+    let data = if omit_non_actionable {
+        apply_omit_filter(data)
+    } else {
+        Cow::Borrowed(data)
+    };
+    let sections = match section {
+        Some(section) => vec![section],
+        None => shown_sections(&data, omit_non_actionable),
+    };
+    sections
+        .into_iter()
+        .map(|section| block(&data, section, delimiter))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Sections `format_tab` shows for `data`, in its order.
+pub(super) fn shown_sections(data: &ReportData, omit_non_actionable: bool) -> Vec<Section> {
+    let mut sections = vec![
+        Section::Uncommitted,
+        Section::Staged,
+        Section::Pushable,
+        Section::Pullable,
+        Section::Unpublished,
+        Section::Stashes,
+    ];
+    if !data.submodules.is_empty() {
+        sections.push(Section::Submodules);
+    }
+    if !data.fetches.is_empty() {
+        sections.push(Section::Fetches);
+    }
+    if !data.errors.is_empty() {
+        sections.push(Section::Errors);
+    }
+    if data.git_rewrite.is_some() {
+        sections.push(Section::GitRewrite);
+    }
+    if data.untracked_enabled && !omit_non_actionable {
+        sections.push(Section::Other);
+    }
+    sections
+}
+
+fn block(data: &ReportData, section: Section, delimiter: Delimiter) -> String {
+    let mut out = String::new();
+    let header = section.header().iter().map(|name| (*name).to_string());
+    push_line(&mut out, section, header.collect(), delimiter);
+    for row in rows(data, section) {
+        push_line(&mut out, section, row, delimiter);
+    }
+    out
+}
+
+fn push_line(out: &mut String, section: Section, fields: Vec<String>, delimiter: Delimiter) {
+    let separator = match delimiter {
+        Delimiter::Comma => ",",
+        Delimiter::Tab => "\t",
+    };
+    let line = std::iter::once(section.key().to_string())
+        .chain(fields)
+        .map(|value| quote(&value, delimiter).into_owned())
+        .collect::<Vec<_>>()
+        .join(separator);
+    out.push_str(&line);
+    out.push('\n');
+}
+
+fn quote(value: &str, delimiter: Delimiter) -> Cow<'_, str> {
+    match delimiter {
+        Delimiter::Comma if value.contains([',', '"', '\n', '\r']) => {
+            Cow::Owned(format!("\"{}\"", value.replace('"', "\"\"")))
+        }
+        Delimiter::Tab if value.contains(['\\', '\t', '\n', '\r']) => Cow::Owned(
+            value
+                .replace('\\', r"\\")
+                .replace('\t', r"\t")
+                .replace('\n', r"\n")
+                .replace('\r', r"\r"),
+        ),
+        _ => Cow::Borrowed(value),
+    }
+}
+
+fn rows(data: &ReportData, section: Section) -> Vec<Vec<String>> {
+    match section {
+        Section::Uncommitted => data.uncommitted.iter().map(uncommitted_row).collect(),
+        Section::Staged => data.staged.iter().map(staged_row).collect(),
+        Section::Pushable => sorted(&data.pushable, |entry| {
+            (&entry.root_display, &entry.repo, &entry.branch)
+        })
+        .map(pushable_row)
+        .collect(),
+        Section::Pullable => sorted(&data.pullable, |entry| {
+            (&entry.root_display, &entry.repo, &entry.branch)
+        })
+        .map(pullable_row)
+        .collect(),
+        Section::Unpublished => sorted(&data.unpublished, |entry| {
+            (&entry.root_display, &entry.repo, &entry.branch)
+        })
+        .map(unpublished_row)
+        .collect(),
+        Section::Stashes => data.stashes.iter().map(stash_row).collect(),
+        Section::Submodules => data.submodules.iter().map(submodule_row).collect(),
+        Section::Fetches => data.fetches.iter().map(fetch_row).collect(),
+        Section::Errors => data.errors.iter().map(error_row).collect(),
+        Section::GitRewrite => {
+            let entries = data.git_rewrite.as_deref().unwrap_or_default();
+            sorted(entries, |entry| (&entry.source_repo, &entry.target_repo))
+                .map(git_rewrite_row)
+                .collect()
+        }
+        Section::Other => data.untracked_repos.iter().map(other_row).collect(),
+    }
+}
+
+/// `entries` in the order the tab tables sort them.
+fn sorted<'a, T, K: Ord>(
+    entries: &'a [T],
+    key: impl Fn(&'a T) -> K,
+) -> impl Iterator<Item = &'a T> {
+    let mut sorted: Vec<&T> = entries.iter().collect();
+    sorted.sort_by_key(|entry| key(entry));
+    sorted.into_iter()
+}
+
+fn uncommitted_row(entry: &UncommittedEntry) -> Vec<String> {
+    vec![
+        entry.root_display.clone(),
+        repo_label(&entry.repo, entry.worktree.as_deref()),
+        entry.branch.clone(),
+        entry.upstream.clone().unwrap_or_default(),
+        entry.lines.to_string(),
+        entry.files.to_string(),
+        entry.untracked.to_string(),
+    ]
+}
+
+fn staged_row(entry: &StagedEntry) -> Vec<String> {
+    vec![
+        entry.root_display.clone(),
+        repo_label(&entry.repo, entry.worktree.as_deref()),
+        entry.branch.clone(),
+        entry.lines.to_string(),
+        entry.files.to_string(),
+        entry.untracked.to_string(),
+    ]
+}
+
+fn pushable_row(entry: &PushableEntry) -> Vec<String> {
+    let mut row = vec![
+        entry.root_display.clone(),
+        entry.repo.clone(),
+        entry.branch.clone(),
+        entry.revs.to_string(),
+    ];
+    row.extend(age(entry.earliest_secs));
+    row.extend(age(entry.latest_secs));
+    row
+}
+
+fn pullable_row(entry: &PullableEntry) -> Vec<String> {
+    let status = if entry.diverged { "diverged" } else { "behind" };
+    let mut row = vec![
+        entry.root_display.clone(),
+        entry.repo.clone(),
+        entry.branch.clone(),
+        entry.upstream.clone(),
+        entry.behind.to_string(),
+        entry.ahead.to_string(),
+        status.to_string(),
+    ];
+    row.extend(age(entry.earliest_secs));
+    row.extend(age(entry.latest_secs));
+    row
+}
+
+fn unpublished_row(entry: &UnpublishedEntry) -> Vec<String> {
+    let mut row = vec![
+        entry.root_display.clone(),
+        entry.repo.clone(),
+        entry.branch.clone(),
+        entry.base.clone().unwrap_or_else(|| "n/a".to_string()),
+        entry.revs.to_string(),
+    ];
+    row.extend(age(entry.earliest_secs));
+    row.extend(age(entry.latest_secs));
+    row
+}
+
+fn stash_row(entry: &StashEntry) -> Vec<String> {
+    let mut row = vec![
+        entry.root_display.clone(),
+        entry.repo.clone(),
+        entry.stash.clone(),
+        entry.branch.clone(),
+        entry.message.clone(),
+    ];
+    row.extend(age(entry.age_secs));
+    row
+}
+
+fn submodule_row(entry: &SubmoduleEntry) -> Vec<String> {
+    vec![
+        entry.root_display.clone(),
+        entry.repo.clone(),
+        entry.path.clone(),
+        entry.commit.clone(),
+        entry.revs.to_string(),
+    ]
+}
+
+fn fetch_row(entry: &FetchEntry) -> Vec<String> {
+    let status = match (&entry.status, &entry.source) {
+        (FetchStatus::Shared, Some(source)) => format!("shared ({source})"),
+        (status, _) => status.label().to_string(),
+    };
+    vec![
+        entry.root_display.clone(),
+        entry.repo.clone(),
+        entry.remote.clone(),
+        status,
+        format!(
+            "{:.1}s",
+            Duration::from_millis(entry.duration_ms).as_secs_f64()
+        ),
+        entry.duration_ms.to_string(),
+        entry.error.clone().unwrap_or_default(),
+    ]
+}
+
+fn error_row(entry: &RepoError) -> Vec<String> {
+    vec![
+        entry.root_display.clone(),
+        repo_label(&entry.repo, entry.worktree.as_deref()),
+        entry.command.clone(),
+        exit_label(entry),
+        entry.stderr.clone(),
+    ]
+}
+
+fn git_rewrite_row(entry: &GitRewriteEntry) -> Vec<String> {
+    let mut row = vec![
+        format!("{}:{}", entry.source_repo, entry.source_branch),
+        format!("{}:{}", entry.target_repo, entry.target_branch),
+        entry.commits.to_string(),
+    ];
+    row.extend(age(entry.earliest_secs));
+    row.extend(age(entry.latest_secs));
+    row
+}
+
+fn other_row(entry: &UntrackedRepoEntry) -> Vec<String> {
+    let status = match entry.reason {
+        UntrackedReason::Ignored => "ignored",
+        UntrackedReason::MissingConfig => "untracked",
+        UntrackedReason::MissingRepo => "missing",
+    };
+    let mut row = vec![
+        format!("{}:{}", entry.repo, entry.branch),
+        status.to_string(),
+        entry
+            .revs
+            .map_or_else(|| "n/a".to_string(), |revs| revs.to_string()),
+    ];
+    row.extend(age(entry.earliest_secs));
+    row.extend(age(entry.latest_secs));
+    row
+}
+
+/// An age as the tab tables show it, then in raw seconds (empty when unknown).
+fn age(secs: Option<u64>) -> [String; 2] {
+    match secs {
+        Some(secs) => [
+            humanize_age_public(Duration::from_secs(secs)),
+            secs.to_string(),
+        ],
+        None => ["n/a".to_string(), String::new()],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{PushableEntry, StashEntry};
+
+    fn data() -> ReportData {
+        ReportData {
+            pushable: vec![PushableEntry {
+                repo: "app".to_string(),
+                branch: "main".to_string(),
+                revs: 2,
+                earliest_secs: Some(7200),
+                latest_secs: None,
+                root_display: "~/src".to_string(),
+                root_full: "/home/me/src".to_string(),
+                path: "/home/me/src/app".into(),
+            }],
+            stashes: vec![StashEntry {
+                repo: "app".to_string(),
+                stash: "stash@{0}".to_string(),
+                branch: "main".to_string(),
+                message: "WIP on main: \"fix\", part\t2".to_string(),
+                age_secs: Some(60),
+                root_display: "~/src".to_string(),
+                root_full: "/home/me/src".to_string(),
+                path: "/home/me/src/app".into(),
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn csv_quotes_fields_and_pairs_ages_with_seconds() {
+        let out = to_delimited(&data(), Delimiter::Comma, None, false);
+        let blocks: Vec<&str> = out.split("\n\n").collect();
+        assert_eq!(blocks.len(), 6);
+        assert_eq!(
+            blocks[0],
+            "uncommitted,Root,Repo,Branch,Upstream,Lines,Files,Untracked"
+        );
+        assert!(blocks[2].ends_with("\npushable,~/src,app,main,2,2.0 hr,7200,n/a,"));
+        assert!(blocks[5].contains(",\"WIP on main: \"\"fix\"\", part\t2\",1.0 min,60\n"));
+    }
+
+    #[test]
+    fn tsv_escapes_tabs_and_selects_one_section() {
+        let out = to_delimited(&data(), Delimiter::Tab, Some(Section::Stashes), false);
+        assert_eq!(
+            out,
+            "stashes\tRoot\tRepo\tStash\tBranch\tMessage\tAge\tAge Secs\n\
+             stashes\t~/src\tapp\tstash@{0}\tmain\tWIP on main: \"fix\", part\\t2\t1.0 min\t60\n"
+        );
+
+        let errors = to_delimited(&data(), Delimiter::Tab, Some(Section::Errors), false);
+        assert_eq!(errors, "errors\tRoot\tRepo\tCommand\tExit\tError\n");
+    }
+}
@@ -1,102 +1,170 @@
-use crate::{ReportData, types::UntrackedReason};
-use serde_json::json;
+//! The versioned JSON form of a report.
+//!
+//! The document is [`ReportData`] as serde writes it, with a top-level `schema_version`.
+//! `schema_version` is bumped whenever a field is removed, renamed or changes meaning; new
+//! fields may appear without a bump, so consumers should ignore keys they do not know. Fields
+//! added after version 1 default when missing, so older reports still parse.
 
+use schemars::{JsonSchema, schema_for};
+use serde::{Deserialize, Serialize};
+
+use crate::ReportData;
+
+/// Version of the JSON report layout written by this build.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// A JSON report: [`ReportData`] plus the layout version it was written with.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[schemars(title = "uncommitted report")]
+pub struct JsonReport {
+    pub schema_version: u32,
+    #[serde(flatten)]
+    pub data: ReportData,
+}
+
+/// A borrowed [`JsonReport`], for serializing without cloning the report.
+#[derive(Serialize)]
+pub(super) struct JsonReportRef<'a> {
+    schema_version: u32,
+    #[serde(flatten)]
+    data: &'a ReportData,
+}
+
+impl<'a> JsonReportRef<'a> {
+    pub(super) fn new(data: &'a ReportData) -> Self {
+        JsonReportRef {
+            schema_version: SCHEMA_VERSION,
+            data,
+        }
+    }
+}
+
+/// Serialize `data` as a JSON report, on one line.
+///
+/// # Panics
+/// Never in practice: every report type serializes to JSON.
 #[must_use]
 pub fn to_json(data: &ReportData) -> String {
-    let uncommitted = data
-        .uncommitted
-        .iter()
-        .map(|e| {
-            json!({
-                "repo": &e.repo,
-                "branch": &e.branch,
-                "upstream": &e.upstream,
-                "lines": e.lines,
-                "files": e.files,
-                "untracked": e.untracked,
-                "root": &e.root_full,
-            })
-        })
-        .collect::<Vec<_>>();
-    let staged = data
-        .staged
-        .iter()
-        .map(|e| {
-            json!({
-                "repo": &e.repo,
-                "branch": &e.branch,
-                "lines": e.lines,
-                "files": e.files,
-                "untracked": e.untracked,
-                "root": &e.root_full,
-            })
-        })
-        .collect::<Vec<_>>();
-    let pushable = data
-        .pushable
-        .iter()
-        .map(|e| {
-            json!({
-                "repo": &e.repo,
-                "branch": &e.branch,
-                "revs": e.revs,
-                "earliest_secs": e.earliest_secs,
-                "latest_secs": e.latest_secs,
-                "root": &e.root_full,
-            })
-        })
-        .collect::<Vec<_>>();
-    let untracked_repos = if data.untracked_enabled {
-        Some(
-            data.untracked_repos
-                .iter()
-                .map(|entry| {
-                    let reason = match entry.reason {
-                        UntrackedReason::Ignored => "ignored",
-                        UntrackedReason::MissingConfig => "missing_config",
-                        UntrackedReason::MissingRepo => "missing",
-                    };
-                    json!({
-                        "repo": &entry.repo,
-                        "branch": &entry.branch,
-                        "root": &entry.root_full,
-                        "root_display": &entry.root_display,
-                        "revs": entry.revs,
-                        "earliest_secs": entry.earliest_secs,
-                        "latest_secs": entry.latest_secs,
-                        "reason": reason,
-                    })
-                })
-                .collect::<Vec<_>>(),
-        )
-    } else {
-        None
-    };
-    let git_rewrite = data.git_rewrite.as_ref().map(|entries| {
-        entries
-            .iter()
-            .map(|entry| {
-                json!({
-                    "source_repo": &entry.source_repo,
-                    "source_branch": &entry.source_branch,
-                    "source_path": &entry.source_path,
-                    "target_repo": &entry.target_repo,
-                    "target_branch": &entry.target_branch,
-                    "target_path": &entry.target_path,
-                    "commits": entry.commits,
-                    "earliest_secs": entry.earliest_secs,
-                    "latest_secs": entry.latest_secs,
-                })
-            })
-            .collect::<Vec<_>>()
-    });
-
-    json!({
-        "uncommitted": uncommitted,
-        "staged": staged,
-        "pushable": pushable,
-        "untracked_repos": untracked_repos,
-        "git_rewrite": git_rewrite,
-    })
-    .to_string()
+    let mut value = serde_json::to_value(JsonReportRef::new(data))
+        .expect("report types always serialize to JSON");
+    // A disabled section is `null`, so consumers can tell it apart from an empty one.
+    if !data.untracked_enabled {
+        value["untracked_repos"] = serde_json::Value::Null;
+    }
+    value.to_string()
+}
+
+/// JSON Schema (draft 2020-12) of the documents [`to_json`] writes, pretty-printed.
+///
+/// # Panics
+/// Never in practice: a generated schema is plain JSON.
+#[must_use]
+pub fn report_schema() -> String {
+    serde_json::to_string_pretty(&schema_for!(JsonReport))
+        .expect("JSON schemas always serialize to JSON")
+}
+
+/// Read a report written by [`to_json`] back into [`ReportData`].
+///
+/// # Errors
+/// Returns an error if `text` is not a JSON report or was written with a newer
+/// `schema_version` than this build understands.
+pub fn parse_report(text: &str) -> Result<ReportData, ParseReportError> {
+    let report: JsonReport = serde_json::from_str(text).map_err(ParseReportError::Json)?;
+    if report.schema_version > SCHEMA_VERSION {
+        return Err(ParseReportError::Version(report.schema_version));
+    }
+    Ok(report.data)
+}
+
+#[derive(Debug)]
+pub enum ParseReportError {
+    Json(serde_json::Error),
+    /// The report declares a `schema_version` newer than [`SCHEMA_VERSION`].
+    Version(u32),
+}
+
+impl std::fmt::Display for ParseReportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseReportError::Json(source) => write!(f, "invalid JSON report: {source}"),
+            ParseReportError::Version(version) => write!(
+                f,
+                "report has schema_version {version}; this build reads up to {SCHEMA_VERSION}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ParseReportError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{FetchEntry, FetchStatus, StagedEntry};
+
+    #[test]
+    fn parse_report_reads_back_what_to_json_wrote() -> Result<(), ParseReportError> {
+        let mut data = ReportData::default();
+        data.meta.generated_at = "2026-01-02T03:04:05Z".to_string();
+        data.meta.host = "box".to_string();
+        data.staged.push(StagedEntry {
+            repo: "app".to_string(),
+            branch: "main".to_string(),
+            upstream: Some("origin/main".to_string()),
+            worktree: None,
+            lines: 3,
+            files: 1,
+            untracked: 0,
+            root_display: "~/src".to_string(),
+            root_full: "/home/me/src".to_string(),
+            path: "/home/me/src/app".into(),
+        });
+        data.fetches.push(FetchEntry {
+            repo: "app".to_string(),
+            remote: "origin".to_string(),
+            url: None,
+            status: FetchStatus::TimedOut,
+            source: None,
+            attempts: 2,
+            duration_ms: 30_000,
+            error: Some("timed out after 30s".to_string()),
+            root_display: "~/src".to_string(),
+            root_full: "/home/me/src".to_string(),
+        });
+
+        let text = to_json(&data);
+        let value: serde_json::Value =
+            serde_json::from_str(&text).map_err(ParseReportError::Json)?;
+        assert_eq!(value["schema_version"], SCHEMA_VERSION);
+        assert_eq!(value["generated_at"], "2026-01-02T03:04:05Z");
+        assert_eq!(value["staged"][0]["upstream"], "origin/main");
+        assert_eq!(value["staged"][0]["root_display"], "~/src");
+        assert_eq!(value["fetches"][0]["status"], "timed_out");
+
+        let parsed = parse_report(&text)?;
+        assert_eq!(to_json(&parsed), text);
+
+        assert_eq!(value["staged"][0]["root"], "/home/me/src");
+        assert!(value["untracked_repos"].is_null());
+
+        let newer = text.replacen("\"schema_version\":1", "\"schema_version\":99", 1);
+        assert!(matches!(
+            parse_report(&newer),
+            Err(ParseReportError::Version(99))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn parse_report_reads_version_1_reports_without_later_fields() -> Result<(), ParseReportError> {
+        let text = r#"{"schema_version":1,"pushable":[{"repo":"app","branch":"main","revs":2,
+            "earliest_secs":null,"latest_secs":null,"root_display":"~/src",
+            "root":"/home/me/src"}],"untracked_repos":null}"#;
+        let data = parse_report(text)?;
+        assert_eq!(data.pushable[0].root_full, "/home/me/src");
+        assert_eq!(data.pushable[0].path, std::path::PathBuf::new());
+        assert!(data.untracked_repos.is_empty());
+        Ok(())
+    }
 }
@@ -1,5 +1,14 @@
+pub mod delimited;
+pub mod html;
 pub mod json;
 pub mod tab;
+pub mod template;
 
-pub use json::to_json;
-pub use tab::{TabStyle, format_tab};
+pub use delimited::{Delimiter, Section, to_delimited};
+pub use html::to_html;
+pub use json::{ParseReportError, SCHEMA_VERSION, parse_report, report_schema, to_json};
+pub use tab::{
+    TabColors, TabStyle, format_history_diff, format_history_timeline, format_push_plan,
+    format_push_results, format_tab,
+};
+pub use template::{BUILTIN_TEMPLATES, ReportTemplate, TemplateError};
@@ -0,0 +1,91 @@
+use std::time::Duration;
+
+use tabled::{
+    Table,
+    settings::{Color, object::Rows},
+};
+
+/// ANSI coloring of `--output tab` tables: bold section titles, highlighted rows that are
+/// older or larger than the thresholds, dimmed rows with nothing to do.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct TabColors {
+    /// Emit escape codes at all; everything else is ignored when this is false.
+    pub enabled: bool,
+    /// Rows whose earliest commit (or stash) is older than this are highlighted.
+    pub age_threshold: Duration,
+    /// Rows changing more lines than this are highlighted.
+    pub lines_threshold: u64,
+}
+
+impl TabColors {
+    pub const DEFAULT_AGE_THRESHOLD: Duration = Duration::from_hours(7 * 24);
+    pub const DEFAULT_LINES_THRESHOLD: u64 = 500;
+
+    /// Monochrome output, as written to pipes and files.
+    pub const OFF: TabColors = TabColors {
+        enabled: false,
+        age_threshold: Self::DEFAULT_AGE_THRESHOLD,
+        lines_threshold: Self::DEFAULT_LINES_THRESHOLD,
+    };
+
+    /// How to paint a row counting `count` items with the given earliest age and line count.
+    pub(crate) fn tone(self, count: u64, earliest_secs: Option<u64>, lines: u64) -> Tone {
+        if count == 0 {
+            Tone::Dim
+        } else if lines > self.lines_threshold
+            || earliest_secs.is_some_and(|secs| secs > self.age_threshold.as_secs())
+        {
+            Tone::Highlight
+        } else {
+            Tone::Plain
+        }
+    }
+}
+
+impl Default for TabColors {
+    fn default() -> Self {
+        TabColors::OFF
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub(crate) enum Tone {
+    Plain,
+    /// Over an age or size threshold.
+    Highlight,
+    /// A failure, e.g. a push or fetch that did not succeed.
+    Failed,
+    /// Nothing to act on.
+    Dim,
+}
+
+impl Tone {
+    fn color(self) -> Option<Color> {
+        match self {
+            Tone::Plain => None,
+            Tone::Highlight => Some(Color::FG_YELLOW),
+            Tone::Failed => Some(Color::FG_RED),
+            Tone::Dim => Some(Color::DIMMED),
+        }
+    }
+}
+
+pub(crate) fn title_color() -> Color {
+    Color::BOLD | Color::FG_CYAN
+}
+
+/// Color the data rows of `table`, which follow its header row, one tone per row.
+pub(crate) fn paint_rows(
+    table: &mut Table,
+    colors: TabColors,
+    tones: impl IntoIterator<Item = Tone>,
+) {
+    if !colors.enabled {
+        return;
+    }
+    for (index, tone) in tones.into_iter().enumerate() {
+        if let Some(color) = tone.color() {
+            table.modify(Rows::one(index + 1), color);
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use tabled::{
+    builder::Builder,
+    settings::{Alignment, Modify, Width, object::Columns},
+};
+
+use crate::{RepoError, ReportData, report::repo_label};
+
+use super::{
+    TabStyle,
+    color::{TabColors, Tone, paint_rows},
+    style::{apply_style, render_titled},
+};
+
+const ERROR_WIDTH: usize = 60;
+
+/// Render repositories that could not be inspected. Callers skip this section when empty.
+pub(crate) fn render(
+    data: &ReportData,
+    style: TabStyle,
+    show_root: bool,
+    colors: TabColors,
+) -> String {
+    let mut builder = Builder::default();
+    if show_root {
+        builder.push_record(["Root", "Repo", "Command", "Exit", "Error"]);
+    } else {
+        builder.push_record(["Repo", "Command", "Exit", "Error"]);
+    }
+
+    for entry in &data.errors {
+        let mut row = vec![
+            repo_label(&entry.repo, entry.worktree.as_deref()),
+            entry.command.clone(),
+            exit_label(entry),
+            entry.stderr.clone(),
+        ];
+        if show_root {
+            row.insert(0, entry.root_display.clone());
+        }
+        builder.push_record(row);
+    }
+
+    let mut table = builder.build();
+    apply_style(&mut table, style);
+    let exit_col = if show_root { 3 } else { 2 };
+    table.with(Modify::new(Columns::new(exit_col..=exit_col)).with(Alignment::right()));
+    table.modify(
+        Columns::new(exit_col + 1..=exit_col + 1),
+        Width::truncate(ERROR_WIDTH).suffix("..."),
+    );
+    paint_rows(&mut table, colors, data.errors.iter().map(|_| Tone::Failed));
+    render_titled(&mut table, "Errors", colors)
+}
+
+pub(crate) fn exit_label(entry: &RepoError) -> String {
+    if entry.timed_out {
+        return "timeout".to_string();
+    }
+    entry
+        .status
+        .map_or_else(|| "n/a".to_string(), |code| code.to_string())
+}
@@ -0,0 +1,67 @@
+use std::time::Duration;
+
+use tabled::{
+    builder::Builder,
+    settings::{Alignment, Modify, object::Columns},
+};
+
+use crate::{FetchStatus, ReportData};
+
+use super::{
+    TabStyle,
+    color::{TabColors, Tone, paint_rows},
+    style::{apply_style, render_titled},
+};
+
+/// Render the outcome of every `--refresh-remotes` fetch. Callers skip this section when empty.
+pub(crate) fn render(
+    data: &ReportData,
+    style: TabStyle,
+    show_root: bool,
+    colors: TabColors,
+) -> String {
+    let mut builder = Builder::default();
+    if show_root {
+        builder.push_record(["Root", "Repo", "Remote", "Status", "Time", "Error"]);
+    } else {
+        builder.push_record(["Repo", "Remote", "Status", "Time", "Error"]);
+    }
+
+    for entry in &data.fetches {
+        let status = match (&entry.status, &entry.source) {
+            (FetchStatus::Shared, Some(source)) => format!("shared ({source})"),
+            (status, _) => status.label().to_string(),
+        };
+        let mut row = vec![
+            entry.repo.clone(),
+            entry.remote.clone(),
+            status,
+            format!(
+                "{:.1}s",
+                Duration::from_millis(entry.duration_ms).as_secs_f64()
+            ),
+            entry.error.clone().unwrap_or_default(),
+        ];
+        if show_root {
+            row.insert(0, entry.root_display.clone());
+        }
+        builder.push_record(row);
+    }
+
+    let mut table = builder.build();
+    apply_style(&mut table, style);
+    let time_col = if show_root { 4 } else { 3 };
+    table.with(Modify::new(Columns::new(time_col..=time_col)).with(Alignment::right()));
+    paint_rows(
+        &mut table,
+        colors,
+        data.fetches.iter().map(|entry| {
+            if entry.error.is_some() {
+                Tone::Failed
+            } else {
+                Tone::Plain
+            }
+        }),
+    );
+    render_titled(&mut table, "Fetch Results", colors)
+}
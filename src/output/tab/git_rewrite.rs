@@ -2,37 +2,29 @@ use std::time::Duration;
 
 use tabled::{
     builder::Builder,
-    settings::{Alignment, Modify, Panel, object::Columns},
+    settings::{Alignment, Modify, object::Columns},
 };
 
 use crate::{GitRewriteEntry, ReportData, humanize_age_public};
 
 use super::{
     TabStyle,
-    style::{apply_style, apply_title_line},
+    color::{TabColors, paint_rows},
+    style::{apply_style, none_table, render_titled},
 };
 
-pub(crate) fn render(data: &ReportData, style: TabStyle) -> String {
+pub(crate) fn render(data: &ReportData, style: TabStyle, colors: TabColors) -> String {
     let entries = data
         .git_rewrite
         .as_ref()
         .expect("git rewrite table requested without data");
 
     if entries.is_empty() {
-        return render_empty_table(style);
+        return none_table("Git Rewrite", style, colors);
     }
 
     let rows = sorted_entries(entries);
-    build_table(&rows, style)
-}
-
-fn render_empty_table(style: TabStyle) -> String {
-    let mut builder = Builder::default();
-    builder.push_record(["(none)"]);
-    let mut table = builder.build();
-    apply_style(&mut table, style);
-    table.with(Panel::header(" Git Rewrite "));
-    table.to_string()
+    build_table(&rows, style, colors)
 }
 
 fn sorted_entries(entries: &[GitRewriteEntry]) -> Vec<GitRewriteEntry> {
@@ -41,7 +33,7 @@ fn sorted_entries(entries: &[GitRewriteEntry]) -> Vec<GitRewriteEntry> {
     rows
 }
 
-fn build_table(rows: &[GitRewriteEntry], style: TabStyle) -> String {
+fn build_table(rows: &[GitRewriteEntry], style: TabStyle, colors: TabColors) -> String {
     let mut builder = Builder::default();
     builder.push_record(["Source", "Target", "Commits", "Earliest", "Latest"]);
     for entry in rows {
@@ -51,8 +43,13 @@ fn build_table(rows: &[GitRewriteEntry], style: TabStyle) -> String {
     let mut table = builder.build();
     apply_style(&mut table, style);
     table.with(Modify::new(Columns::new(2..3)).with(Alignment::right()));
-    apply_title_line(&mut table, "Git Rewrite");
-    table.to_string()
+    paint_rows(
+        &mut table,
+        colors,
+        rows.iter()
+            .map(|entry| colors.tone(entry.commits, entry.earliest_secs, 0)),
+    );
+    render_titled(&mut table, "Git Rewrite", colors)
 }
 
 fn format_entry(entry: &GitRewriteEntry) -> [String; 5] {
@@ -95,7 +92,7 @@ mod tests {
             ..Default::default()
         };
 
-        let output = render(&data, TabStyle::Empty);
+        let output = render(&data, TabStyle::Empty, TabColors::OFF);
 
         assert!(output.contains("source_dir:feature"));
         assert!(output.contains("target_dir:main"));
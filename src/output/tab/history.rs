@@ -0,0 +1,162 @@
+use std::fmt::Write;
+use std::time::{Duration, UNIX_EPOCH};
+
+use chrono::{DateTime, Local};
+use tabled::{
+    builder::Builder,
+    settings::{Alignment, Modify, object::Columns},
+};
+
+use crate::history::{RepoChange, RepoState, RepoTimeline, streak_start};
+
+use super::{
+    TabStyle,
+    color::{TabColors, Tone, paint_rows},
+    style::{apply_style, none_table, render_titled},
+};
+
+/// Render the repositories that changed between two snapshots.
+#[must_use]
+pub fn format_history_diff(
+    changes: &[RepoChange],
+    (from, to): (u64, u64),
+    style: TabStyle,
+    colors: TabColors,
+) -> String {
+    let range = format!("{} -> {}", format_time(from), format_time(to));
+    if changes.is_empty() {
+        return format!("{range}\n{}", none_table("Changes", style, colors));
+    }
+    let show_root = changes
+        .iter()
+        .any(|change| change.state().root != changes[0].state().root);
+
+    let mut builder = Builder::default();
+    if show_root {
+        builder.push_record(["Root", "Repo", "Change", "Details"]);
+    } else {
+        builder.push_record(["Repo", "Change", "Details"]);
+    }
+    for change in changes {
+        let state = change.state();
+        let details = match change {
+            RepoChange::Changed { changes, .. } => changes.join(", "),
+            RepoChange::Appeared(_) | RepoChange::Disappeared(_) => String::new(),
+        };
+        let mut row = vec![state.repo.clone(), change.label().to_string(), details];
+        if show_root {
+            row.insert(0, state.root_display.clone());
+        }
+        builder.push_record(row);
+    }
+
+    let mut table = builder.build();
+    apply_style(&mut table, style);
+    paint_rows(
+        &mut table,
+        colors,
+        changes.iter().map(|change| match change {
+            RepoChange::Appeared(_) | RepoChange::Changed { .. } => Tone::Plain,
+            RepoChange::Disappeared(_) => Tone::Dim,
+        }),
+    );
+    format!("{range}\n{}", render_titled(&mut table, "Changes", colors))
+}
+
+/// Render a repository's counters across snapshots, one table per root it was found under,
+/// each followed by how long it has been dirty or had unpushed commits.
+#[must_use]
+pub fn format_history_timeline(
+    repo: &str,
+    timelines: &[RepoTimeline<'_>],
+    style: TabStyle,
+    colors: TabColors,
+) -> String {
+    let title = format!("History of {repo}");
+    if timelines.is_empty() {
+        return none_table(&title, style, colors);
+    }
+    timelines
+        .iter()
+        .map(|timeline| {
+            let title = if timelines.len() > 1 {
+                format!("{title} in {}", timeline.root_display)
+            } else {
+                title.clone()
+            };
+            format_timeline(&title, &timeline.entries, style, colors)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn format_timeline(
+    title: &str,
+    timeline: &[(u64, Option<&RepoState>)],
+    style: TabStyle,
+    colors: TabColors,
+) -> String {
+    let mut builder = Builder::default();
+    builder.push_record([
+        "Time",
+        "Uncommitted",
+        "Untracked",
+        "Staged",
+        "Unpushed",
+        "Behind",
+        "Unpublished",
+        "Stashes",
+        "Errors",
+    ]);
+    for (timestamp, state) in timeline {
+        let mut row = vec![format_time(*timestamp)];
+        match state {
+            Some(state) => row.extend([
+                state.uncommitted_files.to_string(),
+                state.untracked.to_string(),
+                state.staged_files.to_string(),
+                state.unpushed.to_string(),
+                state.behind.to_string(),
+                state.unpublished.to_string(),
+                state.stashes.to_string(),
+                state.errors.to_string(),
+            ]),
+            None => row.extend(std::iter::repeat_n("-".to_string(), 8)),
+        }
+        builder.push_record(row);
+    }
+
+    let mut table = builder.build();
+    apply_style(&mut table, style);
+    table.with(Modify::new(Columns::new(1..)).with(Alignment::right()));
+    // Snapshots where the repository was missing or had nothing to do are dimmed.
+    paint_rows(
+        &mut table,
+        colors,
+        timeline.iter().map(|(_, state)| {
+            let active = state.is_some_and(|s| {
+                s.is_dirty() || s.unpushed + s.behind + s.unpublished + s.stashes + s.errors > 0
+            });
+            if active { Tone::Plain } else { Tone::Dim }
+        }),
+    );
+    let mut out = render_titled(&mut table, title, colors);
+    for (label, start) in [
+        ("dirty", streak_start(timeline, RepoState::is_dirty)),
+        (
+            "unpushed commits",
+            streak_start(timeline, |s| s.unpushed > 0),
+        ),
+    ] {
+        if let Some(start) = start {
+            let _ = write!(out, "\n{label} since {}", format_time(start));
+        }
+    }
+    out
+}
+
+fn format_time(timestamp: u64) -> String {
+    DateTime::<Local>::from(UNIX_EPOCH + Duration::from_secs(timestamp))
+        .format("%Y-%m-%d %H:%M")
+        .to_string()
+}
@@ -4,12 +4,26 @@ use clap::ValueEnum;
 
 use crate::ReportData;
 
+mod color;
+mod errors;
+mod fetches;
 mod git_rewrite;
+mod history;
 mod other;
+mod pullable;
+mod push;
 mod pushable;
 mod staged;
+mod stashes;
 mod style;
+mod submodules;
 mod uncommitted;
+mod unpublished;
+
+pub use color::TabColors;
+pub(crate) use errors::exit_label;
+pub use history::{format_history_diff, format_history_timeline};
+pub use push::{format_push_plan, format_push_results};
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, ValueEnum)]
 pub enum TabStyle {
@@ -29,7 +43,12 @@ pub enum TabStyle {
 }
 
 #[must_use]
-pub fn format_tab(data: &ReportData, style: TabStyle, omit_non_actionable: bool) -> String {
+pub fn format_tab(
+    data: &ReportData,
+    style: TabStyle,
+    omit_non_actionable: bool,
+    colors: TabColors,
+) -> String {
     let render_data = if omit_non_actionable {
         apply_omit_filter(data)
     } else {
@@ -38,20 +57,33 @@ pub fn format_tab(data: &ReportData, style: TabStyle, omit_non_actionable: bool)
     let render_ref: &ReportData = render_data.as_ref();
 
     let show_root = render_ref.multi_root;
-    let mut sections = Vec::with_capacity(4);
-    sections.push(uncommitted::render(render_ref, style, show_root));
-    sections.push(staged::render(render_ref, style, show_root));
-    sections.push(pushable::render(render_ref, style, show_root));
+    let mut sections = Vec::with_capacity(8);
+    sections.push(uncommitted::render(render_ref, style, show_root, colors));
+    sections.push(staged::render(render_ref, style, show_root, colors));
+    sections.push(pushable::render(render_ref, style, show_root, colors));
+    sections.push(pullable::render(render_ref, style, show_root, colors));
+    sections.push(unpublished::render(render_ref, style, show_root, colors));
+    sections.push(stashes::render(render_ref, style, show_root, colors));
+    if !render_ref.submodules.is_empty() {
+        sections.push(submodules::render(render_ref, style, show_root, colors));
+    }
+    if !render_ref.fetches.is_empty() {
+        sections.push(fetches::render(render_ref, style, show_root, colors));
+    }
+    if !render_ref.errors.is_empty() {
+        sections.push(errors::render(render_ref, style, show_root, colors));
+    }
     if render_ref.git_rewrite.is_some() {
-        sections.push(git_rewrite::render(render_ref, style));
+        sections.push(git_rewrite::render(render_ref, style, colors));
     }
     if render_ref.untracked_enabled && !omit_non_actionable {
-        sections.push(other::render(render_ref, style));
+        sections.push(other::render(render_ref, style, colors));
     }
     sections.join("\n")
 }
 
-fn apply_omit_filter(data: &ReportData) -> Cow<'_, ReportData> {
+/// `data` without rows `--omit-non-actionable` hides: branches and repos with no commits.
+pub(crate) fn apply_omit_filter(data: &ReportData) -> Cow<'_, ReportData> {
     let mut filtered = data.clone();
 
     filtered.pushable.retain(|entry| entry.revs > 0);
@@ -81,6 +113,7 @@ mod tests {
             latest_secs: None,
             root_display: "~/src".to_string(),
             root_full: "/tmp/src".to_string(),
+            path: std::path::Path::new("/tmp/src").join(repo),
         }
     }
 
@@ -118,7 +151,7 @@ mod tests {
             ..Default::default()
         };
 
-        let output = format_tab(&data, TabStyle::Empty, true);
+        let output = format_tab(&data, TabStyle::Empty, true, TabColors::OFF);
 
         assert!(output.contains("pushable-keep"));
         assert!(!output.contains("pushable-drop"));
@@ -126,6 +159,41 @@ mod tests {
         assert!(!output.contains("rewrite-drop-src:main"));
     }
 
+    #[test]
+    fn format_tab_colors_titles_and_rows_without_changing_layout() {
+        let mut stale = pushable_entry("pushable-stale", 3);
+        stale.earliest_secs = Some(30 * 24 * 60 * 60);
+        let data = ReportData {
+            pushable: vec![stale, pushable_entry("pushable-empty", 0)],
+            ..Default::default()
+        };
+        let colors = TabColors {
+            enabled: true,
+            ..TabColors::OFF
+        };
+
+        let plain = format_tab(&data, TabStyle::Ascii, false, TabColors::OFF);
+        let colored = format_tab(&data, TabStyle::Ascii, false, colors);
+        assert!(!plain.contains('\x1b'));
+        // The title in the top border is painted as one span.
+        assert!(colored.contains("+\x1b[1m\x1b[36m Pushable Commits \x1b[22m\x1b[39m-"));
+        let row = |name| colored.lines().find(|line| line.contains(name)).unwrap();
+        assert!(row("pushable-stale").contains("\x1b[33mpushable-stale"));
+        assert!(row("pushable-empty").contains("\x1b[2mpushable-empty"));
+        assert!(row("(none)").contains("\x1b[2m(none)"));
+
+        let mut stripped = String::new();
+        let mut chars = colored.chars();
+        while let Some(c) = chars.next() {
+            if c == '\x1b' {
+                chars.by_ref().find(|c| *c == 'm');
+            } else {
+                stripped.push(c);
+            }
+        }
+        assert_eq!(stripped, plain);
+    }
+
     #[test]
     fn format_tab_renders_other_repos_only_when_not_omitting() {
         let data = ReportData {
@@ -165,11 +233,11 @@ mod tests {
             ..Default::default()
         };
 
-        let omitted = format_tab(&data, TabStyle::Empty, true);
+        let omitted = format_tab(&data, TabStyle::Empty, true, TabColors::OFF);
         assert!(!omitted.contains("Other Repos"));
         assert!(!omitted.contains("ignored-repo:main"));
 
-        let rendered = format_tab(&data, TabStyle::Empty, false);
+        let rendered = format_tab(&data, TabStyle::Empty, false, TabColors::OFF);
         assert!(rendered.contains("ignored-repo:main"));
         assert!(rendered.contains("missing-repo:dev"));
         assert!(rendered.contains("ignored"));
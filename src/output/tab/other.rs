@@ -2,24 +2,20 @@ use std::time::Duration;
 
 use tabled::{
     builder::Builder,
-    settings::{Alignment, Modify, Panel, object::Columns},
+    settings::{Alignment, Modify, object::Columns},
 };
 
 use crate::{ReportData, humanize_age_public, types::UntrackedReason};
 
 use super::{
     TabStyle,
-    style::{apply_style, apply_title_line},
+    color::{TabColors, Tone, paint_rows},
+    style::{apply_style, none_table, render_titled},
 };
 
-pub(crate) fn render(data: &ReportData, style: TabStyle) -> String {
+pub(crate) fn render(data: &ReportData, style: TabStyle, colors: TabColors) -> String {
     if data.untracked_repos.is_empty() {
-        let mut builder = Builder::default();
-        builder.push_record(["(none)"]);
-        let mut table = builder.build();
-        apply_style(&mut table, style);
-        table.with(Panel::header(" Other Repos "));
-        return table.to_string();
+        return none_table("Other Repos", style, colors);
     }
 
     let mut builder = Builder::default();
@@ -54,6 +50,15 @@ pub(crate) fn render(data: &ReportData, style: TabStyle) -> String {
     let mut table = builder.build();
     apply_style(&mut table, style);
     table.with(Modify::new(Columns::new(2..3)).with(Alignment::right()));
-    apply_title_line(&mut table, "Other Repos");
-    table.to_string()
+    // A missing repository has no commit count; it still needs attention.
+    paint_rows(
+        &mut table,
+        colors,
+        data.untracked_repos.iter().map(|entry| {
+            entry.revs.map_or(Tone::Failed, |revs| {
+                colors.tone(revs, entry.earliest_secs, 0)
+            })
+        }),
+    );
+    render_titled(&mut table, "Other Repos", colors)
 }
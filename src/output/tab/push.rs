@@ -0,0 +1,115 @@
+use tabled::{
+    builder::Builder,
+    settings::{Alignment, Modify, object::Columns},
+};
+
+use crate::actions::{PushResult, PushTarget};
+
+use super::{
+    TabStyle,
+    color::{TabColors, Tone, paint_rows},
+    style::{apply_style, none_table, render_titled},
+};
+
+/// Render the branches a push will publish.
+#[must_use]
+pub fn format_push_plan(targets: &[PushTarget], style: TabStyle, colors: TabColors) -> String {
+    let title = "Push Plan";
+    if targets.is_empty() {
+        return none_table(title, style, colors);
+    }
+    let show_root = multi_root(targets.iter());
+
+    let mut builder = Builder::default();
+    let mut header = vec!["Repo", "Branch", "Upstream", "Commits"];
+    if show_root {
+        header.insert(0, "Root");
+    }
+    builder.push_record(header);
+    for target in targets {
+        let mut row = vec![
+            target.repo.clone(),
+            target.branch.clone(),
+            target.upstream(),
+            target.revs.to_string(),
+        ];
+        if show_root {
+            row.insert(0, target.root_display.clone());
+        }
+        builder.push_record(row);
+    }
+
+    let mut table = builder.build();
+    apply_style(&mut table, style);
+    let commits = if show_root { 4 } else { 3 };
+    table.with(Modify::new(Columns::new(commits..=commits)).with(Alignment::right()));
+    paint_rows(
+        &mut table,
+        colors,
+        targets
+            .iter()
+            .map(|target| colors.tone(target.revs, None, 0)),
+    );
+    render_titled(&mut table, title, colors)
+}
+
+/// Render the outcome of each push, failures with their error excerpt.
+#[must_use]
+pub fn format_push_results(results: &[PushResult], style: TabStyle, colors: TabColors) -> String {
+    let title = "Push Results";
+    if results.is_empty() {
+        return none_table(title, style, colors);
+    }
+    let show_root = multi_root(results.iter().map(|result| &result.target));
+
+    let mut builder = Builder::default();
+    let mut header = vec!["Repo", "Branch", "Upstream", "Status", "Time", "Error"];
+    if show_root {
+        header.insert(0, "Root");
+    }
+    builder.push_record(header);
+    for result in results {
+        let target = &result.target;
+        let status = if result.error.is_some() {
+            "failed"
+        } else {
+            "pushed"
+        };
+        let mut row = vec![
+            target.repo.clone(),
+            target.branch.clone(),
+            target.upstream(),
+            status.to_string(),
+            format!("{:.1}s", result.duration.as_secs_f64()),
+            result.error.clone().unwrap_or_default(),
+        ];
+        if show_root {
+            row.insert(0, target.root_display.clone());
+        }
+        builder.push_record(row);
+    }
+
+    let mut table = builder.build();
+    apply_style(&mut table, style);
+    let time = if show_root { 5 } else { 4 };
+    table.with(Modify::new(Columns::new(time..=time)).with(Alignment::right()));
+    paint_rows(
+        &mut table,
+        colors,
+        results.iter().map(|result| {
+            if result.error.is_some() {
+                Tone::Failed
+            } else {
+                Tone::Plain
+            }
+        }),
+    );
+    render_titled(&mut table, title, colors)
+}
+
+fn multi_root<'a>(mut targets: impl Iterator<Item = &'a PushTarget>) -> bool {
+    let Some(first) = targets.next() else {
+        return false;
+    };
+    targets.any(|target| target.root_full != first.root_full)
+}
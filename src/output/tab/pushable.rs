@@ -2,32 +2,29 @@ use std::time::Duration;
 
 use tabled::{
     builder::Builder,
-    settings::{Alignment, Modify, Panel, object::Columns},
+    settings::{Alignment, Modify, object::Columns},
 };
 
 use crate::{PushableEntry, ReportData, humanize_age_public};
 
 use super::{
     TabStyle,
-    style::{apply_style, apply_title_line},
+    color::{TabColors, paint_rows},
+    style::{apply_style, none_table, render_titled},
 };
 
-pub(crate) fn render(data: &ReportData, style: TabStyle, show_root: bool) -> String {
+pub(crate) fn render(
+    data: &ReportData,
+    style: TabStyle,
+    show_root: bool,
+    colors: TabColors,
+) -> String {
     if data.pushable.is_empty() {
-        return render_empty(style);
+        return none_table("Pushable Commits", style, colors);
     }
 
     let rows = sorted_rows(data, show_root);
-    build_table(rows, style, show_root)
-}
-
-fn render_empty(style: TabStyle) -> String {
-    let mut builder = Builder::default();
-    builder.push_record(["(none)"]);
-    let mut table = builder.build();
-    apply_style(&mut table, style);
-    table.with(Panel::header(" Pushable Commits "));
-    table.to_string()
+    build_table(&rows, style, show_root, colors)
 }
 
 fn sorted_rows(data: &ReportData, show_root: bool) -> Vec<PushableEntry> {
@@ -40,11 +37,16 @@ fn sorted_rows(data: &ReportData, show_root: bool) -> Vec<PushableEntry> {
     rows
 }
 
-fn build_table(rows: Vec<PushableEntry>, style: TabStyle, show_root: bool) -> String {
+fn build_table(
+    rows: &[PushableEntry],
+    style: TabStyle,
+    show_root: bool,
+    colors: TabColors,
+) -> String {
     let mut builder = Builder::default();
     push_header(&mut builder, show_root);
     for entry in rows {
-        builder.push_record(row_values(&entry, show_root));
+        builder.push_record(row_values(entry, show_root));
     }
 
     let mut table = builder.build();
@@ -56,8 +58,13 @@ fn build_table(rows: Vec<PushableEntry>, style: TabStyle, show_root: bool) -> St
         // Columns: 0 Repo, 1 Branch, 2 Commits, 3 Earliest, 4 Latest
         table.with(Modify::new(Columns::new(2..3)).with(Alignment::right()));
     }
-    apply_title_line(&mut table, "Pushable Commits");
-    table.to_string()
+    paint_rows(
+        &mut table,
+        colors,
+        rows.iter()
+            .map(|entry| colors.tone(entry.revs, entry.earliest_secs, 0)),
+    );
+    render_titled(&mut table, "Pushable Commits", colors)
 }
 
 fn push_header(builder: &mut Builder, show_root: bool) {
@@ -0,0 +1,69 @@
+use std::time::Duration;
+
+use tabled::{
+    builder::Builder,
+    settings::{Width, object::Columns},
+};
+
+use crate::{ReportData, humanize_age_public};
+
+use super::{
+    TabStyle,
+    color::{TabColors, paint_rows},
+    style::{apply_style, none_table, render_titled},
+};
+
+const MESSAGE_WIDTH: usize = 50;
+
+pub(crate) fn render(
+    data: &ReportData,
+    style: TabStyle,
+    show_root: bool,
+    colors: TabColors,
+) -> String {
+    if data.stashes.is_empty() {
+        return none_table("Stashes", style, colors);
+    }
+
+    let mut builder = Builder::default();
+    if show_root {
+        builder.push_record(["Root", "Repo", "Stash", "Branch", "Message", "Age"]);
+    } else {
+        builder.push_record(["Repo", "Stash", "Branch", "Message", "Age"]);
+    }
+
+    for entry in &data.stashes {
+        let age = entry.age_secs.map_or_else(
+            || "n/a".to_string(),
+            |secs| humanize_age_public(Duration::from_secs(secs)),
+        );
+        let mut row = vec![
+            entry.repo.clone(),
+            entry.stash.clone(),
+            entry.branch.clone(),
+            entry.message.clone(),
+            age,
+        ];
+        if show_root {
+            row.insert(0, entry.root_display.clone());
+        }
+        builder.push_record(row);
+    }
+
+    let mut table = builder.build();
+    apply_style(&mut table, style);
+    // Message is the fourth column from the right edge regardless of the Root column.
+    let message_col = if show_root { 4 } else { 3 };
+    table.modify(
+        Columns::new(message_col..=message_col),
+        Width::truncate(MESSAGE_WIDTH).suffix("..."),
+    );
+    paint_rows(
+        &mut table,
+        colors,
+        data.stashes
+            .iter()
+            .map(|entry| colors.tone(1, entry.age_secs, 0)),
+    );
+    render_titled(&mut table, "Stashes", colors)
+}
@@ -1,9 +1,13 @@
 use tabled::{
     Table,
-    settings::{Style, object::Rows, style::LineText},
+    builder::Builder,
+    settings::{Color, Panel, Style, object::Rows, style::LineText},
 };
 
-use super::TabStyle;
+use super::{
+    TabStyle,
+    color::{TabColors, title_color},
+};
 
 pub(crate) fn apply_style(table: &mut Table, style: TabStyle) {
     match style {
@@ -23,6 +27,35 @@ pub(crate) fn apply_style(table: &mut Table, style: TabStyle) {
     };
 }
 
-pub(crate) fn apply_title_line(table: &mut Table, title: &str) {
-    table.with(LineText::new(format!(" {title} "), Rows::first()).offset(1));
+/// Render `table` with `title` set into its top border.
+pub(crate) fn render_titled(table: &mut Table, title: &str, colors: TabColors) -> String {
+    let label = format!(" {title} ");
+    table.with(LineText::new(label.clone(), Rows::first()).offset(1));
+    let out = table.to_string();
+    if !colors.enabled {
+        return out;
+    }
+    // `LineText::color` would wrap every character in its own escape codes; paint the title
+    // as one span instead.
+    let (first, rest) = out.split_once('\n').unwrap_or((&out, ""));
+    let first = first.replacen(&label, &title_color().colorize(&label), 1);
+    if rest.is_empty() {
+        first
+    } else {
+        format!("{first}\n{rest}")
+    }
+}
+
+/// A table saying a section is empty: its title as a header above a dimmed `(none)`.
+pub(crate) fn none_table(title: &str, style: TabStyle, colors: TabColors) -> String {
+    let mut builder = Builder::default();
+    builder.push_record(["(none)"]);
+    let mut table = builder.build();
+    apply_style(&mut table, style);
+    table.with(Panel::header(format!(" {title} ")));
+    if colors.enabled {
+        table.modify(Rows::first(), title_color());
+        table.modify(Rows::one(1), Color::DIMMED);
+    }
+    table.to_string()
 }
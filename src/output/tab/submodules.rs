@@ -0,0 +1,53 @@
+use tabled::{
+    builder::Builder,
+    settings::{Alignment, Modify, object::Columns},
+};
+
+use crate::ReportData;
+
+use super::{
+    TabStyle,
+    color::{TabColors, paint_rows},
+    style::{apply_style, render_titled},
+};
+
+/// Render submodules whose recorded commit is not pushed. Callers skip this section when empty.
+pub(crate) fn render(
+    data: &ReportData,
+    style: TabStyle,
+    show_root: bool,
+    colors: TabColors,
+) -> String {
+    let mut builder = Builder::default();
+    if show_root {
+        builder.push_record(["Root", "Repo", "Submodule", "Commit", "Revs"]);
+    } else {
+        builder.push_record(["Repo", "Submodule", "Commit", "Revs"]);
+    }
+
+    for entry in &data.submodules {
+        let mut row = vec![
+            entry.repo.clone(),
+            entry.path.clone(),
+            entry.commit.clone(),
+            entry.revs.to_string(),
+        ];
+        if show_root {
+            row.insert(0, entry.root_display.clone());
+        }
+        builder.push_record(row);
+    }
+
+    let mut table = builder.build();
+    apply_style(&mut table, style);
+    let revs_col = if show_root { 4 } else { 3 };
+    table.with(Modify::new(Columns::new(revs_col..=revs_col)).with(Alignment::right()));
+    paint_rows(
+        &mut table,
+        colors,
+        data.submodules
+            .iter()
+            .map(|entry| colors.tone(entry.revs, None, 0)),
+    );
+    render_titled(&mut table, "Submodule Commits Not Pushed", colors)
+}
@@ -1,23 +1,24 @@
 use tabled::{
     builder::Builder,
-    settings::{Alignment, Modify, Panel, object::Columns},
+    settings::{Alignment, Modify, object::Columns},
 };
 
-use crate::ReportData;
+use crate::{ReportData, report::repo_label};
 
 use super::{
     TabStyle,
-    style::{apply_style, apply_title_line},
+    color::{TabColors, paint_rows},
+    style::{apply_style, none_table, render_titled},
 };
 
-pub(crate) fn render(data: &ReportData, style: TabStyle, show_root: bool) -> String {
+pub(crate) fn render(
+    data: &ReportData,
+    style: TabStyle,
+    show_root: bool,
+    colors: TabColors,
+) -> String {
     if data.uncommitted.is_empty() {
-        let mut builder = Builder::default();
-        builder.push_record(["(none)"]);
-        let mut table = builder.build();
-        apply_style(&mut table, style);
-        table.with(Panel::header(" Uncommitted Changes "));
-        return table.to_string();
+        return none_table("Uncommitted Changes", style, colors);
     }
 
     let mut builder = Builder::default();
@@ -39,7 +40,7 @@ pub(crate) fn render(data: &ReportData, style: TabStyle, show_root: bool) -> Str
         if show_root {
             builder.push_record([
                 entry.root_display.clone(),
-                entry.repo.clone(),
+                repo_label(&entry.repo, entry.worktree.as_deref()),
                 entry.branch.clone(),
                 entry.upstream.clone().unwrap_or_default(),
                 entry.lines.to_string(),
@@ -48,7 +49,7 @@ pub(crate) fn render(data: &ReportData, style: TabStyle, show_root: bool) -> Str
             ]);
         } else {
             builder.push_record([
-                entry.repo.clone(),
+                repo_label(&entry.repo, entry.worktree.as_deref()),
                 entry.branch.clone(),
                 entry.upstream.clone().unwrap_or_default(),
                 entry.lines.to_string(),
@@ -71,6 +72,12 @@ pub(crate) fn render(data: &ReportData, style: TabStyle, show_root: bool) -> Str
         table.with(Modify::new(Columns::new(4..5)).with(Alignment::right()));
         table.with(Modify::new(Columns::new(5..6)).with(Alignment::right()));
     }
-    apply_title_line(&mut table, "Uncommitted Changes");
-    table.to_string()
+    paint_rows(
+        &mut table,
+        colors,
+        data.uncommitted
+            .iter()
+            .map(|entry| colors.tone(entry.files + entry.untracked, None, entry.lines)),
+    );
+    render_titled(&mut table, "Uncommitted Changes", colors)
 }
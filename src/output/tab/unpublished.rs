@@ -0,0 +1,100 @@
+use std::time::Duration;
+
+use tabled::{
+    builder::Builder,
+    settings::{Alignment, Modify, object::Columns},
+};
+
+use crate::{ReportData, UnpublishedEntry, humanize_age_public};
+
+use super::{
+    TabStyle,
+    color::{TabColors, paint_rows},
+    style::{apply_style, none_table, render_titled},
+};
+
+pub(crate) fn render(
+    data: &ReportData,
+    style: TabStyle,
+    show_root: bool,
+    colors: TabColors,
+) -> String {
+    if data.unpublished.is_empty() {
+        return none_table("Unpublished Branches", style, colors);
+    }
+
+    let rows = sorted_rows(data, show_root);
+    build_table(&rows, style, show_root, colors)
+}
+
+fn sorted_rows(data: &ReportData, show_root: bool) -> Vec<UnpublishedEntry> {
+    let mut rows = data.unpublished.clone();
+    rows.sort_by(|a, b| {
+        let left_root = if show_root { &a.root_display } else { "" };
+        let right_root = if show_root { &b.root_display } else { "" };
+        (left_root, &a.repo, &a.branch).cmp(&(right_root, &b.repo, &b.branch))
+    });
+    rows
+}
+
+fn build_table(
+    rows: &[UnpublishedEntry],
+    style: TabStyle,
+    show_root: bool,
+    colors: TabColors,
+) -> String {
+    let mut builder = Builder::default();
+    push_header(&mut builder, show_root);
+    for entry in rows {
+        builder.push_record(row_values(entry, show_root));
+    }
+
+    let mut table = builder.build();
+    apply_style(&mut table, style);
+    if show_root {
+        // Columns: 0 Root, 1 Repo, 2 Branch, 3 Base, 4 Commits, 5 Earliest, 6 Latest
+        table.with(Modify::new(Columns::new(4..5)).with(Alignment::right()));
+    } else {
+        // Columns: 0 Repo, 1 Branch, 2 Base, 3 Commits, 4 Earliest, 5 Latest
+        table.with(Modify::new(Columns::new(3..4)).with(Alignment::right()));
+    }
+    paint_rows(
+        &mut table,
+        colors,
+        rows.iter()
+            .map(|entry| colors.tone(entry.revs, entry.earliest_secs, 0)),
+    );
+    render_titled(&mut table, "Unpublished Branches", colors)
+}
+
+fn push_header(builder: &mut Builder, show_root: bool) {
+    if show_root {
+        builder.push_record([
+            "Root", "Repo", "Branch", "Base", "Commits", "Earliest", "Latest",
+        ]);
+    } else {
+        builder.push_record(["Repo", "Branch", "Base", "Commits", "Earliest", "Latest"]);
+    }
+}
+
+fn row_values(entry: &UnpublishedEntry, show_root: bool) -> Vec<String> {
+    let mut row = vec![
+        entry.repo.clone(),
+        entry.branch.clone(),
+        entry.base.clone().unwrap_or_else(|| "n/a".to_string()),
+        entry.revs.to_string(),
+        format_age(entry.earliest_secs),
+        format_age(entry.latest_secs),
+    ];
+    if show_root {
+        row.insert(0, entry.root_display.clone());
+    }
+    row
+}
+
+fn format_age(value: Option<u64>) -> String {
+    value.map_or_else(
+        || "n/a".to_string(),
+        |secs| humanize_age_public(Duration::from_secs(secs)),
+    )
+}
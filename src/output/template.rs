@@ -0,0 +1,235 @@
+//! User-defined report output rendered with `minijinja` templates.
+//!
+//! A template sees the same document `--output json` writes: `uncommitted`, `pushable`,
+//! `generated_at`, `host` and every other top-level key. On top of the Jinja built-ins it has
+//! three filters:
+//!
+//! - `humanize_age` turns seconds into the ages the tables show (`3.2 days`, `n/a` for none);
+//! - `pluralize(word, plural)` picks `word` for a count of 1 and `plural` (default `word`+`s`)
+//!   otherwise, e.g. `{{ n }} {{ n | pluralize("repo") }}`;
+//! - `color(name)` wraps text in an ANSI color (`red`, `green`, `yellow`, `blue`, `magenta`,
+//!   `cyan`, `bold` or `dim`) when colors are on, and leaves it alone otherwise.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use minijinja::{Environment, Error, ErrorKind, Value};
+
+use crate::{ReportData, humanize_age_public};
+
+use super::json::JsonReportRef;
+
+/// Templates shipped with the binary, by the name `--template` accepts.
+pub const BUILTIN_TEMPLATES: &[(&str, &str)] = &[
+    ("slack", include_str!("templates/slack.j2")),
+    ("markdown", include_str!("templates/markdown.j2")),
+    ("email", include_str!("templates/email.j2")),
+    ("prompt", include_str!("templates/prompt.j2")),
+];
+
+/// A template to render a report with: a built-in or the contents of a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReportTemplate {
+    name: String,
+    source: String,
+}
+
+impl ReportTemplate {
+    /// The built-in template called `spec`, or else the template file at `spec`.
+    ///
+    /// # Errors
+    /// Returns an error if `spec` names no built-in and the file cannot be read.
+    pub fn load(spec: &str) -> Result<Self, TemplateError> {
+        if let Some((name, source)) = BUILTIN_TEMPLATES.iter().find(|(name, _)| *name == spec) {
+            return Ok(ReportTemplate {
+                name: (*name).to_string(),
+                source: (*source).to_string(),
+            });
+        }
+        let path = Path::new(spec);
+        let source = std::fs::read_to_string(path).map_err(|source| TemplateError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        Ok(ReportTemplate {
+            name: path.file_name().map_or_else(
+                || spec.to_string(),
+                |name| name.to_string_lossy().to_string(),
+            ),
+            source,
+        })
+    }
+
+    /// Render `data`; `color` decides whether the `color` filter emits escape codes. Trailing
+    /// newlines are dropped, so the caller decides how the output ends.
+    ///
+    /// # Errors
+    /// Returns the template's syntax error or the first error raised while rendering.
+    pub fn render(&self, data: &ReportData, color: bool) -> Result<String, TemplateError> {
+        let mut env = Environment::new();
+        env.set_trim_blocks(true);
+        env.set_lstrip_blocks(true);
+        env.add_filter("humanize_age", humanize_age);
+        env.add_filter("pluralize", pluralize);
+        env.add_filter("color", move |text: String, name: &str| {
+            paint(&text, name, color)
+        });
+        env.add_template(&self.name, &self.source)
+            .map_err(TemplateError::Render)?;
+        env.get_template(&self.name)
+            .and_then(|template| template.render(Value::from_serialize(JsonReportRef::new(data))))
+            .map(|out| out.trim_end_matches('\n').to_string())
+            .map_err(TemplateError::Render)
+    }
+}
+
+fn humanize_age(secs: Option<u64>) -> String {
+    secs.map_or_else(
+        || "n/a".to_string(),
+        |secs| humanize_age_public(Duration::from_secs(secs)),
+    )
+}
+
+fn pluralize(count: u64, singular: &str, plural: Option<&str>) -> String {
+    match (count, plural) {
+        (1, _) => singular.to_string(),
+        (_, Some(plural)) => plural.to_string(),
+        (_, None) => format!("{singular}s"),
+    }
+}
+
+fn paint(text: &str, name: &str, enabled: bool) -> Result<String, Error> {
+    let code = match name {
+        "bold" => "1",
+        "dim" => "2",
+        "red" => "31",
+        "green" => "32",
+        "yellow" => "33",
+        "blue" => "34",
+        "magenta" => "35",
+        "cyan" => "36",
+        _ => {
+            return Err(Error::new(
+                ErrorKind::InvalidOperation,
+                format!("unknown color '{name}'"),
+            ));
+        }
+    };
+    Ok(if enabled {
+        format!("\x1b[{code}m{text}\x1b[0m")
+    } else {
+        text.to_string()
+    })
+}
+
+#[derive(Debug)]
+pub enum TemplateError {
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    Render(Error),
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TemplateError::Io { path, source } => {
+                let names: Vec<&str> = BUILTIN_TEMPLATES.iter().map(|(name, _)| *name).collect();
+                write!(
+                    f,
+                    "failed to read template {}: {source} (built-in templates: {})",
+                    path.display(),
+                    names.join(", ")
+                )
+            }
+            TemplateError::Render(source) => write!(f, "template error: {source:#}"),
+        }
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{PushableEntry, UncommittedEntry};
+
+    fn data() -> ReportData {
+        let mut data = ReportData::default();
+        data.meta.host = "box".to_string();
+        data.meta.generated_at = "2026-01-02T03:04:05Z".to_string();
+        data.uncommitted.push(UncommittedEntry {
+            repo: "app".to_string(),
+            branch: "main".to_string(),
+            upstream: None,
+            worktree: None,
+            lines: 1,
+            files: 3,
+            untracked: 0,
+            root_display: "~/src".to_string(),
+            root_full: "/home/me/src".to_string(),
+            path: "/home/me/src/app".into(),
+        });
+        data.pushable.push(PushableEntry {
+            repo: "lib".to_string(),
+            branch: "dev".to_string(),
+            revs: 2,
+            earliest_secs: Some(7200),
+            latest_secs: None,
+            root_display: "~/src".to_string(),
+            root_full: "/home/me/src".to_string(),
+            path: "/home/me/src/lib".into(),
+        });
+        data
+    }
+
+    #[test]
+    fn filters_humanize_pluralize_and_color() -> Result<(), TemplateError> {
+        let template = ReportTemplate {
+            name: "test".to_string(),
+            source: "{% for e in uncommitted %}{{ e.files }} {{ e.files | pluralize('file') }} \
+                     {{ e.lines }} {{ e.lines | pluralize('line') }}{% endfor %}; \
+                     {{ pushable[0].earliest_secs | humanize_age }}/\
+                     {{ pushable[0].latest_secs | humanize_age }}; \
+                     {{ host | color('red') }}"
+                .to_string(),
+        };
+        assert_eq!(
+            template.render(&data(), false)?,
+            "3 files 1 line; 2.0 hr/n/a; box"
+        );
+        assert!(
+            template
+                .render(&data(), true)?
+                .ends_with("\x1b[31mbox\x1b[0m")
+        );
+
+        let unknown = ReportTemplate {
+            name: "bad".to_string(),
+            source: "{{ host | color('plaid') }}".to_string(),
+        };
+        assert!(matches!(
+            unknown.render(&data(), false),
+            Err(TemplateError::Render(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn builtin_templates_render_a_report() -> Result<(), TemplateError> {
+        for (name, _) in BUILTIN_TEMPLATES {
+            let out = ReportTemplate::load(name)?.render(&data(), false)?;
+            let empty = ReportTemplate::load(name)?.render(&ReportData::default(), false)?;
+            assert_ne!(out, empty, "{name}");
+            if *name == "prompt" {
+                assert_eq!(out, "1 dirty 2 to push");
+                assert_eq!(empty, "");
+            } else {
+                assert!(out.contains("app") && out.contains("lib"), "{name}: {out}");
+            }
+        }
+        Ok(())
+    }
+}
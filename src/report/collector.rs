@@ -1,13 +1,35 @@
+use std::collections::HashSet;
 use std::env;
+use std::fs;
 use std::path::{Path, PathBuf};
 
+use chrono::{DateTime, SecondsFormat, Utc};
+use rayon::{ThreadPoolBuilder, prelude::*};
+
 use crate::git::GitRunner;
-use crate::scan::find_repos;
+use crate::scan::{Discovery, DiscoveryPatterns, RepoLayout, find_repos};
 use crate::system::{Clock, FsOps};
-use crate::types::{Options, ReportData};
+use crate::types::{Depth, Options, ReportData, ReportMeta};
 
+use super::fetch::fetch_all;
 use super::repository::{RootContext, process_repo};
 
+/// A scan root with its display form, absolute path and effective per-root settings.
+pub(super) struct ResolvedRoot {
+    pub(super) display: String,
+    pub(super) full: PathBuf,
+    depth: Depth,
+    no_untracked: bool,
+}
+
+#[derive(Clone)]
+pub(super) struct RepoJob<'a> {
+    pub(super) repo: PathBuf,
+    pub(super) layout: RepoLayout,
+    pub(super) name: String,
+    pub(super) root: &'a ResolvedRoot,
+}
+
 pub fn collect_report_data(
     opts: &Options,
     fs: &dyn FsOps,
@@ -17,17 +39,49 @@ pub fn collect_report_data(
     let rooted = resolve_roots(opts, fs);
 
     let mut data = ReportData {
+        meta: report_meta(opts, clock),
         multi_root: rooted.len() > 1,
         ..Default::default()
     };
-    for (root_display, root_full) in &rooted {
-        scan_root(root_display, root_full, opts, fs, git, clock, &mut data);
+    let jobs = discover_jobs(&rooted, opts, fs);
+    if opts.refresh_remotes {
+        data.fetches = fetch_all(&jobs, opts, git);
+    }
+    for part in process_jobs(&jobs, opts, git, clock) {
+        merge_repo_data(&mut data, part);
     }
 
     data
 }
 
-fn resolve_roots(opts: &Options, fs: &dyn FsOps) -> Vec<(String, PathBuf)> {
+/// Collection time, host and options recorded with a report.
+pub(super) fn report_meta(opts: &Options, clock: &dyn Clock) -> ReportMeta {
+    ReportMeta {
+        generated_at: DateTime::<Utc>::from(clock.now()).to_rfc3339_opts(SecondsFormat::Secs, true),
+        host: gethostname::gethostname().to_string_lossy().into_owned(),
+        options: opts.clone(),
+    }
+}
+
+/// Every repository to inspect under `rooted`, in report order.
+pub(super) fn discover_jobs<'a>(
+    rooted: &'a [ResolvedRoot],
+    opts: &Options,
+    fs: &dyn FsOps,
+) -> Vec<RepoJob<'a>> {
+    let jobs: Vec<RepoJob<'_>> = rooted
+        .iter()
+        .flat_map(|root| discover_root(root, opts, fs))
+        .collect();
+    let jobs = drop_grouped_worktrees(jobs);
+    if opts.submodules {
+        drop_submodule_checkouts(jobs)
+    } else {
+        jobs
+    }
+}
+
+pub(super) fn resolve_roots(opts: &Options, fs: &dyn FsOps) -> Vec<ResolvedRoot> {
     let default_root = PathBuf::from("~/src");
     let roots = if opts.roots.is_empty() {
         vec![default_root]
@@ -39,9 +93,19 @@ fn resolve_roots(opts: &Options, fs: &dyn FsOps) -> Vec<(String, PathBuf)> {
         .into_iter()
         .map(|root| {
             let display = root.to_string_lossy().to_string();
-            let expanded = fs.expand_tilde(&root);
-            let full = normalize_root_path(expanded);
-            (display, full)
+            let full = normalize_root_path(fs.expand_tilde(&root));
+            let settings = opts
+                .root_settings
+                .iter()
+                .find(|settings| normalize_root_path(fs.expand_tilde(&settings.root)) == full);
+            ResolvedRoot {
+                display,
+                depth: settings.and_then(|s| s.depth).unwrap_or(opts.depth),
+                no_untracked: settings
+                    .and_then(|s| s.no_untracked)
+                    .unwrap_or(opts.no_untracked),
+                full,
+            }
         })
         .collect()
 }
@@ -56,46 +120,139 @@ fn normalize_root_path(expanded: PathBuf) -> PathBuf {
     }
 }
 
-#[allow(clippy::too_many_arguments)]
-fn scan_root(
-    root_display: &str,
-    root_full: &PathBuf,
+fn discover_root<'a>(root: &'a ResolvedRoot, opts: &Options, fs: &dyn FsOps) -> Vec<RepoJob<'a>> {
+    let discovery = Discovery {
+        depth: root.depth,
+        patterns: DiscoveryPatterns {
+            exclude: &opts.exclude,
+            include: &opts.include,
+        },
+        nested: opts.nested,
+        debug: opts.debug,
+    };
+    let repos = find_repos(fs, std::slice::from_ref(&root.full), &discovery);
+    log_debug(opts, root, repos.len());
+
+    repos
+        .into_iter()
+        .map(|found| {
+            let name = found
+                .path
+                .file_name()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_default();
+            RepoJob {
+                repo: found.path,
+                layout: found.layout,
+                name,
+                root,
+            }
+        })
+        .collect()
+}
+
+/// Linked worktrees are reported through their main repository, so only keep a worktree as
+/// its own job when the main repository was not discovered under any root.
+fn drop_grouped_worktrees(jobs: Vec<RepoJob<'_>>) -> Vec<RepoJob<'_>> {
+    let mains: HashSet<PathBuf> = jobs
+        .iter()
+        .filter(|job| !matches!(job.layout, RepoLayout::Worktree { .. }))
+        .map(|job| canonical(&job.repo))
+        .collect();
+    jobs.into_iter()
+        .filter(|job| match &job.layout {
+            RepoLayout::Worktree { main } => !mains.contains(&canonical(main)),
+            RepoLayout::Standard | RepoLayout::Bare => true,
+        })
+        .collect()
+}
+
+/// In submodule mode a submodule checkout is reported under its superproject, so drop it
+/// when the walk also discovered it on its own: a `.git` file below another discovered repo.
+fn drop_submodule_checkouts(jobs: Vec<RepoJob<'_>>) -> Vec<RepoJob<'_>> {
+    let repos: Vec<PathBuf> = jobs.iter().map(|job| job.repo.clone()).collect();
+    jobs.into_iter()
+        .filter(|job| {
+            !(job.repo.join(".git").is_file()
+                && repos
+                    .iter()
+                    .any(|repo| repo != &job.repo && job.repo.starts_with(repo)))
+        })
+        .collect()
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Inspect every discovered repository on a bounded worker pool.
+///
+/// Results come back in the same order as `jobs`, so the merged report is identical to a
+/// sequential scan regardless of how the work was scheduled.
+pub(super) fn process_jobs(
+    jobs: &[RepoJob<'_>],
     opts: &Options,
-    fs: &dyn FsOps,
     git: &dyn GitRunner,
     clock: &dyn Clock,
-    data: &mut ReportData,
-) {
-    let repos = find_repos(fs, std::slice::from_ref(root_full), opts.depth, opts.debug);
-    log_debug(opts, root_display, root_full.as_path(), repos.len());
-
-    for repo in repos {
-        let name = repo
-            .file_name()
-            .map(|s| s.to_string_lossy().to_string())
-            .unwrap_or_default();
+) -> Vec<ReportData> {
+    let run = |job: &RepoJob<'_>| {
         process_repo(
-            &repo,
-            &name,
+            &job.repo,
+            &job.layout,
+            &job.name,
             RootContext {
-                display: root_display,
-                full: root_full,
+                display: &job.root.display,
+                full: &job.root.full,
+                no_untracked: job.root.no_untracked,
             },
             opts,
             git,
             clock,
-            data,
-        );
+        )
+    };
+
+    let threads = worker_count(opts);
+    if threads <= 1 || jobs.len() <= 1 {
+        return jobs.iter().map(run).collect();
+    }
+    match ThreadPoolBuilder::new().num_threads(threads).build() {
+        Ok(pool) => pool.install(|| jobs.par_iter().map(run).collect()),
+        Err(err) => {
+            if opts.debug {
+                eprintln!("[debug] worker pool unavailable, scanning sequentially: {err}");
+            }
+            jobs.iter().map(run).collect()
+        }
     }
 }
 
-fn log_debug(opts: &Options, root_display: &str, root_full: &Path, repo_count: usize) {
+fn worker_count(opts: &Options) -> usize {
+    match opts.jobs {
+        Some(jobs) if jobs > 0 => jobs,
+        _ => num_cpus::get(),
+    }
+}
+
+pub(super) fn merge_repo_data(data: &mut ReportData, part: ReportData) {
+    data.uncommitted.extend(part.uncommitted);
+    data.staged.extend(part.staged);
+    data.pushable.extend(part.pushable);
+    data.pullable.extend(part.pullable);
+    data.unpublished.extend(part.unpublished);
+    data.stashes.extend(part.stashes);
+    data.submodules.extend(part.submodules);
+    data.errors.extend(part.errors);
+    data.repos.extend(part.repos);
+}
+
+fn log_debug(opts: &Options, root: &ResolvedRoot, repo_count: usize) {
     if opts.debug {
         eprintln!(
-            "[debug] root_display={} root_full={} depth={} repos_found={}",
-            root_display,
-            root_full.display(),
-            opts.depth,
+            "[debug] root_display={} root_full={} depth={} no_untracked={} repos_found={}",
+            root.display,
+            root.full.display(),
+            root.depth,
+            root.no_untracked,
             repo_count
         );
     }
@@ -0,0 +1,219 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use rayon::{ThreadPoolBuilder, prelude::*};
+
+use crate::git::{
+    GitCommandError, GitRunner, fetch_from_peer, fetch_remote, remote_url, upstream_remotes,
+};
+use crate::types::{FetchEntry, FetchStatus, Options};
+
+use super::collector::RepoJob;
+
+/// Ceiling used for a fetch when no timeout is configured.
+const NO_TIMEOUT: Duration = Duration::from_hours(24 * 365);
+
+/// A remote to fetch into one repository.
+struct Request<'a> {
+    job: &'a RepoJob<'a>,
+    remote: String,
+    url: Option<String>,
+    /// Index of the earlier request with the same URL that fetches over the network.
+    leader: Option<usize>,
+}
+
+/// Fetch every remote tracked by a branch of the repositories in `jobs`.
+///
+/// Remotes are fetched concurrently with a per-attempt timeout and `opts.fetch_retries`
+/// retries. A URL shared by several repositories goes over the network once; the other
+/// repositories then copy the tracking refs from the repository that fetched it.
+pub(super) fn fetch_all(
+    jobs: &[RepoJob<'_>],
+    opts: &Options,
+    git: &dyn GitRunner,
+) -> Vec<FetchEntry> {
+    let requests = plan_requests(jobs, opts, git);
+    let leaders: Vec<usize> = (0..requests.len())
+        .filter(|&index| requests[index].leader.is_none())
+        .collect();
+    let mut fetched: HashMap<usize, FetchEntry> = leaders
+        .iter()
+        .copied()
+        .zip(run_parallel(&leaders, opts, |&index| {
+            fetch_over_network(&requests[index], opts, git)
+        }))
+        .collect();
+
+    let followers: Vec<usize> = (0..requests.len())
+        .filter(|&index| requests[index].leader.is_some())
+        .collect();
+    let mut copied: HashMap<usize, FetchEntry> = followers
+        .iter()
+        .copied()
+        .zip(run_parallel(&followers, opts, |&index| {
+            let request = &requests[index];
+            let leader = request.leader.unwrap_or(index);
+            copy_from_leader(request, &requests[leader], &fetched[&leader], opts, git)
+        }))
+        .collect();
+
+    (0..requests.len())
+        .filter_map(|index| fetched.remove(&index).or_else(|| copied.remove(&index)))
+        .collect()
+}
+
+fn plan_requests<'a>(
+    jobs: &'a [RepoJob<'a>],
+    opts: &Options,
+    git: &dyn GitRunner,
+) -> Vec<Request<'a>> {
+    let mut requests: Vec<Request<'a>> = Vec::new();
+    let mut by_url: HashMap<String, usize> = HashMap::new();
+    for job in jobs {
+        let remotes = match upstream_remotes(&job.repo, git) {
+            Ok(remotes) => remotes,
+            Err(err) => {
+                if opts.debug {
+                    eprintln!("[debug] repo={} fetch skipped: {err}", job.repo.display());
+                }
+                continue;
+            }
+        };
+        for remote in remotes {
+            // The URL only pairs remotes up for sharing one fetch; without it the remote is
+            // fetched on its own.
+            let url = remote_url(&job.repo, git, &remote).unwrap_or_else(|err| {
+                if opts.debug {
+                    eprintln!("[debug] repo={} remote={remote}: {err}", job.repo.display());
+                }
+                None
+            });
+            let leader = url.as_ref().and_then(|url| by_url.get(url).copied());
+            if let (Some(url), None) = (&url, leader) {
+                by_url.insert(url.clone(), requests.len());
+            }
+            requests.push(Request {
+                job,
+                remote,
+                url,
+                leader,
+            });
+        }
+    }
+    requests
+}
+
+fn run_parallel<T, F>(items: &[T], opts: &Options, run: F) -> Vec<FetchEntry>
+where
+    T: Sync,
+    F: Fn(&T) -> FetchEntry + Sync + Send,
+{
+    let threads = match opts.jobs {
+        Some(jobs) if jobs > 0 => jobs,
+        _ => num_cpus::get(),
+    };
+    if threads <= 1 || items.len() <= 1 {
+        return items.iter().map(run).collect();
+    }
+    match ThreadPoolBuilder::new().num_threads(threads).build() {
+        Ok(pool) => pool.install(|| items.par_iter().map(run).collect()),
+        Err(_) => items.iter().map(run).collect(),
+    }
+}
+
+fn fetch_over_network(request: &Request<'_>, opts: &Options, git: &dyn GitRunner) -> FetchEntry {
+    let timeout = opts.fetch_timeout.unwrap_or(NO_TIMEOUT);
+    let started = Instant::now();
+    let mut attempts = 0;
+    let mut last_error = None;
+    while attempts <= opts.fetch_retries {
+        attempts += 1;
+        match fetch_remote(&request.job.repo, git, &request.remote, timeout) {
+            Ok(()) => {
+                last_error = None;
+                break;
+            }
+            Err(err) => {
+                if opts.debug {
+                    eprintln!(
+                        "[debug] repo={} remote={} attempt={attempts} error={err}",
+                        request.job.repo.display(),
+                        request.remote
+                    );
+                }
+                last_error = Some(err);
+            }
+        }
+    }
+    let status = match &last_error {
+        None => FetchStatus::Fetched,
+        Some(err) if err.timed_out => FetchStatus::TimedOut,
+        Some(_) => FetchStatus::Failed,
+    };
+    entry(request, status, attempts, started, last_error.as_ref())
+}
+
+fn copy_from_leader(
+    request: &Request<'_>,
+    leader: &Request<'_>,
+    leader_entry: &FetchEntry,
+    opts: &Options,
+    git: &dyn GitRunner,
+) -> FetchEntry {
+    let started = Instant::now();
+    if !leader_entry.status.is_ok() {
+        let mut skipped = entry(request, FetchStatus::Skipped, 0, started, None);
+        skipped.error = Some(format!(
+            "same URL as {} {}, which {}",
+            leader.job.name,
+            leader.remote,
+            leader_entry.status.label()
+        ));
+        return skipped;
+    }
+    let timeout = opts.fetch_timeout.unwrap_or(NO_TIMEOUT);
+    let result = fetch_from_peer(
+        &request.job.repo,
+        git,
+        &request.remote,
+        &leader.job.repo,
+        &leader.remote,
+        timeout,
+    );
+    match result {
+        Ok(()) => {
+            let mut shared = entry(request, FetchStatus::Shared, 0, started, None);
+            shared.source = Some(leader.job.name.clone());
+            shared
+        }
+        // The local copy failing says nothing about the remote, so fall back to the network.
+        Err(_) => fetch_over_network(request, opts, git),
+    }
+}
+
+fn entry(
+    request: &Request<'_>,
+    status: FetchStatus,
+    attempts: u32,
+    started: Instant,
+    error: Option<&GitCommandError>,
+) -> FetchEntry {
+    FetchEntry {
+        repo: request.job.name.clone(),
+        remote: request.remote.clone(),
+        url: request.url.clone(),
+        status,
+        source: None,
+        attempts,
+        duration_ms: u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX),
+        error: error.map(|err| {
+            if err.stderr.is_empty() {
+                err.to_string()
+            } else {
+                err.stderr.clone()
+            }
+        }),
+        root_display: request.job.root.display.clone(),
+        root_full: request.job.root.full.display().to_string(),
+    }
+}
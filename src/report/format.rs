@@ -2,7 +2,8 @@ use std::time::Duration;
 
 use crate::system::{Clock, FsOps};
 use crate::types::{
-    GitRewriteEntry, Options, PushableEntry, StagedEntry, UncommittedEntry, UntrackedReason,
+    FetchEntry, GitRewriteEntry, Options, PullableEntry, PushableEntry, RepoError, StagedEntry,
+    StashEntry, SubmoduleEntry, UncommittedEntry, UnpublishedEntry, UntrackedReason,
     UntrackedRepoEntry,
 };
 
@@ -19,12 +20,33 @@ pub fn generate_report(
     let uncommitted = change_rows(&data.uncommitted);
     let staged = change_rows(&data.staged);
     let pushable = pushable_rows(&data.pushable);
+    let pullable = pullable_rows(&data.pullable);
+    let unpublished = unpublished_rows(&data.unpublished);
+    let stashes = stash_rows(&data.stashes);
     let mut sections = vec![
         format_section("uncommitted", &uncommitted),
         format_section("staged", &staged),
         format_section("pushable", &pushable),
+        format_section("pullable", &pullable),
+        format_section("unpublished", &unpublished),
+        format_section("stashes", &stashes),
     ];
 
+    if !data.submodules.is_empty() {
+        let submodules = submodule_rows(&data.submodules);
+        sections.push(format_section("submodules", &submodules));
+    }
+
+    if !data.fetches.is_empty() {
+        let fetches = fetch_rows(&data.fetches);
+        sections.push(format_section("fetches", &fetches));
+    }
+
+    if !data.errors.is_empty() {
+        let errors = error_rows(&data.errors);
+        sections.push(format_section("errors", &errors));
+    }
+
     if data.untracked_enabled {
         let untracked = untracked_rows(&data.untracked_repos);
         sections.push(format_section("untracked", &untracked));
@@ -47,7 +69,7 @@ where
         .map(|entry| {
             format!(
                 "{} ({} lines, {} files, {} untracked)",
-                entry.repo(),
+                repo_label(entry.repo(), entry.worktree()),
                 entry.lines(),
                 entry.files(),
                 entry.untracked()
@@ -75,6 +97,118 @@ fn pushable_rows(entries: &[PushableEntry]) -> Vec<String> {
         .collect()
 }
 
+fn pullable_rows(entries: &[PullableEntry]) -> Vec<String> {
+    entries
+        .iter()
+        .map(|entry| {
+            let status = if entry.diverged {
+                format!("diverged, {} ahead", entry.ahead)
+            } else {
+                "behind".to_string()
+            };
+            format!(
+                "{}:{} ({} behind {}, {status}, earliest: {} ago, latest: {} ago)",
+                entry.repo,
+                entry.branch,
+                entry.behind,
+                entry.upstream,
+                format_age(entry.earliest_secs),
+                format_age(entry.latest_secs)
+            )
+        })
+        .collect()
+}
+
+fn unpublished_rows(entries: &[UnpublishedEntry]) -> Vec<String> {
+    entries
+        .iter()
+        .map(|entry| {
+            let base = entry.base.as_deref().unwrap_or("n/a");
+            format!(
+                "{}:{} ({} revs vs {base}, earliest: {} ago, latest: {} ago)",
+                entry.repo,
+                entry.branch,
+                entry.revs,
+                format_age(entry.earliest_secs),
+                format_age(entry.latest_secs)
+            )
+        })
+        .collect()
+}
+
+fn stash_rows(entries: &[StashEntry]) -> Vec<String> {
+    entries
+        .iter()
+        .map(|entry| {
+            format!(
+                "{}:{} ({}, branch: {}, age: {})",
+                entry.repo,
+                entry.stash,
+                entry.message,
+                entry.branch,
+                format_age(entry.age_secs)
+            )
+        })
+        .collect()
+}
+
+fn submodule_rows(entries: &[SubmoduleEntry]) -> Vec<String> {
+    entries
+        .iter()
+        .map(|entry| {
+            format!(
+                "{}/{} (commit {} not pushed, {} revs)",
+                entry.repo, entry.path, entry.commit, entry.revs
+            )
+        })
+        .collect()
+}
+
+fn fetch_rows(entries: &[FetchEntry]) -> Vec<String> {
+    entries
+        .iter()
+        .map(|entry| {
+            let source = entry
+                .source
+                .as_ref()
+                .map(|source| format!(" from {source}"))
+                .unwrap_or_default();
+            let error = entry
+                .error
+                .as_ref()
+                .map(|error| format!(": {error}"))
+                .unwrap_or_default();
+            format!(
+                "{}/{} ({}{source}{error})",
+                entry.repo,
+                entry.remote,
+                entry.status.label()
+            )
+        })
+        .collect()
+}
+
+fn error_rows(entries: &[RepoError]) -> Vec<String> {
+    entries
+        .iter()
+        .map(|entry| {
+            let status = if entry.timed_out {
+                "timed out".to_string()
+            } else {
+                entry
+                    .status
+                    .map_or_else(|| "n/a".to_string(), |code| code.to_string())
+            };
+            format!(
+                "{} ({}, exit: {status}, {})",
+                repo_label(&entry.repo, entry.worktree.as_deref()),
+                entry.command,
+                entry.stderr
+            )
+        })
+        .collect()
+}
+
 fn untracked_rows(entries: &[UntrackedRepoEntry]) -> Vec<String> {
     entries
         .iter()
@@ -115,6 +249,15 @@ fn git_rewrite_rows(entries: &[GitRewriteEntry]) -> Vec<String> {
         .collect()
 }
 
+/// Repository column text, suffixed with the linked worktree name when present.
+#[must_use]
+pub fn repo_label(repo: &str, worktree: Option<&str>) -> String {
+    match worktree {
+        Some(worktree) => format!("{repo} [{worktree}]"),
+        None => repo.to_string(),
+    }
+}
+
 fn format_section(label: &str, rows: &[String]) -> String {
     format!("{label}: {}", rows.join(", "))
 }
@@ -128,6 +271,7 @@ fn format_age(value: Option<u64>) -> String {
 
 trait ChangeEntry {
     fn repo(&self) -> &str;
+    fn worktree(&self) -> Option<&str>;
     fn lines(&self) -> u64;
     fn files(&self) -> u64;
     fn untracked(&self) -> u64;
@@ -137,6 +281,9 @@ impl ChangeEntry for UncommittedEntry {
     fn repo(&self) -> &str {
         &self.repo
     }
+    fn worktree(&self) -> Option<&str> {
+        self.worktree.as_deref()
+    }
     fn lines(&self) -> u64 {
         self.lines
     }
@@ -152,6 +299,9 @@ impl ChangeEntry for StagedEntry {
     fn repo(&self) -> &str {
         &self.repo
     }
+    fn worktree(&self) -> Option<&str> {
+        self.worktree.as_deref()
+    }
     fn lines(&self) -> u64 {
         self.lines
     }
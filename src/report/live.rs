@@ -0,0 +1,259 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use crate::git::GitRunner;
+use crate::scan::{DiscoveryPatterns, PathFilter, RepoLayout};
+use crate::system::{Clock, FsOps};
+use crate::types::{FetchEntry, Options, ReportData, ReportMeta};
+
+use super::collector::{
+    RepoJob, ResolvedRoot, discover_jobs, merge_repo_data, process_jobs, report_meta, resolve_roots,
+};
+use super::fetch::fetch_all;
+
+/// A report that can be brought up to date one repository at a time.
+///
+/// Long-running callers such as `watch` scan everything once, then call [`LiveReport::refresh`]
+/// with the paths that changed so only the repositories owning them are inspected again.
+pub struct LiveReport {
+    roots: Vec<ResolvedRoot>,
+    /// Exclude and include patterns of each root, in `roots` order.
+    filters: Vec<PathFilter>,
+    repos: Vec<LiveRepo>,
+    /// Results of the fetch done by the initial scan; refreshes do not fetch again.
+    fetches: Vec<FetchEntry>,
+    /// Time and options of the latest scan or refresh.
+    meta: ReportMeta,
+}
+
+struct LiveRepo {
+    path: PathBuf,
+    layout: RepoLayout,
+    name: String,
+    root: usize,
+    part: ReportData,
+}
+
+/// What a [`LiveReport::refresh`] did.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Refresh {
+    /// Repositories inspected again, in report order.
+    pub rescanned: Vec<PathBuf>,
+    /// Whether the roots were walked again because a path outside every known repository
+    /// changed (a repository may have appeared or disappeared).
+    pub rediscovered: bool,
+}
+
+impl LiveReport {
+    /// Discover and inspect every repository, like [`crate::collect_report_data`].
+    pub fn scan(opts: &Options, fs: &dyn FsOps, git: &dyn GitRunner, clock: &dyn Clock) -> Self {
+        let roots = resolve_roots(opts, fs);
+        let patterns = DiscoveryPatterns {
+            exclude: &opts.exclude,
+            include: &opts.include,
+        };
+        let filters = roots
+            .iter()
+            .map(|root| PathFilter::for_root(&root.full, patterns))
+            .collect();
+        let mut live = LiveReport {
+            roots,
+            filters,
+            repos: Vec::new(),
+            fetches: Vec::new(),
+            meta: report_meta(opts, clock),
+        };
+        live.rediscover(&HashSet::new(), opts, fs, git, clock);
+        live
+    }
+
+    /// The merged report, identical to what a full scan would produce.
+    #[must_use]
+    pub fn report(&self) -> ReportData {
+        let mut data = ReportData {
+            meta: self.meta.clone(),
+            multi_root: self.roots.len() > 1,
+            fetches: self.fetches.clone(),
+            ..Default::default()
+        };
+        for repo in &self.repos {
+            merge_repo_data(&mut data, repo.part.clone());
+        }
+        data
+    }
+
+    /// Directories whose whole subtree affects the report: every repository and its linked
+    /// worktrees.
+    #[must_use]
+    pub fn repo_dirs(&self) -> Vec<PathBuf> {
+        self.repos
+            .iter()
+            .flat_map(|repo| {
+                let worktrees = repo
+                    .part
+                    .repos
+                    .iter()
+                    .flat_map(|summary| summary.worktrees.iter().map(|wt| wt.path.clone()));
+                std::iter::once(repo.path.clone()).chain(worktrees)
+            })
+            .collect()
+    }
+
+    /// Directories where a new repository could appear: each root and every directory
+    /// between a root and a repository found below it.
+    #[must_use]
+    pub fn discovery_dirs(&self) -> Vec<PathBuf> {
+        let mut dirs: Vec<PathBuf> = self.roots.iter().map(|root| root.full.clone()).collect();
+        for repo in &self.repos {
+            let root = &self.roots[repo.root].full;
+            let mut dir = repo.path.parent();
+            while let Some(parent) = dir
+                && parent.starts_with(root)
+                && parent != root
+            {
+                dirs.push(parent.to_path_buf());
+                dir = parent.parent();
+            }
+        }
+        dirs.sort();
+        dirs.dedup();
+        dirs
+    }
+
+    /// Whether the exclude and include patterns of a root containing `path` exclude it or a
+    /// directory above it, so changes there do not affect the report.
+    #[must_use]
+    pub fn is_excluded(&self, path: &Path) -> bool {
+        self.filters.iter().any(|filter| filter.excludes(path))
+    }
+
+    /// Inspect again the repositories owning `changed`; when a changed path belongs to no known
+    /// repository or a repository directory is gone, walk the roots again to pick up added or
+    /// removed repositories.
+    pub fn refresh(
+        &mut self,
+        changed: &[PathBuf],
+        opts: &Options,
+        fs: &dyn FsOps,
+        git: &dyn GitRunner,
+        clock: &dyn Clock,
+    ) -> Refresh {
+        self.meta = report_meta(opts, clock);
+        let mut dirty = HashSet::new();
+        let mut unowned = false;
+        for path in changed {
+            match self.owner(path) {
+                Some(repo) => {
+                    dirty.insert(repo.to_path_buf());
+                }
+                None => unowned = true,
+            }
+        }
+        if unowned || dirty.iter().any(|repo| !repo.exists()) {
+            return self.rediscover(&dirty, opts, fs, git, clock);
+        }
+
+        let jobs: Vec<RepoJob<'_>> = self
+            .repos
+            .iter()
+            .filter(|repo| dirty.contains(&repo.path))
+            .map(|repo| self.job(repo))
+            .collect();
+        let rescanned: Vec<PathBuf> = jobs.iter().map(|job| job.repo.clone()).collect();
+        let mut parts: HashMap<PathBuf, ReportData> = rescanned
+            .iter()
+            .cloned()
+            .zip(process_jobs(&jobs, opts, git, clock))
+            .collect();
+        for repo in &mut self.repos {
+            if let Some(part) = parts.remove(&repo.path) {
+                repo.part = part;
+            }
+        }
+        Refresh {
+            rescanned,
+            rediscovered: false,
+        }
+    }
+
+    /// The repository whose working tree, git dir or linked worktree contains `path`.
+    fn owner(&self, path: &Path) -> Option<&Path> {
+        self.repos
+            .iter()
+            .flat_map(|repo| {
+                let worktrees = repo
+                    .part
+                    .repos
+                    .iter()
+                    .flat_map(|summary| summary.worktrees.iter().map(|wt| wt.path.as_path()));
+                std::iter::once(repo.path.as_path())
+                    .chain(worktrees)
+                    .map(move |dir| (dir, repo.path.as_path()))
+            })
+            .filter(|(dir, _)| path.starts_with(dir))
+            .max_by_key(|(dir, _)| dir.components().count())
+            .map(|(_, repo)| repo)
+    }
+
+    /// Walk the roots again, keeping the rows of known repositories that are not in `dirty`.
+    fn rediscover(
+        &mut self,
+        dirty: &HashSet<PathBuf>,
+        opts: &Options,
+        fs: &dyn FsOps,
+        git: &dyn GitRunner,
+        clock: &dyn Clock,
+    ) -> Refresh {
+        let mut known: HashMap<PathBuf, ReportData> = self
+            .repos
+            .drain(..)
+            .filter(|repo| !dirty.contains(&repo.path))
+            .map(|repo| (repo.path, repo.part))
+            .collect();
+        let jobs = discover_jobs(&self.roots, opts, fs);
+        let pending: Vec<RepoJob<'_>> = jobs
+            .iter()
+            .filter(|job| !known.contains_key(&job.repo))
+            .cloned()
+            .collect();
+        let rescanned: Vec<PathBuf> = pending.iter().map(|job| job.repo.clone()).collect();
+        if opts.refresh_remotes {
+            self.fetches = fetch_all(&pending, opts, git);
+        }
+        known.extend(
+            rescanned
+                .iter()
+                .cloned()
+                .zip(process_jobs(&pending, opts, git, clock)),
+        );
+
+        let repos = jobs
+            .iter()
+            .map(|job| LiveRepo {
+                path: job.repo.clone(),
+                layout: job.layout.clone(),
+                name: job.name.clone(),
+                root: self
+                    .roots
+                    .iter()
+                    .position(|root| std::ptr::eq(root, job.root))
+                    .unwrap_or_default(),
+                part: known.remove(&job.repo).unwrap_or_default(),
+            })
+            .collect();
+        self.repos = repos;
+        Refresh {
+            rescanned,
+            rediscovered: true,
+        }
+    }
+
+    fn job(&self, repo: &LiveRepo) -> RepoJob<'_> {
+        RepoJob {
+            repo: repo.path.clone(),
+            layout: repo.layout.clone(),
+            name: repo.name.clone(),
+            root: &self.roots[repo.root],
+        }
+    }
+}
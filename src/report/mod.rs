@@ -1,8 +1,11 @@
 mod collector;
+mod fetch;
 mod format;
 mod humanize;
+mod live;
 mod repository;
 
 pub use collector::collect_report_data;
-pub use format::generate_report;
+pub use format::{generate_report, repo_label};
 pub use humanize::humanize_age_public;
+pub use live::{LiveReport, Refresh};
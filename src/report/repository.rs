@@ -1,142 +1,367 @@
-use std::collections::HashSet;
 use std::path::Path;
 
 use crate::git::{
-    current_branch, fetch_remote, has_staged, has_uncommitted, list_local_branches_with_upstream,
-    staged_metrics, uncommitted_metrics, upstream_remote_url,
+    GitCommandError, LinkedWorktree, Submodule, SubmoduleDiff, current_branch, has_staged,
+    has_uncommitted, list_linked_worktrees, list_local_branches, list_stashes, list_submodules,
+    staged_metrics, uncommitted_metrics, unpushed_commit_count, upstream_remote_url,
 };
+use crate::scan::RepoLayout;
 use crate::system::Clock;
 use crate::types::{
-    Options, PushableEntry, RepoSummary, ReportData, StagedEntry, UncommittedEntry,
+    Options, PullableEntry, PushableEntry, RepoError, RepoSummary, ReportData, StagedEntry,
+    StashEntry, SubmoduleEntry, UncommittedEntry, UnpublishedEntry, WorktreeSummary,
 };
 
 #[derive(Copy, Clone)]
 pub(crate) struct RootContext<'a> {
     pub(crate) display: &'a str,
     pub(crate) full: &'a Path,
+    pub(crate) no_untracked: bool,
 }
 
 struct RepoContext<'a> {
     repo: &'a Path,
     name: &'a str,
     branch: &'a str,
+    worktree: Option<&'a str>,
     root_display: &'a str,
     root_full: &'a str,
+    include_untracked: bool,
+    submodules: SubmoduleDiff,
 }
 
+impl RepoContext<'_> {
+    fn error(&self, err: GitCommandError) -> RepoError {
+        RepoError {
+            repo: self.name.to_string(),
+            worktree: self.worktree.map(str::to_string),
+            command: err.command,
+            status: err.status,
+            timed_out: err.timed_out,
+            stderr: err.stderr,
+            root_display: self.root_display.to_string(),
+            root_full: self.root_full.to_string(),
+            path: self.repo.to_path_buf(),
+        }
+    }
+}
+
+/// Inspect a single repository and return the rows it contributes to the report.
+///
+/// A git failure stops inspection of the repository (or linked worktree) it occurred in and is
+/// recorded in [`ReportData::errors`]; rows gathered before the failure are kept.
 pub(crate) fn process_repo(
     repo: &Path,
+    layout: &RepoLayout,
     name: &str,
     root: RootContext<'_>,
     opts: &Options,
     git: &dyn crate::git::GitRunner,
     clock: &dyn Clock,
-    data: &mut ReportData,
-) {
-    let branch = current_branch(repo, git).unwrap_or_else(|| "HEAD".to_string());
+) -> ReportData {
+    let mut data = ReportData::default();
+    let branch = current_branch(repo, git);
     let root_display = root.display.to_string();
     let root_full = root.full.display().to_string();
     let ctx = RepoContext {
         repo,
         name,
-        branch: &branch,
+        branch: branch.as_deref().unwrap_or("HEAD"),
+        worktree: None,
         root_display: &root_display,
         root_full: &root_full,
+        include_untracked: !root.no_untracked,
+        submodules: if opts.submodules {
+            SubmoduleDiff::Moved
+        } else {
+            SubmoduleDiff::Ignore
+        },
+    };
+
+    let bare = *layout == RepoLayout::Bare;
+    let mut summary = RepoSummary {
+        repo: ctx.name.to_string(),
+        branch: ctx.branch.to_string(),
+        path: ctx.repo.to_path_buf(),
+        root_display: ctx.root_display.to_string(),
+        root_full: ctx.root_full.to_string(),
+        head_revs: None,
+        head_earliest_secs: None,
+        head_latest_secs: None,
+        bare,
+        worktrees: Vec::new(),
     };
+    // A repository whose HEAD cannot be read is still listed, under `HEAD`, with its error.
+    let inspected = branch
+        .as_ref()
+        .map_err(Clone::clone)
+        .and_then(|_| inspect_repo(&ctx, layout, opts, git, clock, &mut data, &mut summary));
+    if let Err(err) = inspected {
+        if opts.debug {
+            eprintln!("[debug] repo={} error={err}", repo.display());
+        }
+        data.errors.push(ctx.error(err));
+    }
+    data.repos.push(summary);
+    data
+}
 
-    record_uncommitted(&ctx, opts, git, data);
-    record_staged(&ctx, git, data);
+fn inspect_repo(
+    ctx: &RepoContext<'_>,
+    layout: &RepoLayout,
+    opts: &Options,
+    git: &dyn crate::git::GitRunner,
+    clock: &dyn Clock,
+    data: &mut ReportData,
+    summary: &mut RepoSummary,
+) -> Result<(), GitCommandError> {
+    if !summary.bare {
+        record_uncommitted(ctx, git, data)?;
+        record_staged(ctx, git, data)?;
+        record_stashes(ctx, git, clock, data)?;
+        if opts.submodules {
+            record_submodules(ctx, opts, git, clock, data)?;
+        }
+    }
 
-    let branches = list_local_branches_with_upstream(repo, git);
-    refresh_remotes(repo, opts, git, &branches);
+    let worktrees = match layout {
+        RepoLayout::Worktree { .. } => Vec::new(),
+        RepoLayout::Standard | RepoLayout::Bare => list_linked_worktrees(ctx.repo, git)?,
+    };
+    for worktree in &worktrees {
+        record_worktree(ctx, worktree, opts, git, data);
+    }
+    summary.worktrees = worktrees
+        .into_iter()
+        .map(|wt| WorktreeSummary {
+            path: wt.path,
+            branch: wt.branch,
+        })
+        .collect();
 
-    let (head_revs, head_earliest_secs, head_latest_secs) =
-        record_pushables(&ctx, branches, git, clock, data);
+    let (branches, unpublished): (Vec<_>, Vec<_>) = list_local_branches(ctx.repo, git)?
+        .into_iter()
+        .partition(|(_, upstream)| upstream.is_some());
+    let branches: Vec<(String, String)> = branches
+        .into_iter()
+        .filter_map(|(branch, upstream)| upstream.map(|upstream| (branch, upstream)))
+        .collect();
+    // Branches of a bare repository are what others push to, not unpublished work.
+    if !summary.bare {
+        record_unpublished(ctx, &unpublished, git, clock, data)?;
+    }
 
-    add_repo_summary(&ctx, head_revs, head_earliest_secs, head_latest_secs, data);
+    (
+        summary.head_revs,
+        summary.head_earliest_secs,
+        summary.head_latest_secs,
+    ) = record_pushables(ctx, branches, git, clock, data)?;
+    Ok(())
 }
 
-fn record_uncommitted(
-    ctx: &RepoContext<'_>,
+/// Record the dirty and staged state of a linked worktree under its main repository.
+fn record_worktree(
+    main: &RepoContext<'_>,
+    worktree: &LinkedWorktree,
     opts: &Options,
     git: &dyn crate::git::GitRunner,
     data: &mut ReportData,
 ) {
-    if !has_uncommitted(ctx.repo, !opts.no_untracked, git) {
-        return;
+    let label = worktree.path.file_name().map_or_else(
+        || worktree.path.display().to_string(),
+        |n| n.to_string_lossy().to_string(),
+    );
+    let ctx = RepoContext {
+        repo: &worktree.path,
+        name: main.name,
+        branch: &worktree.branch,
+        worktree: Some(&label),
+        root_display: main.root_display,
+        root_full: main.root_full,
+        include_untracked: main.include_untracked,
+        submodules: main.submodules,
+    };
+    let recorded =
+        record_uncommitted(&ctx, git, data).and_then(|()| record_staged(&ctx, git, data));
+    if let Err(err) = recorded {
+        if opts.debug {
+            eprintln!("[debug] worktree={} error={err}", worktree.path.display());
+        }
+        data.errors.push(ctx.error(err));
     }
-    let metrics = uncommitted_metrics(ctx.repo, !opts.no_untracked, git);
-    let upstream = upstream_remote_url(ctx.repo, git)
-        .map(|url| normalize_upstream_url(&url))
-        .filter(|url| !url.is_empty());
+}
+
+/// Report each checked-out submodule of `sup` as a child row named `<superproject>/<path>`.
+///
+/// Failures inside a submodule are recorded against that submodule and do not stop the scan
+/// of the superproject or its other submodules.
+fn record_submodules(
+    sup: &RepoContext<'_>,
+    opts: &Options,
+    git: &dyn crate::git::GitRunner,
+    clock: &dyn Clock,
+    data: &mut ReportData,
+) -> Result<(), GitCommandError> {
+    for submodule in list_submodules(sup.repo, git)? {
+        let path = sup.repo.join(&submodule.path);
+        if !path.join(".git").exists() {
+            continue;
+        }
+        let name = format!("{}/{}", sup.name, submodule.path.display());
+        let branch = current_branch(&path, git);
+        let ctx = RepoContext {
+            repo: &path,
+            name: &name,
+            branch: branch.as_deref().unwrap_or("HEAD"),
+            ..*sup
+        };
+        let inspected = branch
+            .as_ref()
+            .map_err(Clone::clone)
+            .and_then(|_| inspect_submodule(&ctx, sup, &submodule, opts, git, clock, data));
+        if let Err(err) = inspected {
+            if opts.debug {
+                eprintln!("[debug] submodule={} error={err}", path.display());
+            }
+            data.errors.push(ctx.error(err));
+        }
+    }
+    Ok(())
+}
+
+fn inspect_submodule(
+    ctx: &RepoContext<'_>,
+    sup: &RepoContext<'_>,
+    submodule: &Submodule,
+    opts: &Options,
+    git: &dyn crate::git::GitRunner,
+    clock: &dyn Clock,
+    data: &mut ReportData,
+) -> Result<(), GitCommandError> {
+    record_uncommitted(ctx, git, data)?;
+    record_staged(ctx, git, data)?;
+    let branches: Vec<(String, String)> = list_local_branches(ctx.repo, git)?
+        .into_iter()
+        .filter_map(|(branch, upstream)| upstream.map(|upstream| (branch, upstream)))
+        .collect();
+    record_pushables(ctx, branches, git, clock, data)?;
+
+    // A recorded commit the submodule lacks was never made here, so there is nothing to push.
+    if let Some(revs) = unpushed_commit_count(ctx.repo, git, &submodule.commit)?
+        && revs > 0
+    {
+        data.submodules.push(SubmoduleEntry {
+            repo: sup.name.to_string(),
+            path: submodule.path.display().to_string(),
+            commit: submodule.commit.chars().take(12).collect(),
+            revs,
+            root_display: ctx.root_display.to_string(),
+            root_full: ctx.root_full.to_string(),
+            repo_path: sup.repo.to_path_buf(),
+        });
+    }
+    record_submodules(ctx, opts, git, clock, data)
+}
+
+fn record_uncommitted(
+    ctx: &RepoContext<'_>,
+    git: &dyn crate::git::GitRunner,
+    data: &mut ReportData,
+) -> Result<(), GitCommandError> {
+    if !has_uncommitted(ctx.repo, ctx.include_untracked, ctx.submodules, git)? {
+        return Ok(());
+    }
+    let metrics = uncommitted_metrics(ctx.repo, ctx.include_untracked, ctx.submodules, git)?;
     data.uncommitted.push(UncommittedEntry {
         repo: ctx.name.to_string(),
         branch: ctx.branch.to_string(),
-        upstream,
+        upstream: upstream_url(ctx, git)?,
+        worktree: ctx.worktree.map(str::to_string),
         lines: metrics.lines,
         files: metrics.files,
         untracked: metrics.untracked,
         root_display: ctx.root_display.to_string(),
         root_full: ctx.root_full.to_string(),
+        path: ctx.repo.to_path_buf(),
     });
+    Ok(())
 }
 
-fn record_staged(ctx: &RepoContext<'_>, git: &dyn crate::git::GitRunner, data: &mut ReportData) {
-    if !has_staged(ctx.repo, git) {
-        return;
+/// The upstream remote's URL in its short display form, e.g. `acme/app.git`.
+fn upstream_url(
+    ctx: &RepoContext<'_>,
+    git: &dyn crate::git::GitRunner,
+) -> Result<Option<String>, GitCommandError> {
+    Ok(upstream_remote_url(ctx.repo, git, ctx.branch)?
+        .map(|url| normalize_upstream_url(&url))
+        .filter(|url| !url.is_empty()))
+}
+
+fn record_staged(
+    ctx: &RepoContext<'_>,
+    git: &dyn crate::git::GitRunner,
+    data: &mut ReportData,
+) -> Result<(), GitCommandError> {
+    if !has_staged(ctx.repo, ctx.submodules, git)? {
+        return Ok(());
     }
-    let metrics = staged_metrics(ctx.repo, git);
+    let metrics = staged_metrics(ctx.repo, ctx.submodules, git)?;
     data.staged.push(StagedEntry {
         repo: ctx.name.to_string(),
         branch: ctx.branch.to_string(),
+        upstream: upstream_url(ctx, git)?,
+        worktree: ctx.worktree.map(str::to_string),
         lines: metrics.lines,
         files: metrics.files,
         untracked: metrics.untracked,
         root_display: ctx.root_display.to_string(),
         root_full: ctx.root_full.to_string(),
+        path: ctx.repo.to_path_buf(),
     });
+    Ok(())
 }
 
-fn refresh_remotes(
-    repo: &Path,
-    opts: &Options,
+fn record_stashes(
+    ctx: &RepoContext<'_>,
     git: &dyn crate::git::GitRunner,
-    branches: &[(String, String)],
-) {
-    if !opts.refresh_remotes || branches.is_empty() {
-        return;
-    }
-    let mut remotes = HashSet::new();
-    for (_, upstream) in branches {
-        if let Some((remote, _rest)) = upstream.split_once('/') {
-            remotes.insert(remote.to_string());
-        }
-    }
-    for remote in remotes {
-        let _ = fetch_remote(repo, git, &remote);
+    clock: &dyn Clock,
+    data: &mut ReportData,
+) -> Result<(), GitCommandError> {
+    for stash in list_stashes(ctx.repo, git, clock)? {
+        data.stashes.push(StashEntry {
+            repo: ctx.name.to_string(),
+            stash: stash.reference,
+            branch: stash.branch,
+            message: stash.message,
+            age_secs: stash.age.map(|d| d.as_secs()),
+            root_display: ctx.root_display.to_string(),
+            root_full: ctx.root_full.to_string(),
+            path: ctx.repo.to_path_buf(),
+        });
     }
+    Ok(())
 }
 
+/// Pushable commit count and earliest/latest commit ages, in seconds, of the checked-out branch.
+type HeadPushable = (Option<u64>, Option<u64>, Option<u64>);
+
 fn record_pushables(
     ctx: &RepoContext<'_>,
     branches: Vec<(String, String)>,
     git: &dyn crate::git::GitRunner,
     clock: &dyn Clock,
     data: &mut ReportData,
-) -> (Option<u64>, Option<u64>, Option<u64>) {
+) -> Result<HeadPushable, GitCommandError> {
     let mut head_revs = None;
     let mut head_earliest_secs = None;
     let mut head_latest_secs = None;
 
     for (branch_name, upstream) in branches {
-        let Some(ahead) =
-            crate::git::ahead_count_for_ref_pair(ctx.repo, git, &branch_name, &upstream)
-        else {
-            continue;
-        };
+        let ahead = crate::git::ahead_count_for_ref_pair(ctx.repo, git, &branch_name, &upstream)?;
         if branch_name == ctx.branch {
             head_revs = Some(ahead);
         }
+        record_pullable(ctx, &branch_name, &upstream, ahead, git, clock, data)?;
         if ahead == 0 {
             continue;
         }
@@ -146,8 +371,7 @@ fn record_pushables(
             clock,
             &branch_name,
             &upstream,
-        )
-        .unwrap_or((None, None));
+        )?;
         let earliest_secs = earliest.map(|d| d.as_secs());
         let latest_secs = latest.map(|d| d.as_secs());
         if branch_name == ctx.branch {
@@ -162,29 +386,85 @@ fn record_pushables(
             latest_secs,
             root_display: ctx.root_display.to_string(),
             root_full: ctx.root_full.to_string(),
+            path: ctx.repo.to_path_buf(),
         });
     }
 
-    (head_revs, head_earliest_secs, head_latest_secs)
+    Ok((head_revs, head_earliest_secs, head_latest_secs))
 }
 
-fn add_repo_summary(
+/// Record branches without an upstream that have commits not contained in the repo's base ref.
+///
+/// Without a base ref there is nothing to compare against, and the base branch itself never
+/// counts as unpublished, so neither is reported.
+fn record_unpublished(
     ctx: &RepoContext<'_>,
-    head_revs: Option<u64>,
-    head_earliest_secs: Option<u64>,
-    head_latest_secs: Option<u64>,
+    branches: &[(String, Option<String>)],
+    git: &dyn crate::git::GitRunner,
+    clock: &dyn Clock,
     data: &mut ReportData,
-) {
-    data.repos.push(RepoSummary {
+) -> Result<(), GitCommandError> {
+    if branches.is_empty() {
+        return Ok(());
+    }
+    let Some(base) = crate::git::default_base_ref(ctx.repo, git)? else {
+        return Ok(());
+    };
+    for (branch, _) in branches {
+        if *branch == base {
+            continue;
+        }
+        let range = format!("{base}..{branch}");
+        let revs = crate::git::commit_count(ctx.repo, git, &range)?;
+        if revs == 0 {
+            continue;
+        }
+        let (earliest, latest) = crate::git::commit_age_bounds(ctx.repo, git, clock, &range)?;
+        data.unpublished.push(UnpublishedEntry {
+            repo: ctx.name.to_string(),
+            branch: branch.clone(),
+            base: Some(base.clone()),
+            revs,
+            earliest_secs: earliest.map(|d| d.as_secs()),
+            latest_secs: latest.map(|d| d.as_secs()),
+            root_display: ctx.root_display.to_string(),
+            root_full: ctx.root_full.to_string(),
+            path: ctx.repo.to_path_buf(),
+        });
+    }
+    Ok(())
+}
+
+/// Record `branch` when its upstream has commits that are not present locally.
+fn record_pullable(
+    ctx: &RepoContext<'_>,
+    branch: &str,
+    upstream: &str,
+    ahead: u64,
+    git: &dyn crate::git::GitRunner,
+    clock: &dyn Clock,
+    data: &mut ReportData,
+) -> Result<(), GitCommandError> {
+    let behind = crate::git::ahead_count_for_ref_pair(ctx.repo, git, upstream, branch)?;
+    if behind == 0 {
+        return Ok(());
+    }
+    let (earliest, latest) =
+        crate::git::commit_age_bounds_for_ref_pair(ctx.repo, git, clock, upstream, branch)?;
+    data.pullable.push(PullableEntry {
         repo: ctx.name.to_string(),
-        branch: ctx.branch.to_string(),
-        path: ctx.repo.to_path_buf(),
+        branch: branch.to_string(),
+        upstream: upstream.to_string(),
+        behind,
+        ahead,
+        diverged: ahead > 0,
+        earliest_secs: earliest.map(|d| d.as_secs()),
+        latest_secs: latest.map(|d| d.as_secs()),
         root_display: ctx.root_display.to_string(),
         root_full: ctx.root_full.to_string(),
-        head_revs,
-        head_earliest_secs,
-        head_latest_secs,
+        path: ctx.repo.to_path_buf(),
     });
+    Ok(())
 }
 
 fn normalize_upstream_url(url: &str) -> String {
@@ -0,0 +1,114 @@
+//! Gitignore-style exclude and include patterns that prune the repository discovery walk.
+//!
+//! Each root may carry a `.uncommittedignore` file; patterns from the command line and config
+//! are layered after it, so they win when both match (the last matching pattern decides, as
+//! in `.gitignore`). Include patterns are negations and can only re-include a directory whose
+//! parent was not itself excluded.
+
+use std::path::{Path, PathBuf};
+
+use ignore::Match;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+
+/// Per-root file holding gitignore-style patterns for paths the scan should not descend into.
+pub const IGNORE_FILE: &str = ".uncommittedignore";
+
+/// Exclude and include patterns given on the command line or in the config file.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct DiscoveryPatterns<'a> {
+    pub(crate) exclude: &'a [String],
+    pub(crate) include: &'a [String],
+}
+
+/// Compiled patterns for one root.
+pub(crate) struct PathFilter {
+    matcher: Gitignore,
+}
+
+impl PathFilter {
+    /// Combine the root's ignore file with `patterns`; invalid lines are reported and dropped.
+    pub(crate) fn for_root(root: &Path, patterns: DiscoveryPatterns<'_>) -> Self {
+        let mut builder = GitignoreBuilder::new(root);
+        let file = root.join(IGNORE_FILE);
+        if file.is_file()
+            && let Some(err) = builder.add(&file)
+        {
+            eprintln!("warning: {}: {err}", file.display());
+        }
+        let lines = patterns
+            .exclude
+            .iter()
+            .map(String::clone)
+            .chain(patterns.include.iter().map(|pattern| format!("!{pattern}")));
+        for line in lines {
+            if let Err(err) = builder.add_line(None, &line) {
+                eprintln!("warning: ignoring pattern '{line}': {err}");
+            }
+        }
+        let matcher = builder.build().unwrap_or_else(|err| {
+            eprintln!("warning: ignoring patterns for {}: {err}", root.display());
+            Gitignore::empty()
+        });
+        PathFilter { matcher }
+    }
+
+    /// Whether the walk should descend into `dir`, with the reason when a pattern matched.
+    pub(crate) fn check(&self, dir: &Path) -> Verdict {
+        match self.matcher.matched(dir, true) {
+            Match::None => Verdict::Walk,
+            Match::Ignore(glob) => Verdict::Skip(Reason::new(glob)),
+            Match::Whitelist(glob) => Verdict::Include(Reason::new(glob)),
+        }
+    }
+
+    /// Whether `path` or a directory above it up to the root is excluded; paths outside the
+    /// root are never excluded.
+    pub(crate) fn excludes(&self, path: &Path) -> bool {
+        path.starts_with(self.matcher.path())
+            && self
+                .matcher
+                .matched_path_or_any_parents(path, path.is_dir())
+                .is_ignore()
+    }
+}
+
+pub(crate) enum Verdict {
+    Walk,
+    Skip(Reason),
+    Include(Reason),
+}
+
+/// The pattern that decided a verdict and where it was written.
+pub(crate) struct Reason {
+    pattern: String,
+    source: Option<PathBuf>,
+}
+
+impl Reason {
+    fn new(glob: &ignore::gitignore::Glob) -> Self {
+        Reason {
+            pattern: glob.original().to_string(),
+            source: glob.from().map(Path::to_path_buf),
+        }
+    }
+}
+
+impl std::fmt::Display for Reason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.source {
+            Some(file) => write!(f, "pattern '{}' in {}", self.pattern, file.display()),
+            None => write!(f, "pattern '{}' from options", self.pattern),
+        }
+    }
+}
+
+/// Check that `pattern` is a valid gitignore-style glob.
+///
+/// # Errors
+/// Returns the parser message when the glob cannot be compiled.
+pub fn validate_pattern(pattern: &str) -> Result<(), String> {
+    GitignoreBuilder::new("/")
+        .add_line(None, pattern)
+        .map(|_| ())
+        .map_err(|err| err.to_string())
+}
@@ -0,0 +1,373 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use walkdir::WalkDir;
+
+use crate::system::FsOps;
+use crate::types::Depth;
+
+mod filter;
+
+use filter::Verdict;
+pub(crate) use filter::{DiscoveryPatterns, PathFilter};
+pub use filter::{IGNORE_FILE, validate_pattern};
+
+/// How a discovered repository stores its git metadata.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum RepoLayout {
+    /// Working tree with a `.git` directory, or a `.git` file pointing at a private git dir
+    /// (submodule checkouts, `--separate-git-dir`).
+    Standard,
+    /// Repository without a working tree.
+    Bare,
+    /// Linked worktree created by `git worktree add`; `main` is the owning repository.
+    Worktree { main: PathBuf },
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct DiscoveredRepo {
+    pub(crate) path: PathBuf,
+    pub(crate) layout: RepoLayout,
+}
+
+/// Directories `Depth::Auto` visits under one root before it gives up on the rest.
+const AUTO_DIR_LIMIT: usize = 100_000;
+
+/// How `find_repos` walks each root.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Discovery<'a> {
+    pub(crate) depth: Depth,
+    pub(crate) patterns: DiscoveryPatterns<'a>,
+    /// With `Depth::Auto`, keep descending into repositories found on the way.
+    pub(crate) nested: bool,
+    pub(crate) debug: bool,
+}
+
+pub(crate) fn find_repos(
+    fs: &dyn FsOps,
+    roots: &[PathBuf],
+    discovery: &Discovery<'_>,
+) -> Vec<DiscoveredRepo> {
+    let debug = discovery.debug;
+    let mut repos = HashMap::<PathBuf, RepoLayout>::new();
+
+    for root in roots {
+        let root = fs.expand_tilde(root);
+        if !root.exists() {
+            if debug {
+                eprintln!("[debug] root missing: {}", root.display());
+            }
+            continue;
+        }
+
+        if fs.is_repo(&root) {
+            let layout = detect_layout(&root).unwrap_or(RepoLayout::Standard);
+            log_repo(debug, &root, &layout);
+            repos.insert(root.clone(), layout);
+            continue;
+        }
+
+        walk_root(&root, discovery, &mut repos);
+    }
+
+    let mut v: Vec<_> = repos
+        .into_iter()
+        .map(|(path, layout)| DiscoveredRepo { path, layout })
+        .collect();
+    v.sort_unstable_by(|a, b| (a.path.file_name(), &a.path).cmp(&(b.path.file_name(), &b.path)));
+    v
+}
+
+fn walk_root(root: &Path, discovery: &Discovery<'_>, repos: &mut HashMap<PathBuf, RepoLayout>) {
+    let debug = discovery.debug;
+    let started = Instant::now();
+    let filter = PathFilter::for_root(root, discovery.patterns);
+    let walker = WalkDir::new(root).min_depth(1).follow_links(false);
+    let (walker, stop_at_repos) = match discovery.depth {
+        Depth::Levels(levels) => (walker.max_depth(levels), false),
+        Depth::Auto => (walker, !discovery.nested),
+    };
+    let mut walker = walker.into_iter();
+    let mut visited = 0usize;
+    let mut found = 0usize;
+    while let Some(entry) = walker.next() {
+        let Ok(entry) = entry else {
+            continue;
+        };
+        if !entry.file_type().is_dir() {
+            continue;
+        }
+        let p = entry.path();
+        if p.file_name().is_some_and(|n| n == ".git") {
+            walker.skip_current_dir();
+            continue;
+        }
+        visited += 1;
+        if discovery.depth == Depth::Auto && visited > AUTO_DIR_LIMIT {
+            eprintln!(
+                "warning: stopped scanning {} after {AUTO_DIR_LIMIT} directories; \
+                 use a numeric --depth or --exclude to narrow the search",
+                root.display()
+            );
+            break;
+        }
+        match filter.check(p) {
+            Verdict::Skip(reason) => {
+                if debug {
+                    eprintln!("[debug] skip: {} (excluded by {reason})", p.display());
+                }
+                walker.skip_current_dir();
+                continue;
+            }
+            Verdict::Include(reason) if debug => {
+                eprintln!("[debug] include: {} (included by {reason})", p.display());
+            }
+            Verdict::Include(_) | Verdict::Walk => {}
+        }
+        let Some(layout) = detect_layout(p) else {
+            continue;
+        };
+        log_repo(debug, p, &layout);
+        if layout == RepoLayout::Bare || stop_at_repos {
+            walker.skip_current_dir();
+        }
+        found += 1;
+        repos.insert(p.to_path_buf(), layout);
+    }
+    if debug {
+        eprintln!(
+            "[debug] walked {} depth={} dirs_visited={visited} repos_found={found} elapsed={:?}",
+            root.display(),
+            discovery.depth,
+            started.elapsed()
+        );
+    }
+}
+
+/// Classify `dir` as a repository, returning `None` when it holds no git metadata.
+pub(crate) fn detect_layout(dir: &Path) -> Option<RepoLayout> {
+    let dot_git = dir.join(".git");
+    if dot_git.is_dir() {
+        return Some(RepoLayout::Standard);
+    }
+    if dot_git.is_file() {
+        let git_dir = read_gitdir_file(&dot_git)?;
+        return Some(match common_dir(&git_dir) {
+            Some(common) => RepoLayout::Worktree {
+                main: main_repo_path(&common),
+            },
+            None => RepoLayout::Standard,
+        });
+    }
+    if is_bare_git_dir(dir) {
+        return Some(RepoLayout::Bare);
+    }
+    None
+}
+
+fn read_gitdir_file(dot_git: &Path) -> Option<PathBuf> {
+    let text = fs::read_to_string(dot_git).ok()?;
+    let target = text
+        .lines()
+        .find_map(|line| line.strip_prefix("gitdir:"))?
+        .trim();
+    if target.is_empty() {
+        return None;
+    }
+    let base = dot_git.parent().unwrap_or_else(|| Path::new("."));
+    Some(base.join(target))
+}
+
+/// Linked worktree git dirs carry a `commondir` file pointing back at the shared git dir.
+fn common_dir(git_dir: &Path) -> Option<PathBuf> {
+    let text = fs::read_to_string(git_dir.join("commondir")).ok()?;
+    let common = git_dir.join(text.trim());
+    Some(fs::canonicalize(&common).unwrap_or(common))
+}
+
+fn main_repo_path(common: &Path) -> PathBuf {
+    if common.file_name().is_some_and(|n| n == ".git")
+        && let Some(parent) = common.parent()
+    {
+        return parent.to_path_buf();
+    }
+    common.to_path_buf()
+}
+
+fn is_bare_git_dir(dir: &Path) -> bool {
+    dir.join("HEAD").is_file() && dir.join("objects").is_dir() && dir.join("refs").is_dir()
+}
+
+fn log_repo(debug: bool, path: &Path, layout: &RepoLayout) {
+    if !debug {
+        return;
+    }
+    match layout {
+        RepoLayout::Standard => eprintln!("[debug] repo: {}", path.display()),
+        RepoLayout::Bare => eprintln!("[debug] bare repo: {}", path.display()),
+        RepoLayout::Worktree { main } => eprintln!(
+            "[debug] worktree: {} (main: {})",
+            path.display(),
+            main.display()
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::system::DefaultFsOps;
+    use tempfile::tempdir;
+
+    fn discovery(depth: Depth) -> Discovery<'static> {
+        Discovery {
+            depth,
+            patterns: DiscoveryPatterns::default(),
+            nested: false,
+            debug: false,
+        }
+    }
+
+    fn make_bare(dir: &Path) {
+        fs::create_dir_all(dir.join("objects")).expect("objects");
+        fs::create_dir_all(dir.join("refs")).expect("refs");
+        fs::write(dir.join("HEAD"), "ref: refs/heads/main\n").expect("HEAD");
+    }
+
+    #[test]
+    fn find_repos_detects_worktrees_gitdir_files_and_bare_repos() {
+        let temp = tempdir().expect("tempdir");
+        let root = temp.path();
+
+        let main = root.join("main");
+        let wt_git_dir = main.join(".git").join("worktrees").join("feature");
+        fs::create_dir_all(&wt_git_dir).expect("worktree git dir");
+        fs::write(wt_git_dir.join("commondir"), "../..\n").expect("commondir");
+
+        let worktree = root.join("feature");
+        fs::create_dir_all(&worktree).expect("worktree dir");
+        fs::write(
+            worktree.join(".git"),
+            format!("gitdir: {}\n", wt_git_dir.display()),
+        )
+        .expect("worktree .git file");
+
+        let module_git_dir = main.join(".git").join("modules").join("sub");
+        make_bare(&module_git_dir);
+        let submodule = root.join("sub");
+        fs::create_dir_all(&submodule).expect("submodule dir");
+        fs::write(submodule.join(".git"), "gitdir: ../main/.git/modules/sub\n")
+            .expect("submodule .git file");
+
+        make_bare(&root.join("mirror.git"));
+
+        let repos = find_repos(
+            &DefaultFsOps,
+            &[root.to_path_buf()],
+            &discovery(Depth::Levels(1)),
+        );
+        let layouts: Vec<(String, RepoLayout)> = repos
+            .into_iter()
+            .map(|r| {
+                let name = r.path.file_name().unwrap().to_string_lossy().to_string();
+                (name, r.layout)
+            })
+            .collect();
+
+        let main_canonical = fs::canonicalize(&main).expect("canonical main");
+        assert_eq!(
+            layouts,
+            vec![
+                (
+                    "feature".to_string(),
+                    RepoLayout::Worktree {
+                        main: main_canonical
+                    }
+                ),
+                ("main".to_string(), RepoLayout::Standard),
+                ("mirror.git".to_string(), RepoLayout::Bare),
+                ("sub".to_string(), RepoLayout::Standard),
+            ]
+        );
+    }
+
+    #[test]
+    fn find_repos_prunes_excluded_directories() {
+        let temp = tempdir().expect("tempdir");
+        let root = temp.path();
+        for repo in [
+            "app",
+            "node_modules/dep",
+            "archive/old",
+            "archive/keep",
+            "vendor/lib",
+        ] {
+            fs::create_dir_all(root.join(repo).join(".git")).expect("repo");
+        }
+        fs::write(root.join(IGNORE_FILE), "# vendored code\narchive/*\n").expect("ignore file");
+
+        let exclude = vec!["node_modules".to_string(), "vendor/".to_string()];
+        let include = vec!["archive/keep".to_string()];
+        let patterns = DiscoveryPatterns {
+            exclude: &exclude,
+            include: &include,
+        };
+        let discovery = Discovery {
+            patterns,
+            ..discovery(Depth::Levels(2))
+        };
+        let repos = find_repos(&DefaultFsOps, &[root.to_path_buf()], &discovery);
+        let found: Vec<PathBuf> = repos
+            .into_iter()
+            .map(|r| r.path.strip_prefix(root).unwrap().to_path_buf())
+            .collect();
+        assert_eq!(
+            found,
+            vec![PathBuf::from("app"), PathBuf::from("archive/keep")]
+        );
+    }
+
+    #[test]
+    fn validate_pattern_rejects_broken_globs() {
+        assert!(validate_pattern("target/").is_ok());
+        assert!(validate_pattern("src/[z-a]").is_err());
+    }
+
+    #[test]
+    fn auto_depth_stops_at_repository_boundaries() {
+        let temp = tempdir().expect("tempdir");
+        let root = temp.path();
+        for repo in ["org/team/project", "org/team/project/vendor/inner", "solo"] {
+            fs::create_dir_all(root.join(repo).join(".git")).expect("repo");
+        }
+        fs::create_dir_all(root.join("solo/deep/deeper")).expect("working tree");
+
+        let names = |discovery: &Discovery<'_>| -> Vec<PathBuf> {
+            find_repos(&DefaultFsOps, &[root.to_path_buf()], discovery)
+                .into_iter()
+                .map(|r| r.path.strip_prefix(root).unwrap().to_path_buf())
+                .collect()
+        };
+        assert_eq!(
+            names(&discovery(Depth::Auto)),
+            vec![PathBuf::from("org/team/project"), PathBuf::from("solo")]
+        );
+        let nested = Discovery {
+            nested: true,
+            ..discovery(Depth::Auto)
+        };
+        assert_eq!(
+            names(&nested),
+            vec![
+                PathBuf::from("org/team/project/vendor/inner"),
+                PathBuf::from("org/team/project"),
+                PathBuf::from("solo"),
+            ]
+        );
+        assert_eq!(
+            names(&discovery(Depth::Levels(2))),
+            vec![PathBuf::from("solo")]
+        );
+    }
+}
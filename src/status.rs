@@ -0,0 +1,342 @@
+//! Compact report summaries for shell prompts and status bars, cached between runs.
+//!
+//! `uncommitted status` prints a line like `U3 S1 P5 R2` from a [`StatusCache`] file, so a
+//! prompt redrawn on every command does not rescan its roots each time. Caches live in
+//! `$UNCOMMITTED_CACHE_DIR`, else `$XDG_CACHE_HOME/uncommitted` (falling back to `~/.cache`),
+//! one file per set of scan options.
+
+use std::fs::{self, OpenOptions};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::ReportData;
+
+/// Environment variable naming the directory to keep status caches in.
+pub const CACHE_DIR_ENV: &str = "UNCOMMITTED_CACHE_DIR";
+
+/// Summary printed when no `--format` is given.
+pub const DEFAULT_STATUS_FORMAT: &str = "U{uncommitted} S{staged} P{pushable} R{pullable}";
+
+/// A refresh lock older than this is assumed to belong to a refresh that died.
+const STALE_LOCK: Duration = Duration::from_mins(10);
+
+/// The counters a status line can show.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StatusCounts {
+    /// Repositories with uncommitted changes.
+    pub uncommitted: u64,
+    /// Repositories with staged changes.
+    pub staged: u64,
+    /// Commits not pushed to their upstreams.
+    pub pushable: u64,
+    /// Upstream commits not pulled yet.
+    pub pullable: u64,
+    /// Local branches without upstream that carry commits.
+    pub unpublished: u64,
+    pub stashes: u64,
+    /// Repositories that could not be inspected.
+    pub errors: u64,
+}
+
+impl StatusCounts {
+    #[must_use]
+    pub fn from_report(data: &ReportData) -> Self {
+        let len = |len: usize| len as u64;
+        StatusCounts {
+            uncommitted: len(data.uncommitted.len()),
+            staged: len(data.staged.len()),
+            pushable: data.pushable.iter().map(|entry| entry.revs).sum(),
+            pullable: data.pullable.iter().map(|entry| entry.behind).sum(),
+            unpublished: len(data.unpublished.len()),
+            stashes: len(data.stashes.len()),
+            errors: len(data.errors.len()),
+        }
+    }
+
+    fn get(&self, name: &str) -> Option<u64> {
+        Some(match name {
+            "uncommitted" => self.uncommitted,
+            "staged" => self.staged,
+            "pushable" => self.pushable,
+            "pullable" => self.pullable,
+            "unpublished" => self.unpublished,
+            "stashes" => self.stashes,
+            "errors" => self.errors,
+            _ => return None,
+        })
+    }
+}
+
+/// Fill the `{counter}` placeholders of `format` from `counts`. Whitespace-separated parts
+/// whose placeholders are all 0 are left out, so `U{uncommitted} P{pushable}` prints `P5`
+/// when nothing is uncommitted and nothing at all when everything is clean.
+///
+/// # Errors
+/// Returns an error naming the first unknown or unterminated placeholder.
+pub fn format_status(format: &str, counts: &StatusCounts) -> Result<String, StatusError> {
+    let mut parts = Vec::new();
+    for part in format.split_whitespace() {
+        let mut out = String::new();
+        let mut counters = 0;
+        let mut zeros = 0;
+        let mut rest = part;
+        while let Some(start) = rest.find('{') {
+            out.push_str(&rest[..start]);
+            let Some(len) = rest[start..].find('}') else {
+                return Err(StatusError::Format(rest[start..].to_string()));
+            };
+            let name = &rest[start + 1..start + len];
+            let value = counts
+                .get(name)
+                .ok_or_else(|| StatusError::Format(format!("{{{name}}}")))?;
+            counters += 1;
+            if value == 0 {
+                zeros += 1;
+            }
+            out.push_str(&value.to_string());
+            rest = &rest[start + len + 1..];
+        }
+        out.push_str(rest);
+        if counters == 0 || zeros < counters {
+            parts.push(out);
+        }
+    }
+    Ok(parts.join(" "))
+}
+
+/// Counts of the last scan with a set of options, as stored in the cache file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StatusCache {
+    /// The scan options the counts were collected with.
+    pub key: String,
+    /// Seconds since the Unix epoch.
+    pub timestamp: u64,
+    pub counts: StatusCounts,
+}
+
+impl StatusCache {
+    /// Whether the cache was written for `key` less than `ttl` before `now`.
+    #[must_use]
+    pub fn is_fresh(&self, key: &str, now: SystemTime, ttl: Duration) -> bool {
+        let age = unix_secs(now).saturating_sub(self.timestamp);
+        self.key == key && Duration::from_secs(age) < ttl
+    }
+}
+
+/// Resolve the cache directory from the environment and the XDG base directories.
+#[must_use]
+pub fn locate_status_dir() -> Option<PathBuf> {
+    if let Some(path) = non_empty_env(CACHE_DIR_ENV) {
+        return Some(PathBuf::from(path));
+    }
+    let base = non_empty_env("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+        .or_else(|| non_empty_env("HOME").map(|home| PathBuf::from(home).join(".cache")))?;
+    Some(base.join("uncommitted"))
+}
+
+/// Cache file in `dir` for the scan options `key`.
+///
+/// The name is a 64-bit FNV-1a hash of `key`, which unlike `std`'s hasher stays the same
+/// across Rust releases, so an upgraded binary still finds the caches of the previous one.
+#[must_use]
+pub fn status_cache_path(dir: &Path, key: &str) -> PathBuf {
+    let hash = key.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    });
+    dir.join(format!("status-{hash:016x}.json"))
+}
+
+/// Read the cache at `path`; a missing file is `None`.
+///
+/// # Errors
+/// Returns an error if the file exists but cannot be read or parsed.
+pub fn load_status(path: &Path) -> Result<Option<StatusCache>, StatusError> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(source) => {
+            return Err(StatusError::Io {
+                path: path.to_path_buf(),
+                source,
+            });
+        }
+    };
+    serde_json::from_str(&text)
+        .map(Some)
+        .map_err(|source| StatusError::Parse {
+            path: path.to_path_buf(),
+            source,
+        })
+}
+
+/// Write `cache` to `path` through a temporary file, so readers never see half a cache.
+///
+/// # Errors
+/// Returns an error if the directory or file cannot be created or written.
+pub fn save_status(path: &Path, cache: &StatusCache) -> Result<(), StatusError> {
+    let io_err = |source| StatusError::Io {
+        path: path.to_path_buf(),
+        source,
+    };
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::create_dir_all(dir).map_err(io_err)?;
+    }
+    let text = serde_json::to_string(cache).map_err(|e| io_err(e.into()))?;
+    let tmp = path.with_extension(format!("tmp{}", std::process::id()));
+    fs::write(&tmp, text).map_err(io_err)?;
+    fs::rename(&tmp, path).map_err(io_err)
+}
+
+/// Claim the refresh of the cache at `path`: `true` if no other refresh holds its lock file.
+/// The refresh releases the claim with [`release_refresh`].
+#[must_use]
+pub fn claim_refresh(path: &Path, now: SystemTime) -> bool {
+    let lock = lock_path(path);
+    if let Some(dir) = lock.parent() {
+        let _ = fs::create_dir_all(dir);
+    }
+    let create = || OpenOptions::new().write(true).create_new(true).open(&lock);
+    if create().is_ok() {
+        return true;
+    }
+    let abandoned = fs::metadata(&lock)
+        .and_then(|meta| meta.modified())
+        .is_ok_and(|modified| now.duration_since(modified).unwrap_or_default() > STALE_LOCK);
+    abandoned && fs::remove_file(&lock).is_ok() && create().is_ok()
+}
+
+/// Drop the lock taken by [`claim_refresh`].
+pub fn release_refresh(path: &Path) {
+    let _ = fs::remove_file(lock_path(path));
+}
+
+fn lock_path(path: &Path) -> PathBuf {
+    path.with_extension("lock")
+}
+
+fn unix_secs(time: SystemTime) -> u64 {
+    time.duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
+
+/// A [`StatusCache`] stamped with `now`.
+#[must_use]
+pub fn new_status_cache(key: &str, now: SystemTime, counts: StatusCounts) -> StatusCache {
+    StatusCache {
+        key: key.to_string(),
+        timestamp: unix_secs(now),
+        counts,
+    }
+}
+
+#[derive(Debug)]
+pub enum StatusError {
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    Parse {
+        path: PathBuf,
+        source: serde_json::Error,
+    },
+    /// A `--format` placeholder that names no counter or is not closed.
+    Format(String),
+}
+
+impl std::fmt::Display for StatusError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StatusError::Io { path, source } => {
+                write!(
+                    f,
+                    "failed to access status cache {}: {source}",
+                    path.display()
+                )
+            }
+            StatusError::Parse { path, source } => {
+                write!(f, "invalid status cache {}: {source}", path.display())
+            }
+            StatusError::Format(placeholder) => write!(
+                f,
+                "unknown status placeholder '{placeholder}' (use {{uncommitted}}, {{staged}}, \
+                 {{pushable}}, {{pullable}}, {{unpublished}}, {{stashes}} or {{errors}})"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for StatusError {}
+
+fn non_empty_env(key: &str) -> Option<std::ffi::OsString> {
+    std::env::var_os(key).filter(|value| !value.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn format_status_drops_parts_that_are_all_zero() -> Result<(), StatusError> {
+        let counts = StatusCounts {
+            uncommitted: 3,
+            pushable: 5,
+            ..StatusCounts::default()
+        };
+        assert_eq!(format_status(DEFAULT_STATUS_FORMAT, &counts)?, "U3 P5");
+        assert_eq!(
+            format_status("git: {staged}/{pushable} [{errors}!]", &counts)?,
+            "git: 0/5"
+        );
+        assert_eq!(
+            format_status(DEFAULT_STATUS_FORMAT, &StatusCounts::default())?,
+            ""
+        );
+        assert!(matches!(
+            format_status("U{uncomitted}", &counts),
+            Err(StatusError::Format(name)) if name == "{uncomitted}"
+        ));
+        assert!(matches!(
+            format_status("U{uncommitted", &counts),
+            Err(StatusError::Format(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn cache_round_trips_and_expires() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = TempDir::new()?;
+        let path = status_cache_path(dir.path(), "roots=~/src");
+        assert_eq!(path, dir.path().join("status-8312dd509d9b9f70.json"));
+        assert_ne!(path, status_cache_path(dir.path(), "roots=/srv"));
+        assert_eq!(load_status(&path)?, None);
+
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let counts = StatusCounts {
+            staged: 1,
+            ..StatusCounts::default()
+        };
+        save_status(&path, &new_status_cache("roots=~/src", now, counts))?;
+        let Some(cache) = load_status(&path)? else {
+            panic!("cache not written");
+        };
+        assert_eq!(cache.counts, counts);
+
+        let ttl = Duration::from_mins(1);
+        assert!(cache.is_fresh("roots=~/src", now + Duration::from_secs(59), ttl));
+        assert!(!cache.is_fresh("roots=~/src", now + ttl, ttl));
+        assert!(!cache.is_fresh("roots=/srv", now, ttl));
+
+        assert!(claim_refresh(&path, now));
+        assert!(!claim_refresh(&path, SystemTime::now()));
+        assert!(claim_refresh(&path, SystemTime::now() + STALE_LOCK * 2));
+        release_refresh(&path);
+        assert!(claim_refresh(&path, SystemTime::now()));
+        Ok(())
+    }
+}
@@ -1,7 +1,7 @@
 use std::path::{Path, PathBuf};
 use std::time::SystemTime;
 
-pub trait FsOps {
+pub trait FsOps: Send + Sync {
     fn is_repo(&self, dir: &Path) -> bool;
     fn expand_tilde(&self, p: &Path) -> PathBuf;
 }
@@ -9,7 +9,7 @@ pub trait FsOps {
 pub struct DefaultFsOps;
 impl FsOps for DefaultFsOps {
     fn is_repo(&self, dir: &Path) -> bool {
-        dir.join(".git").is_dir()
+        crate::scan::detect_layout(dir).is_some()
     }
     fn expand_tilde(&self, p: &Path) -> PathBuf {
         if let Some(home) = std::env::var_os("HOME") {
@@ -24,7 +24,7 @@ impl FsOps for DefaultFsOps {
     }
 }
 
-pub trait Clock {
+pub trait Clock: Send + Sync {
     fn now(&self) -> SystemTime;
 }
 
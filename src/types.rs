@@ -1,31 +1,48 @@
+use std::borrow::Cow;
 use std::path::PathBuf;
+use std::time::Duration;
 
-#[derive(Debug, Clone)]
+use schemars::{JsonSchema, Schema, SchemaGenerator, json_schema};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct UncommittedEntry {
     pub repo: String,
     pub branch: String,
     pub upstream: Option<String>,
+    /// Linked worktree directory name when the row belongs to a worktree of `repo`.
+    pub worktree: Option<String>,
     pub lines: u64,
     pub files: u64,
     pub untracked: u64,
     // Root as passed on CLI (e.g., "~/src")
     pub root_display: String,
-    // Expanded root path for JSON (e.g., "/home/user/src")
+    // Expanded root path, written as `root` in JSON (e.g., "/home/user/src")
+    #[serde(rename = "root", alias = "root_full")]
     pub root_full: String,
+    /// Directory the changes are in: the repository, or the linked worktree if `worktree` is set.
+    #[serde(default)]
+    pub path: PathBuf,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct StagedEntry {
     pub repo: String,
     pub branch: String,
+    pub upstream: Option<String>,
+    pub worktree: Option<String>,
     pub lines: u64,
     pub files: u64,
     pub untracked: u64,
     pub root_display: String,
+    #[serde(rename = "root", alias = "root_full")]
     pub root_full: String,
+    /// Directory of the repository or linked worktree.
+    #[serde(default)]
+    pub path: PathBuf,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct PushableEntry {
     pub repo: String,
     pub branch: String,
@@ -33,10 +50,163 @@ pub struct PushableEntry {
     pub earliest_secs: Option<u64>,
     pub latest_secs: Option<u64>,
     pub root_display: String,
+    #[serde(rename = "root", alias = "root_full")]
+    pub root_full: String,
+    /// Directory of the repository; repository names alone are not unique.
+    #[serde(default)]
+    pub path: PathBuf,
+}
+
+/// Local branch whose upstream has commits the branch does not contain.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PullableEntry {
+    pub repo: String,
+    pub branch: String,
+    pub upstream: String,
+    /// Commits on the upstream that are missing locally (`branch..upstream`).
+    pub behind: u64,
+    /// Local commits missing from the upstream (`upstream..branch`).
+    pub ahead: u64,
+    /// True when the branch is both ahead of and behind its upstream.
+    pub diverged: bool,
+    // Age bounds of the incoming (behind) commits
+    pub earliest_secs: Option<u64>,
+    pub latest_secs: Option<u64>,
+    pub root_display: String,
+    #[serde(rename = "root", alias = "root_full")]
+    pub root_full: String,
+    #[serde(default)]
+    pub path: PathBuf,
+}
+
+/// Local branch without a configured upstream that carries commits of its own.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct UnpublishedEntry {
+    pub repo: String,
+    pub branch: String,
+    /// Ref the commit count is relative to (remote HEAD or local default branch). Branches of
+    /// repos without a base are not reported, so this is only `None` in older reports.
+    pub base: Option<String>,
+    pub revs: u64,
+    pub earliest_secs: Option<u64>,
+    pub latest_secs: Option<u64>,
+    pub root_display: String,
+    #[serde(rename = "root", alias = "root_full")]
+    pub root_full: String,
+    #[serde(default)]
+    pub path: PathBuf,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct StashEntry {
+    pub repo: String,
+    /// Stash reference, e.g. `stash@{0}`.
+    pub stash: String,
+    /// Branch the stash was created on (empty when git did not record one).
+    pub branch: String,
+    pub message: String,
+    pub age_secs: Option<u64>,
+    pub root_display: String,
+    #[serde(rename = "root", alias = "root_full")]
+    pub root_full: String,
+    #[serde(default)]
+    pub path: PathBuf,
+}
+
+/// Submodule whose commit recorded in the superproject is on no remote-tracking ref of the
+/// submodule, so pushing the superproject would publish a dangling gitlink.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SubmoduleEntry {
+    /// Superproject name.
+    pub repo: String,
+    /// Submodule path inside the superproject.
+    pub path: String,
+    /// Abbreviated commit recorded by the superproject's gitlink.
+    pub commit: String,
+    /// Commits reachable from `commit` that no remote-tracking ref contains.
+    pub revs: u64,
+    pub root_display: String,
+    #[serde(rename = "root", alias = "root_full")]
+    pub root_full: String,
+    /// Directory of the superproject.
+    #[serde(default)]
+    pub repo_path: PathBuf,
+}
+
+/// How fetching one remote of one repository went.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FetchStatus {
+    /// Fetched from the remote itself.
+    Fetched,
+    /// Copied from another repository that had just fetched the same URL.
+    Shared,
+    Failed,
+    /// Every attempt was killed after the fetch timeout.
+    TimedOut,
+    /// Not attempted because fetching the same URL for another repository failed.
+    Skipped,
+}
+
+impl FetchStatus {
+    #[must_use]
+    pub fn label(self) -> &'static str {
+        match self {
+            FetchStatus::Fetched => "fetched",
+            FetchStatus::Shared => "shared",
+            FetchStatus::Failed => "failed",
+            FetchStatus::TimedOut => "timed out",
+            FetchStatus::Skipped => "skipped",
+        }
+    }
+
+    #[must_use]
+    pub fn is_ok(self) -> bool {
+        matches!(self, FetchStatus::Fetched | FetchStatus::Shared)
+    }
+}
+
+/// One remote fetched by `--refresh-remotes`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FetchEntry {
+    pub repo: String,
+    pub remote: String,
+    pub url: Option<String>,
+    pub status: FetchStatus,
+    /// Repository the refs were copied from when `status` is `Shared`.
+    pub source: Option<String>,
+    /// Network attempts made, including retries (0 for shared or skipped fetches).
+    pub attempts: u32,
+    pub duration_ms: u64,
+    /// Error of the last attempt, trimmed to a short excerpt.
+    pub error: Option<String>,
+    pub root_display: String,
+    #[serde(rename = "root", alias = "root_full")]
     pub root_full: String,
 }
 
-#[derive(Debug, Clone)]
+/// A repository (or one of its linked worktrees) that git could not inspect.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RepoError {
+    pub repo: String,
+    pub worktree: Option<String>,
+    /// The failing command line, e.g. `git diff --quiet --ignore-submodules -- .`.
+    pub command: String,
+    /// Exit code, or `None` when git could not be spawned or was killed by a signal.
+    pub status: Option<i32>,
+    /// Set when git was killed for exceeding the command timeout.
+    pub timed_out: bool,
+    /// Leading stderr output, trimmed to a short excerpt.
+    pub stderr: String,
+    pub root_display: String,
+    #[serde(rename = "root", alias = "root_full")]
+    pub root_full: String,
+    /// Directory of the repository or linked worktree that failed.
+    #[serde(default)]
+    pub path: PathBuf,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct GitRewriteEntry {
     pub source_repo: String,
     pub source_branch: String,
@@ -49,30 +219,42 @@ pub struct GitRewriteEntry {
     pub latest_secs: Option<u64>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct RepoSummary {
     pub repo: String,
     pub branch: String,
     pub path: PathBuf,
     pub root_display: String,
+    #[serde(rename = "root", alias = "root_full")]
     pub root_full: String,
     pub head_revs: Option<u64>,
     pub head_earliest_secs: Option<u64>,
     pub head_latest_secs: Option<u64>,
+    pub bare: bool,
+    pub worktrees: Vec<WorktreeSummary>,
 }
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct WorktreeSummary {
+    pub path: PathBuf,
+    pub branch: String,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
 pub enum UntrackedReason {
     Ignored,
     MissingConfig,
+    #[serde(rename = "missing")]
     MissingRepo,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct UntrackedRepoEntry {
     pub repo: String,
     pub branch: String,
     pub root_display: String,
+    #[serde(rename = "root", alias = "root_full")]
     pub root_full: String,
     pub revs: Option<u64>,
     pub earliest_secs: Option<u64>,
@@ -80,25 +262,192 @@ pub struct UntrackedRepoEntry {
     pub reason: UntrackedReason,
 }
 
-#[derive(Debug, Default, Clone)]
+/// A collected report. Its JSON form (see [`crate::output::to_json`]) is versioned and
+/// described by [`crate::output::json::report_schema`].
+#[derive(Debug, Default, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
 pub struct ReportData {
+    #[serde(flatten)]
+    pub meta: ReportMeta,
     pub uncommitted: Vec<UncommittedEntry>,
     pub staged: Vec<StagedEntry>,
     pub pushable: Vec<PushableEntry>,
+    pub pullable: Vec<PullableEntry>,
+    pub unpublished: Vec<UnpublishedEntry>,
+    pub stashes: Vec<StashEntry>,
+    pub submodules: Vec<SubmoduleEntry>,
+    /// Remote fetches done before inspection; empty unless `refresh_remotes` is set.
+    pub fetches: Vec<FetchEntry>,
+    pub errors: Vec<RepoError>,
     pub git_rewrite: Option<Vec<GitRewriteEntry>>,
     pub multi_root: bool,
     pub repos: Vec<RepoSummary>,
+    /// Written as `null` in JSON when `untracked_enabled` is off.
+    #[serde(deserialize_with = "null_as_default")]
+    #[schemars(with = "Option<Vec<UntrackedRepoEntry>>")]
     pub untracked_repos: Vec<UntrackedRepoEntry>,
     pub untracked_enabled: bool,
 }
 
-#[derive(Debug, Clone, Default)]
+/// When, where and with which options a report was collected.
+#[derive(Debug, Default, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct ReportMeta {
+    /// Collection time as an RFC 3339 timestamp in UTC.
+    pub generated_at: String,
+    /// Name of the host the repositories were inspected on.
+    pub host: String,
+    pub options: Options,
+}
+
+/// Scan settings; each flag mirrors a command-line switch.
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
 pub struct Options {
     pub roots: Vec<std::path::PathBuf>,
-    pub depth: usize,
+    pub depth: Depth,
     pub no_untracked: bool,
+    /// With `Depth::Auto`, keep descending into repositories to find nested ones.
+    pub nested: bool,
+    /// Report submodules as child rows and flag gitlinks whose commit is not pushed.
+    pub submodules: bool,
+    #[serde(skip)]
     pub debug: bool,
     pub refresh_remotes: bool,
+    /// Longest a single fetch attempt may run before it is killed (`None` = no limit).
+    #[serde(with = "optional_secs")]
+    #[schemars(with = "Option<u64>")]
+    pub fetch_timeout: Option<Duration>,
+    /// Further attempts after a fetch fails or times out.
+    pub fetch_retries: u32,
+    /// Number of repositories to inspect concurrently (`None` = number of CPUs).
+    pub jobs: Option<usize>,
     pub git_rewrite_toml: Option<std::path::PathBuf>,
     pub git_rewrite_path: Option<std::path::PathBuf>,
+    /// Per-root overrides of `depth` and `no_untracked`.
+    pub root_settings: Vec<RootSettings>,
+    /// Gitignore-style patterns for directories the scan does not descend into.
+    pub exclude: Vec<String>,
+    /// Patterns re-including directories matched by `exclude` or a root's ignore file.
+    pub include: Vec<String>,
+}
+
+/// Scan settings for one root, matched against `Options::roots` after `~` expansion.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct RootSettings {
+    pub root: std::path::PathBuf,
+    pub depth: Option<Depth>,
+    pub no_untracked: Option<bool>,
+}
+
+/// How far below a root the scan looks for repositories.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Depth {
+    /// Fixed number of directory levels (0 = only the root itself).
+    Levels(usize),
+    /// No level limit, but never descend into a repository found on the way.
+    Auto,
+}
+
+impl Default for Depth {
+    fn default() -> Self {
+        Depth::Levels(0)
+    }
+}
+
+impl std::fmt::Display for Depth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Depth::Levels(levels) => write!(f, "{levels}"),
+            Depth::Auto => f.write_str("auto"),
+        }
+    }
+}
+
+impl std::str::FromStr for Depth {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.eq_ignore_ascii_case("auto") {
+            return Ok(Depth::Auto);
+        }
+        s.parse()
+            .map(Depth::Levels)
+            .map_err(|_| format!("invalid depth '{s}' (expected a number or 'auto')"))
+    }
+}
+
+/// Written as a plain integer or the string `"auto"`, matching `--depth`.
+impl Serialize for Depth {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Depth::Levels(levels) => serializer.serialize_u64(*levels as u64),
+            Depth::Auto => serializer.serialize_str("auto"),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Depth {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Levels(usize),
+            Name(String),
+        }
+        match Raw::deserialize(deserializer)? {
+            Raw::Levels(levels) => Ok(Depth::Levels(levels)),
+            Raw::Name(name) => name.parse().map_err(serde::de::Error::custom),
+        }
+    }
+}
+
+impl JsonSchema for Depth {
+    fn schema_name() -> Cow<'static, str> {
+        "Depth".into()
+    }
+
+    fn json_schema(_generator: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "description": "Directory levels below a root to search, or \"auto\".",
+            "anyOf": [
+                { "type": "integer", "minimum": 0 },
+                { "const": "auto" }
+            ]
+        })
+    }
+}
+
+/// An optional duration written as whole seconds.
+mod optional_secs {
+    use std::time::Duration;
+
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    #[allow(clippy::ref_option)]
+    pub(super) fn serialize<S: Serializer>(
+        value: &Option<Duration>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(duration) => serializer.serialize_some(&duration.as_secs()),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Duration>, D::Error> {
+        Ok(Option::<u64>::deserialize(deserializer)?.map(Duration::from_secs))
+    }
+}
+
+/// Read `null` as the type's default, for sections older reports write as `null` when off.
+fn null_as_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de> + Default,
+{
+    Ok(Option::<T>::deserialize(deserializer)?.unwrap_or_default())
 }
@@ -0,0 +1,75 @@
+//! Fixture helpers shared by the integration tests that build real repositories.
+
+// Each test binary compiles its own copy and uses only some of the helpers.
+#![allow(dead_code)]
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use uncommitted::{Clock, Depth, Options};
+
+/// A clock a little over a day after the fixture commits.
+pub struct FixedClock;
+
+impl Clock for FixedClock {
+    fn now(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(1_700_100_000)
+    }
+}
+
+/// Run git in `dir` with a fixed identity, branch name and commit dates, ignoring the user's
+/// and the system's config; returns the trimmed stdout.
+pub fn git_output(dir: &Path, args: &[&str]) -> std::io::Result<String> {
+    let out = Command::new("git")
+        .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+        .args([
+            "-c",
+            "init.defaultBranch=main",
+            "-c",
+            "commit.gpgsign=false",
+            "-c",
+            "protocol.file.allow=always",
+        ])
+        .args(args)
+        .current_dir(dir)
+        .env("GIT_AUTHOR_DATE", "1700000000 +0000")
+        .env("GIT_COMMITTER_DATE", "1700000000 +0000")
+        .env("GIT_CONFIG_NOSYSTEM", "1")
+        .env("GIT_CONFIG_GLOBAL", "/dev/null")
+        .output()?;
+    if out.status.success() {
+        Ok(String::from_utf8_lossy(&out.stdout).trim().to_string())
+    } else {
+        Err(std::io::Error::other(format!("git {args:?} failed")))
+    }
+}
+
+/// [`git_output`] for commands whose output does not matter.
+pub fn git(dir: &Path, args: &[&str]) -> std::io::Result<()> {
+    git_output(dir, args).map(drop)
+}
+
+pub fn commit_file(dir: &Path, name: &str, contents: &str, message: &str) -> std::io::Result<()> {
+    fs::write(dir.join(name), contents)?;
+    git(dir, &["add", name])?;
+    git(dir, &["commit", "--quiet", "-m", message])
+}
+
+pub fn git_available() -> bool {
+    Command::new("git")
+        .arg("--version")
+        .output()
+        .is_ok_and(|out| out.status.success())
+}
+
+/// Scan the direct children of `root` on one thread, so results come in a stable order.
+pub fn options(root: &Path) -> Options {
+    Options {
+        roots: vec![root.to_path_buf()],
+        depth: Depth::Levels(1),
+        jobs: Some(1),
+        ..Options::default()
+    }
+}
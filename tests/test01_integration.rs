@@ -4,8 +4,9 @@ use std::path::{Path, PathBuf};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tempfile::TempDir;
 use uncommitted::{
-    Clock, DefaultFsOps, FsOps, GitRunner, Options, collect_report_data,
-    output::{TabStyle, format_tab},
+    Clock, DefaultFsOps, Depth, FsOps, GitRunner, IGNORE_FILE, LiveReport, Options, RootSettings,
+    collect_report_data, generate_report,
+    output::{Delimiter, Section, TabColors, TabStyle, format_tab, to_delimited, to_json},
 };
 
 const EXPECTED_OUTPUT: &str = concat!(
@@ -23,7 +24,22 @@ const EXPECTED_OUTPUT: &str = concat!(
     "| Repo | Branch | Commits | Earliest | Latest |\n",
     "+------+--------+---------+----------+--------+\n",
     "| c    | main   |       7 | 1.4 days | 1.1 hr |\n",
-    "+------+--------+---------+----------+--------+"
+    "+------+--------+---------+----------+--------+\n",
+    "+ Pullable Commits -----------+--------+-------+----------+----------+----------+\n",
+    "| Repo | Branch | Upstream    | Behind | Ahead | Status   | Earliest | Latest   |\n",
+    "+------+--------+-------------+--------+-------+----------+----------+----------+\n",
+    "| c    | main   | origin/main |      2 |     7 | diverged | 2.0 hr   | 30.0 min |\n",
+    "+------+--------+-------------+--------+-------+----------+----------+----------+\n",
+    "+ Unpublished Branches --------+---------+----------+----------+\n",
+    "| Repo | Branch  | Base        | Commits | Earliest | Latest   |\n",
+    "+------+---------+-------------+---------+----------+----------+\n",
+    "| a    | feature | origin/main |       3 | 3.0 days | 1.0 days |\n",
+    "+------+---------+-------------+---------+----------+----------+\n",
+    "+ Stashes ---------+--------+--------------------+--------+\n",
+    "| Repo | Stash     | Branch | Message            | Age    |\n",
+    "+------+-----------+--------+--------------------+--------+\n",
+    "| b    | stash@{0} | main   | half-done refactor | 1.0 hr |\n",
+    "+------+-----------+--------+--------------------+--------+"
 );
 
 struct MockGit;
@@ -38,16 +54,25 @@ impl MockGit {
     }
 
     fn out_fail() -> std::process::Output {
+        Self::out_status(1, "")
+    }
+
+    /// A process that exited with `code` (raw wait statuses carry the exit code in bits 8..16).
+    fn out_status(code: i32, stderr: &str) -> std::process::Output {
         std::process::Output {
-            status: std::process::ExitStatus::from_raw(1),
+            status: std::process::ExitStatus::from_raw(code << 8),
             stdout: Vec::new(),
-            stderr: Vec::new(),
+            stderr: stderr.as_bytes().to_vec(),
         }
     }
 
     fn run_for_each_ref(reponame: &str) -> std::process::Output {
-        if reponame == "c" {
+        if reponame == "gone" {
+            Self::out_ok("feat origin/feat\nmain origin/main\n")
+        } else if ["c", "d", "slowlog"].contains(&reponame) {
             Self::out_ok("main origin/main\n")
+        } else if reponame == "a" || reponame == "solo" || reponame == "remote.git" {
+            Self::out_ok("main\nfeature\n")
         } else {
             Self::out_ok("main\n")
         }
@@ -93,14 +118,13 @@ impl MockGit {
 
     fn run_rev_parse(reponame: &str, args: &[&str]) -> std::process::Output {
         match args {
-            ["rev-parse", "--abbrev-ref", "HEAD"] => Self::out_ok("main\n"),
-            ["rev-parse", "--abbrev-ref", "--symbolic-full-name", "@{u}"] => {
-                if reponame == "c" {
-                    Self::out_ok("origin/main\n")
-                } else {
-                    Self::out_fail()
-                }
+            ["rev-parse", "--verify", "--quiet", "refs/heads/main"]
+                if ["solo", "remote.git", "gone"].contains(&reponame) =>
+            {
+                Self::out_ok("")
             }
+            // `gone` tracks `origin/feat`, which was deleted on the remote and pruned.
+            ["rev-parse", "--verify", "--quiet", "origin/main"] => Self::out_ok(""),
             ["rev-parse", "--verify", "HEAD"] => {
                 if reponame == "a" || reponame == "b" {
                     Self::out_fail()
@@ -112,6 +136,21 @@ impl MockGit {
         }
     }
 
+    fn run_symbolic_ref(reponame: &str, args: &[&str]) -> std::process::Output {
+        if Self::reads_head(args) {
+            Self::out_ok("main\n")
+        } else if reponame == "a" {
+            Self::out_ok("origin/main\n")
+        } else {
+            Self::out_fail()
+        }
+    }
+
+    /// Commands that still work in the hung and broken repos, so they fail on a later query.
+    fn reads_head(args: &[&str]) -> bool {
+        args.first() == Some(&"rev-parse") || args == ["symbolic-ref", "--quiet", "--short", "HEAD"]
+    }
+
     fn run_config(reponame: &str, args: &[&str]) -> std::process::Output {
         if args.get(1) == Some(&"--get") {
             let url = format!("git@github.com:acme/{reponame}.git\n");
@@ -121,8 +160,38 @@ impl MockGit {
         }
     }
 
-    fn run_log(reponame: &str) -> std::process::Output {
-        if reponame == "c" {
+    fn run_rev_list(reponame: &str, args: &[&str]) -> std::process::Output {
+        let range = args.last().copied().unwrap_or_default();
+        if range.contains("origin/feat") {
+            return Self::out_status(128, &format!("fatal: bad revision '{range}'\n"));
+        }
+        match (reponame, args.last().copied()) {
+            ("gone", Some("origin/main..main")) => Self::out_ok("2\n"),
+            ("gone", Some("main..feat")) => Self::out_ok("1\n"),
+            ("c", Some("origin/main..main")) => Self::out_ok("7\n"),
+            ("c", Some("main..origin/main")) => Self::out_ok("2\n"),
+            ("d" | "slowlog", Some("main..origin/main")) => Self::out_ok("4\n"),
+            ("a", Some("origin/main..feature")) => Self::out_ok("3\n"),
+            ("solo", Some("main..feature")) => Self::out_ok("2\n"),
+            ("solo" | "remote.git", Some("main" | "feature")) => Self::out_ok("5\n"),
+            _ => Self::out_ok("0\n"),
+        }
+    }
+
+    fn run_log(reponame: &str, args: &[&str]) -> std::process::Output {
+        if reponame == "a" && args.last() == Some(&"origin/main..feature") {
+            let now = 1_000_000_000u64;
+            let output = format!("{}\n{}\n", now - 259_200, now - 86_400);
+            Self::out_ok(&output)
+        } else if reponame == "c" && args.last() == Some(&"main..origin/main") {
+            let now = 1_000_000_000u64;
+            let output = format!("{}\n{}\n", now - 7_200, now - 1_800);
+            Self::out_ok(&output)
+        } else if reponame == "d" && args.last() == Some(&"main..origin/main") {
+            let now = 1_000_000_000u64;
+            let output = format!("{}\n{}\n", now - 172_800, now - 3_600);
+            Self::out_ok(&output)
+        } else if reponame == "c" {
             let now = 1_000_000_000u64;
             let earliest = now - 120_960;
             let latest = now - 4_080;
@@ -137,6 +206,24 @@ impl MockGit {
 impl GitRunner for MockGit {
     fn run_git(&self, repo: &Path, args: &[&str]) -> std::io::Result<std::process::Output> {
         let reponame = repo.file_name().unwrap().to_string_lossy();
+        if reponame == "hung" && !Self::reads_head(args) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::TimedOut,
+                "timed out after 120s",
+            ));
+        }
+        if reponame == "slowlog" && args.first() == Some(&"log") {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::TimedOut,
+                "timed out after 5s",
+            ));
+        }
+        if reponame == "broken" && !Self::reads_head(args) {
+            return Ok(Self::out_status(
+                128,
+                "fatal: detected dubious ownership in repository at '/src/broken'\n",
+            ));
+        }
         let output = match args.first().copied().unwrap_or("") {
             "for-each-ref" => Self::run_for_each_ref(&reponame),
             "diff" => Self::run_diff(&reponame, args),
@@ -149,14 +236,16 @@ impl GitRunner for MockGit {
             }
             "rev-parse" => Self::run_rev_parse(&reponame, args),
             "config" => Self::run_config(&reponame, args),
-            "rev-list" => {
-                if reponame == "c" {
-                    Self::out_ok("7\n")
+            "rev-list" => Self::run_rev_list(&reponame, args),
+            "log" => Self::run_log(&reponame, args),
+            "stash" => {
+                if reponame == "b" {
+                    Self::out_ok("stash@{0}\u{1f}999996400\u{1f}On main: half-done refactor\n")
                 } else {
-                    Self::out_ok("0\n")
+                    Self::out_ok("")
                 }
             }
-            "log" => Self::run_log(&reponame),
+            "symbolic-ref" => Self::run_symbolic_ref(&reponame, args),
             _ => Self::out_ok(""),
         };
         Ok(output)
@@ -192,17 +281,32 @@ fn create_fixture_repos(root: &Path) -> std::io::Result<()> {
 }
 
 fn build_report(root: &Path) -> String {
-    let opts = Options {
+    build_report_with_jobs(root, None)
+}
+
+fn build_report_with_jobs(root: &Path, jobs: Option<usize>) -> String {
+    let data = collect_report_data(&options(root, jobs), &MockFs, &MockGit, &MockClock);
+    format_tab(&data, TabStyle::Ascii, false, TabColors::OFF)
+}
+
+fn options(root: &Path, jobs: Option<usize>) -> Options {
+    Options {
         roots: vec![root.to_path_buf()],
-        depth: 1,
+        depth: Depth::Levels(1),
         no_untracked: false,
+        nested: false,
+        submodules: false,
         debug: false,
         refresh_remotes: false,
+        fetch_timeout: None,
+        fetch_retries: 0,
+        jobs,
         git_rewrite_toml: None,
         git_rewrite_path: None,
-    };
-    let data = collect_report_data(&opts, &MockFs, &MockGit, &MockClock);
-    format_tab(&data, TabStyle::Ascii, false)
+        root_settings: Vec::new(),
+        exclude: Vec::new(),
+        include: Vec::new(),
+    }
 }
 
 #[test]
@@ -214,3 +318,303 @@ fn test01_integration() -> Result<(), Box<dyn std::error::Error>> {
     assert_eq!(report, EXPECTED_OUTPUT);
     Ok(())
 }
+
+#[test]
+fn test01_parallel_scan_matches_sequential() -> Result<(), Box<dyn std::error::Error>> {
+    let tmp = TempDir::new()?;
+    create_fixture_repos(tmp.path())?;
+
+    let sequential = build_report_with_jobs(tmp.path(), Some(1));
+    let parallel = build_report_with_jobs(tmp.path(), Some(4));
+    assert_eq!(sequential, EXPECTED_OUTPUT);
+    assert_eq!(parallel, EXPECTED_OUTPUT);
+    Ok(())
+}
+
+#[test]
+fn test01_reports_repos_git_cannot_inspect() -> Result<(), Box<dyn std::error::Error>> {
+    let tmp = TempDir::new()?;
+    create_fixture_repos(tmp.path())?;
+    fs::create_dir_all(tmp.path().join("broken/.git"))?;
+
+    let data = collect_report_data(&options(tmp.path(), None), &MockFs, &MockGit, &MockClock);
+    assert_eq!(data.errors.len(), 1);
+    let error = &data.errors[0];
+    assert_eq!(error.repo, "broken");
+    assert_eq!(error.command, "git diff --quiet --ignore-submodules -- .");
+    assert_eq!(error.status, Some(128));
+    assert!(
+        error
+            .stderr
+            .starts_with("fatal: detected dubious ownership")
+    );
+    assert!(data.repos.iter().any(|repo| repo.repo == "broken"));
+
+    let report = format_tab(&data, TabStyle::Ascii, false, TabColors::OFF);
+    assert!(report.starts_with(EXPECTED_OUTPUT));
+    assert!(report.contains(" Errors "));
+    assert!(report.contains("| broken | git diff --quiet --ignore-submodules -- . |  128 |"));
+
+    let json: serde_json::Value = serde_json::from_str(&to_json(&data))?;
+    assert_eq!(json["errors"][0]["repo"], "broken");
+    assert_eq!(json["errors"][0]["status"], 128);
+
+    let tsv = to_delimited(&data, Delimiter::Tab, Some(Section::Errors), false);
+    let mut lines = tsv.lines();
+    assert_eq!(
+        lines.next(),
+        Some("errors\tRoot\tRepo\tCommand\tExit\tError")
+    );
+    let row: Vec<&str> = lines.next().unwrap_or_default().split('\t').collect();
+    assert_eq!(
+        row[2..5],
+        ["broken", "git diff --quiet --ignore-submodules -- .", "128"]
+    );
+    Ok(())
+}
+
+#[test]
+fn test01_branches_with_a_gone_upstream_are_unpublished() -> Result<(), Box<dyn std::error::Error>>
+{
+    let tmp = TempDir::new()?;
+    create_fixture_repos(tmp.path())?;
+    // `gone` has `feat`, whose upstream was deleted and pruned, listed before `main`.
+    fs::create_dir_all(tmp.path().join("gone/.git"))?;
+
+    let data = collect_report_data(&options(tmp.path(), None), &MockFs, &MockGit, &MockClock);
+    assert!(data.errors.is_empty());
+    let unpublished: Vec<_> = data
+        .unpublished
+        .iter()
+        .filter(|entry| entry.repo == "gone")
+        .map(|entry| (entry.branch.as_str(), entry.base.as_deref(), entry.revs))
+        .collect();
+    assert_eq!(unpublished, [("feat", Some("main"), 1)]);
+    let pushable: Vec<_> = data
+        .pushable
+        .iter()
+        .filter(|entry| entry.repo == "gone")
+        .map(|entry| (entry.branch.as_str(), entry.revs))
+        .collect();
+    assert_eq!(pushable, [("main", 2)]);
+    Ok(())
+}
+
+#[test]
+fn test01_reports_timed_out_commands_distinctly() -> Result<(), Box<dyn std::error::Error>> {
+    let tmp = TempDir::new()?;
+    create_fixture_repos(tmp.path())?;
+    fs::create_dir_all(tmp.path().join("hung/.git"))?;
+
+    let data = collect_report_data(&options(tmp.path(), None), &MockFs, &MockGit, &MockClock);
+    assert_eq!(data.errors.len(), 1);
+    let error = &data.errors[0];
+    assert_eq!(error.repo, "hung");
+    assert!(error.timed_out);
+    assert_eq!(error.status, None);
+    assert_eq!(error.stderr, "timed out after 120s");
+
+    let report = format_tab(&data, TabStyle::Ascii, false, TabColors::OFF);
+    assert!(report.contains("| hung | git diff --quiet --ignore-submodules -- . | timeout |"));
+
+    let json: serde_json::Value = serde_json::from_str(&to_json(&data))?;
+    assert_eq!(json["errors"][0]["timed_out"], true);
+    Ok(())
+}
+
+#[test]
+fn test01_reports_timed_out_branch_queries() -> Result<(), Box<dyn std::error::Error>> {
+    let tmp = TempDir::new()?;
+    create_fixture_repos(tmp.path())?;
+    // `slowlog` is behind its upstream, but dating those commits exceeds --git-timeout.
+    fs::create_dir_all(tmp.path().join("slowlog/.git"))?;
+
+    let data = collect_report_data(&options(tmp.path(), None), &MockFs, &MockGit, &MockClock);
+    assert_eq!(data.errors.len(), 1);
+    let error = &data.errors[0];
+    assert_eq!(error.repo, "slowlog");
+    assert_eq!(error.command, "git log --format=%ct main..origin/main");
+    assert!(error.timed_out);
+    assert_eq!(error.status, None);
+    assert_eq!(error.stderr, "timed out after 5s");
+    assert!(data.pullable.iter().all(|entry| entry.repo != "slowlog"));
+
+    let report = format_tab(&data, TabStyle::Ascii, false, TabColors::OFF);
+    assert!(report.contains("| slowlog | git log --format=%ct main..origin/main | timeout |"));
+    Ok(())
+}
+
+#[test]
+fn test01_pullable_separates_behind_only_from_diverged() -> Result<(), Box<dyn std::error::Error>> {
+    let tmp = TempDir::new()?;
+    create_fixture_repos(tmp.path())?;
+    // `d` tracks origin/main with nothing to push, so it is only behind.
+    fs::create_dir_all(tmp.path().join("d/.git"))?;
+    let opts = options(tmp.path(), Some(1));
+
+    let data = collect_report_data(&opts, &MockFs, &MockGit, &MockClock);
+    assert_eq!(data.pullable.len(), 2);
+    let diverged = data
+        .pullable
+        .iter()
+        .find(|entry| entry.repo == "c")
+        .unwrap();
+    assert_eq!((diverged.behind, diverged.ahead), (2, 7));
+    assert!(diverged.diverged);
+    let behind = data
+        .pullable
+        .iter()
+        .find(|entry| entry.repo == "d")
+        .unwrap();
+    assert_eq!((behind.behind, behind.ahead), (4, 0));
+    assert!(!behind.diverged);
+    assert_eq!(behind.earliest_secs, Some(172_800));
+    assert_eq!(behind.latest_secs, Some(3_600));
+    assert!(data.pushable.iter().all(|entry| entry.repo != "d"));
+
+    let json: serde_json::Value = serde_json::from_str(&to_json(&data))?;
+    let rows = json["pullable"].as_array().unwrap();
+    let row = rows.iter().find(|row| row["repo"] == "d").unwrap();
+    assert_eq!(row["upstream"], "origin/main");
+    assert_eq!(row["behind"], 4);
+    assert_eq!(row["ahead"], 0);
+    assert_eq!(row["diverged"], false);
+    let row = rows.iter().find(|row| row["repo"] == "c").unwrap();
+    assert_eq!(row["diverged"], true);
+
+    let report = generate_report(&opts, &MockFs, &MockGit, &MockClock);
+    let pullable = report
+        .lines()
+        .find(|line| line.starts_with("pullable: "))
+        .unwrap();
+    assert_eq!(
+        pullable,
+        "pullable: c:main (2 behind origin/main, diverged, 7 ahead, earliest: 2.0 hr ago, \
+         latest: 30.0 min ago), d:main (4 behind origin/main, behind, earliest: 2.0 days ago, \
+         latest: 1.0 hr ago)"
+    );
+    Ok(())
+}
+
+#[test]
+fn test01_unpublished_skips_base_branch_and_bare_repos() -> Result<(), Box<dyn std::error::Error>> {
+    let tmp = TempDir::new()?;
+    create_fixture_repos(tmp.path())?;
+    // `solo` has no remote: `main` is its base and only `feature` is unpublished.
+    fs::create_dir_all(tmp.path().join("solo/.git"))?;
+    let bare = tmp.path().join("remote.git");
+    fs::create_dir_all(bare.join("objects"))?;
+    fs::create_dir_all(bare.join("refs"))?;
+    fs::write(bare.join("HEAD"), "ref: refs/heads/main\n")?;
+
+    let data = collect_report_data(&options(tmp.path(), None), &MockFs, &MockGit, &MockClock);
+    assert!(
+        data.repos
+            .iter()
+            .any(|repo| repo.repo == "remote.git" && repo.bare)
+    );
+    let unpublished: Vec<_> = data
+        .unpublished
+        .iter()
+        .map(|entry| {
+            (
+                entry.repo.as_str(),
+                entry.branch.as_str(),
+                entry.base.as_deref(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        unpublished,
+        [
+            ("a", "feature", Some("origin/main")),
+            ("solo", "feature", Some("main"))
+        ]
+    );
+    assert_eq!(data.unpublished[1].revs, 2);
+    Ok(())
+}
+
+#[test]
+fn test01_per_root_settings_override_global_options() -> Result<(), Box<dyn std::error::Error>> {
+    let tmp = TempDir::new()?;
+    create_fixture_repos(tmp.path())?;
+
+    let mut opts = options(tmp.path(), None);
+    opts.root_settings = vec![RootSettings {
+        root: tmp.path().join("."),
+        depth: None,
+        no_untracked: Some(true),
+    }];
+    let data = collect_report_data(&opts, &MockFs, &MockGit, &MockClock);
+    assert_eq!(data.uncommitted.len(), 1);
+    assert_eq!(data.uncommitted[0].untracked, 0);
+
+    fs::create_dir_all(tmp.path().join("group/d/.git"))?;
+    let data = collect_report_data(&opts, &MockFs, &MockGit, &MockClock);
+    assert!(data.repos.iter().all(|repo| repo.repo != "d"));
+    opts.root_settings[0].depth = Some(Depth::Levels(2));
+    let data = collect_report_data(&opts, &MockFs, &MockGit, &MockClock);
+    assert!(data.repos.iter().any(|repo| repo.repo == "d"));
+    Ok(())
+}
+
+#[test]
+fn test01_live_report_rescans_only_changed_repos() -> Result<(), Box<dyn std::error::Error>> {
+    let tmp = TempDir::new()?;
+    create_fixture_repos(tmp.path())?;
+    let opts = options(tmp.path(), Some(1));
+
+    let mut live = LiveReport::scan(&opts, &MockFs, &MockGit, &MockClock);
+    assert_eq!(
+        format_tab(&live.report(), TabStyle::Ascii, false, TabColors::OFF),
+        EXPECTED_OUTPUT
+    );
+
+    let refresh = live.refresh(
+        &[tmp.path().join("b/.git/index")],
+        &opts,
+        &MockFs,
+        &MockGit,
+        &MockClock,
+    );
+    assert_eq!(refresh.rescanned, vec![tmp.path().join("b")]);
+    assert!(!refresh.rediscovered);
+    assert_eq!(
+        format_tab(&live.report(), TabStyle::Ascii, false, TabColors::OFF),
+        EXPECTED_OUTPUT
+    );
+
+    fs::create_dir_all(tmp.path().join("broken/.git"))?;
+    let refresh = live.refresh(
+        &[tmp.path().join("broken")],
+        &opts,
+        &MockFs,
+        &MockGit,
+        &MockClock,
+    );
+    assert_eq!(refresh.rescanned, vec![tmp.path().join("broken")]);
+    assert!(refresh.rediscovered);
+    assert_eq!(live.report().errors.len(), 1);
+    assert_eq!(live.report().repos.len(), 4);
+    Ok(())
+}
+
+#[test]
+fn test01_live_report_applies_scan_filters_to_changed_paths()
+-> Result<(), Box<dyn std::error::Error>> {
+    let tmp = TempDir::new()?;
+    create_fixture_repos(tmp.path())?;
+    fs::write(tmp.path().join(IGNORE_FILE), "target/\n")?;
+    fs::create_dir_all(tmp.path().join("a/target/debug"))?;
+    let mut opts = options(tmp.path(), Some(1));
+    opts.exclude = vec!["node_modules".to_string()];
+
+    let live = LiveReport::scan(&opts, &MockFs, &MockGit, &MockClock);
+    assert!(live.is_excluded(&tmp.path().join("a/target/debug/app")));
+    assert!(live.is_excluded(&tmp.path().join("b/web/node_modules/dep/index.js")));
+    assert!(!live.is_excluded(&tmp.path().join("a/src/main.rs")));
+    assert!(!live.is_excluded(&tmp.path().join("b/.git/index")));
+    assert!(!live.is_excluded(Path::new("/elsewhere/target/debug")));
+    Ok(())
+}
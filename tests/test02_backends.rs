@@ -0,0 +1,202 @@
+#![cfg(feature = "native")]
+
+mod common;
+
+use common::{FixedClock, commit_file, git, git_available, options};
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+use uncommitted::{
+    DefaultFsOps, DefaultGitRunner, GitRunner, NativeGitRunner, Options, RecordingGitRunner,
+    ReportData, collect_report_data,
+    output::{TabColors, TabStyle, format_tab, to_json},
+};
+
+/// Build repositories covering every query the report issues: a clone that has diverged from
+/// its origin, an unpublished branch, worktree and index edits, untracked files, a linked
+/// worktree and the bare origin itself.
+fn create_fixture(root: &Path) -> std::io::Result<()> {
+    let seed = root.join("seed");
+    fs::create_dir(&seed)?;
+    git(&seed, &["init", "--quiet"])?;
+    commit_file(&seed, "README.md", "one\ntwo\nthree\n", "initial")?;
+    commit_file(&seed, "lib.rs", "fn a() {}\nfn b() {}\n", "lib")?;
+    git(root, &["clone", "--quiet", "--bare", "seed", "origin.git"])?;
+    fs::remove_dir_all(&seed)?;
+
+    git(root, &["clone", "--quiet", "origin.git", "app"])?;
+    let app = root.join("app");
+    commit_file(&app, "notes.txt", "local\n", "local one")?;
+    commit_file(&app, "notes.txt", "local\nmore\n", "local two")?;
+
+    git(root, &["clone", "--quiet", "origin.git", "upstream-work"])?;
+    let upstream_work = root.join("upstream-work");
+    commit_file(&upstream_work, "remote.txt", "remote\n", "remote change")?;
+    git(&upstream_work, &["push", "--quiet", "origin", "main"])?;
+    fs::remove_dir_all(&upstream_work)?;
+    git(&app, &["fetch", "--quiet", "origin"])?;
+
+    git(&app, &["checkout", "--quiet", "-b", "feature"])?;
+    commit_file(&app, "feature.rs", "fn feature() {}\n", "feature work")?;
+    git(&app, &["checkout", "--quiet", "main"])?;
+    git(
+        &app,
+        &["worktree", "add", "--quiet", "../app-review", "feature"],
+    )?;
+    fs::write(
+        root.join("app-review/feature.rs"),
+        "fn feature() { todo!() }\n",
+    )?;
+
+    fs::write(app.join("README.md"), "one\n2\nthree\nfour\n")?;
+    fs::remove_file(app.join("lib.rs"))?;
+    fs::write(app.join("staged.rs"), "fn staged() {}\nfn more() {}\n")?;
+    git(&app, &["add", "staged.rs"])?;
+    fs::write(app.join("blob.bin"), [0u8, 1, 2, 3])?;
+    git(&app, &["add", "blob.bin"])?;
+    fs::create_dir(app.join("scratch"))?;
+    fs::write(app.join("scratch/todo.txt"), "later\n")?;
+    fs::write(app.join(".gitignore"), "*.log\n")?;
+    fs::write(app.join("debug.log"), "ignored\n")?;
+
+    let clean = root.join("clean");
+    fs::create_dir(&clean)?;
+    git(&clean, &["init", "--quiet"])?;
+    commit_file(&clean, "a.txt", "a\n", "only")?;
+    Ok(())
+}
+
+/// Repositories under `root/work` for the branch states the first fixture lacks: a clone only
+/// behind its upstream, a branch whose upstream was deleted and pruned, a detached `HEAD`, a
+/// repository without commits and a stash. Their remote lives beside `work`.
+fn create_edge_fixture(root: &Path) -> std::io::Result<()> {
+    let seed = root.join("seed");
+    fs::create_dir(&seed)?;
+    git(&seed, &["init", "--quiet"])?;
+    commit_file(&seed, "README.md", "one\n", "initial")?;
+    git(root, &["clone", "--quiet", "--bare", "seed", "origin.git"])?;
+    git(&seed, &["remote", "add", "origin", "../origin.git"])?;
+
+    let work = root.join("work");
+    fs::create_dir(&work)?;
+    for name in ["behind", "gone", "detached", "stashed"] {
+        git(&work, &["clone", "--quiet", "../origin.git", name])?;
+    }
+
+    let gone = work.join("gone");
+    git(&gone, &["checkout", "--quiet", "-b", "feat"])?;
+    commit_file(&gone, "feat.txt", "feat\n", "feat")?;
+    git(&gone, &["push", "--quiet", "-u", "origin", "feat"])?;
+    commit_file(&gone, "feat.txt", "feat\nmore\n", "more feat")?;
+    git(&gone, &["push", "--quiet", "origin", "--delete", "feat"])?;
+    git(&gone, &["fetch", "--quiet", "--prune"])?;
+    git(&gone, &["checkout", "--quiet", "main"])?;
+
+    let detached = work.join("detached");
+    git(&detached, &["checkout", "--quiet", "--detach"])?;
+    fs::write(detached.join("README.md"), "one\ntwo\n")?;
+
+    let stashed = work.join("stashed");
+    fs::write(stashed.join("README.md"), "stashed\n")?;
+    git(&stashed, &["stash", "push", "--quiet", "-m", "wip"])?;
+
+    let empty = work.join("empty");
+    fs::create_dir(&empty)?;
+    git(&empty, &["init", "--quiet"])?;
+    fs::write(empty.join("draft.txt"), "draft\n")?;
+
+    commit_file(&seed, "README.md", "one\nupstream\n", "upstream change")?;
+    git(&seed, &["push", "--quiet", "origin", "main"])?;
+    git(&work.join("behind"), &["fetch", "--quiet", "origin"])?;
+    Ok(())
+}
+
+/// Scan with both backends and check the reports agree, then run every command the `git`
+/// backend was asked through the native backend and check each answer matches. Returns the
+/// report.
+fn assert_backends_agree(opts: &Options) -> Result<ReportData, Box<dyn std::error::Error>> {
+    let recorder = RecordingGitRunner::new(Box::new(DefaultGitRunner));
+    let with_git = collect_report_data(opts, &DefaultFsOps, &recorder, &FixedClock);
+    let native = NativeGitRunner::new();
+    let with_native = collect_report_data(opts, &DefaultFsOps, &native, &FixedClock);
+
+    assert_eq!(to_json(&with_native), to_json(&with_git));
+    assert_eq!(
+        format_tab(&with_native, TabStyle::Ascii, false, TabColors::OFF),
+        format_tab(&with_git, TabStyle::Ascii, false, TabColors::OFF)
+    );
+
+    for exchange in recorder.fixture().exchanges {
+        let args: Vec<&str> = exchange.args.iter().map(String::as_str).collect();
+        let actual = native.run_git(&exchange.repo, &args)?;
+        assert_eq!(
+            actual.status.code(),
+            exchange.status,
+            "status of git {args:?} in {}",
+            exchange.repo.display()
+        );
+        assert_eq!(
+            String::from_utf8_lossy(&actual.stdout),
+            exchange.stdout,
+            "stdout of git {args:?} in {}",
+            exchange.repo.display()
+        );
+    }
+    Ok(with_git)
+}
+
+#[test]
+fn test02_native_backend_matches_git() -> Result<(), Box<dyn std::error::Error>> {
+    if !git_available() {
+        return Ok(());
+    }
+    let tmp = TempDir::new()?;
+    create_fixture(tmp.path())?;
+
+    let data = assert_backends_agree(&options(tmp.path()))?;
+    assert_eq!(data.uncommitted.len(), 2);
+    assert_eq!(data.staged.len(), 1);
+    assert_eq!(data.pullable.len(), 1);
+    // Only app's feature branch: the bare origin and clean's base branch are not unpublished.
+    assert_eq!(data.unpublished.len(), 1);
+    assert_eq!(data.unpublished[0].branch, "feature");
+
+    let data = assert_backends_agree(&Options {
+        no_untracked: true,
+        ..options(tmp.path())
+    })?;
+    assert!(data.uncommitted.iter().all(|entry| entry.untracked == 0));
+    Ok(())
+}
+
+#[test]
+fn test02_native_backend_matches_git_on_edge_cases() -> Result<(), Box<dyn std::error::Error>> {
+    if !git_available() {
+        return Ok(());
+    }
+    let tmp = TempDir::new()?;
+    create_edge_fixture(tmp.path())?;
+
+    let data = assert_backends_agree(&options(&tmp.path().join("work")))?;
+    assert!(data.errors.is_empty(), "{:?}", data.errors);
+    let pullable: Vec<(&str, u64, u64)> = data
+        .pullable
+        .iter()
+        .map(|entry| (entry.repo.as_str(), entry.behind, entry.ahead))
+        .collect();
+    assert_eq!(pullable, [("behind", 1, 0)]);
+    let unpublished: Vec<(&str, &str)> = data
+        .unpublished
+        .iter()
+        .map(|entry| (entry.repo.as_str(), entry.branch.as_str()))
+        .collect();
+    assert_eq!(unpublished, [("gone", "feat")]);
+    let uncommitted: Vec<(&str, &str)> = data
+        .uncommitted
+        .iter()
+        .map(|entry| (entry.repo.as_str(), entry.branch.as_str()))
+        .collect();
+    assert_eq!(uncommitted, [("detached", "HEAD"), ("empty", "main")]);
+    assert_eq!(data.stashes.len(), 1);
+    Ok(())
+}
@@ -0,0 +1,144 @@
+mod common;
+
+use common::{FixedClock, commit_file, git, git_available, options};
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+use uncommitted::{DefaultFsOps, DefaultGitRunner, Options, ReportData, collect_report_data};
+
+/// A superproject `app` with a submodule `lib` that has an edited file and a local commit the
+/// superproject's index already points at, although it was never pushed to `lib.git`.
+fn create_fixture(root: &Path) -> std::io::Result<()> {
+    let seed = root.join("seed");
+    fs::create_dir(&seed)?;
+    git(&seed, &["init", "--quiet"])?;
+    commit_file(&seed, "lib.rs", "fn lib() {}\n", "initial")?;
+    git(root, &["clone", "--quiet", "--bare", "seed", "lib.git"])?;
+    fs::remove_dir_all(&seed)?;
+
+    let app = root.join("app");
+    fs::create_dir(&app)?;
+    git(&app, &["init", "--quiet"])?;
+    commit_file(&app, "main.rs", "fn main() {}\n", "initial")?;
+    let origin = root.join("lib.git");
+    git(
+        &app,
+        &[
+            "submodule",
+            "add",
+            "--quiet",
+            &origin.to_string_lossy(),
+            "lib",
+        ],
+    )?;
+    git(&app, &["commit", "--quiet", "-m", "add lib"])?;
+
+    let lib = app.join("lib");
+    commit_file(&lib, "more.rs", "fn more() {}\n", "local only")?;
+    git(&app, &["add", "lib"])?;
+    fs::write(lib.join("lib.rs"), "fn lib() { todo!() }\n")?;
+    Ok(())
+}
+
+fn repos<'a>(rows: impl Iterator<Item = &'a String>) -> Vec<&'a str> {
+    rows.map(String::as_str).collect()
+}
+
+fn report(root: &Path, submodules: bool) -> ReportData {
+    collect_report_data(
+        &Options {
+            submodules,
+            ..options(root)
+        },
+        &DefaultFsOps,
+        &DefaultGitRunner,
+        &FixedClock,
+    )
+}
+
+#[test]
+fn test03_submodules_are_reported_as_child_rows() -> Result<(), Box<dyn std::error::Error>> {
+    if !git_available() {
+        return Ok(());
+    }
+    let tmp = TempDir::new()?;
+    create_fixture(tmp.path())?;
+
+    let data = report(tmp.path(), true);
+    assert!(data.errors.is_empty(), "{:?}", data.errors);
+    assert_eq!(
+        repos(data.uncommitted.iter().map(|e| &e.repo)),
+        vec!["app/lib"]
+    );
+    assert_eq!(repos(data.staged.iter().map(|e| &e.repo)), vec!["app"]);
+    let pushable: Vec<(&str, u64)> = data
+        .pushable
+        .iter()
+        .map(|e| (e.repo.as_str(), e.revs))
+        .collect();
+    assert_eq!(pushable, vec![("app/lib", 1)]);
+    assert_eq!(data.submodules.len(), 1);
+    assert_eq!(data.submodules[0].repo, "app");
+    assert_eq!(data.submodules[0].path, "lib");
+    assert_eq!(data.submodules[0].revs, 1);
+    Ok(())
+}
+
+#[test]
+fn test03_submodule_commits_missing_locally_are_not_counted()
+-> Result<(), Box<dyn std::error::Error>> {
+    if !git_available() {
+        return Ok(());
+    }
+    let tmp = TempDir::new()?;
+    create_fixture(tmp.path())?;
+    // Point the superproject at a commit the submodule has never seen.
+    git(
+        &tmp.path().join("app"),
+        &[
+            "update-index",
+            "--cacheinfo",
+            "160000,1234567890123456789012345678901234567890,lib",
+        ],
+    )?;
+
+    let data = report(tmp.path(), true);
+    assert!(data.errors.is_empty(), "{:?}", data.errors);
+    assert!(data.submodules.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test03_submodule_paths_with_spaces_and_non_ascii_characters()
+-> Result<(), Box<dyn std::error::Error>> {
+    if !git_available() {
+        return Ok(());
+    }
+    let tmp = TempDir::new()?;
+    create_fixture(tmp.path())?;
+    let app = tmp.path().join("app");
+    git(&app, &["mv", "lib", "ui kit é"])?;
+
+    let data = report(tmp.path(), true);
+    assert!(data.errors.is_empty(), "{:?}", data.errors);
+    assert_eq!(data.submodules.len(), 1);
+    assert_eq!(data.submodules[0].path, "ui kit é");
+    assert_eq!(data.submodules[0].revs, 1);
+    Ok(())
+}
+
+#[test]
+fn test03_submodules_are_ignored_by_default() -> Result<(), Box<dyn std::error::Error>> {
+    if !git_available() {
+        return Ok(());
+    }
+    let tmp = TempDir::new()?;
+    create_fixture(tmp.path())?;
+
+    let data = report(tmp.path(), false);
+    assert!(data.uncommitted.is_empty());
+    assert!(data.staged.is_empty());
+    assert!(data.pushable.is_empty());
+    assert!(data.submodules.is_empty());
+    Ok(())
+}
@@ -0,0 +1,262 @@
+mod common;
+
+use common::{FixedClock, commit_file, git, git_available, options};
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+use uncommitted::{
+    DefaultFsOps, DefaultGitRunner, Depth, Options, ReportData,
+    actions::{PushFilter, RepoAction, plan_push, push_all, repo_details, run_action},
+    collect_report_data,
+};
+
+/// A clone `app` of a bare `origin.git` with an edited tracked file and an untracked file.
+/// Identity is set in the clone's config because actions run git without the fixture's `-c`
+/// flags.
+fn create_fixture(root: &Path) -> std::io::Result<()> {
+    let seed = root.join("seed");
+    fs::create_dir(&seed)?;
+    git(&seed, &["init", "--quiet"])?;
+    commit_file(&seed, "README.md", "one\n", "initial")?;
+    git(root, &["clone", "--quiet", "--bare", "seed", "origin.git"])?;
+    fs::remove_dir_all(&seed)?;
+
+    git(root, &["clone", "--quiet", "origin.git", "app"])?;
+    let app = root.join("app");
+    git(&app, &["config", "user.name", "Test"])?;
+    git(&app, &["config", "user.email", "test@example.com"])?;
+    git(&app, &["config", "commit.gpgsign", "false"])?;
+    fs::write(app.join("README.md"), "one\ntwo\n")?;
+    fs::write(app.join("notes.txt"), "todo\n")?;
+    Ok(())
+}
+
+fn report(root: &Path) -> ReportData {
+    collect_report_data(
+        &options(root),
+        &DefaultFsOps,
+        &DefaultGitRunner,
+        &FixedClock,
+    )
+}
+
+#[test]
+fn test04_actions_stage_commit_and_push() -> Result<(), Box<dyn std::error::Error>> {
+    if !git_available() {
+        return Ok(());
+    }
+    let tmp = TempDir::new()?;
+    create_fixture(tmp.path())?;
+    let data = report(tmp.path());
+    let app = data.uncommitted[0].path.clone();
+    assert_eq!(app, tmp.path().join("app"));
+
+    run_action(&app, &DefaultGitRunner, &RepoAction::StageAll)?;
+    let data = report(tmp.path());
+    assert!(data.uncommitted.is_empty());
+    assert_eq!(data.staged.len(), 1);
+    assert_eq!(data.staged[0].files, 2);
+
+    let message = RepoAction::Commit("notes".to_string());
+    run_action(&app, &DefaultGitRunner, &message)?;
+    let data = report(tmp.path());
+    assert!(data.staged.is_empty());
+    assert_eq!(data.pushable.len(), 1);
+    let details = repo_details(&app, &DefaultGitRunner);
+    assert_eq!(details.unpushed.len(), 1);
+    assert!(details.unpushed[0].ends_with(" notes"));
+
+    run_action(
+        &app,
+        &DefaultGitRunner,
+        &RepoAction::Push("main".to_string()),
+    )?;
+    let data = report(tmp.path());
+    assert!(data.pushable.is_empty());
+    assert!(repo_details(&app, &DefaultGitRunner).unpushed.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test04_push_action_pushes_the_given_branch() -> Result<(), Box<dyn std::error::Error>> {
+    if !git_available() {
+        return Ok(());
+    }
+    let tmp = TempDir::new()?;
+    create_fixture(tmp.path())?;
+    let app = tmp.path().join("app");
+    git(
+        &app,
+        &[
+            "checkout",
+            "--quiet",
+            "-b",
+            "feature",
+            "--track",
+            "origin/main",
+        ],
+    )?;
+    commit_file(&app, "feature.txt", "f\n", "feature")?;
+    git(&app, &["checkout", "--quiet", "-b", "local"])?;
+
+    run_action(
+        &app,
+        &DefaultGitRunner,
+        &RepoAction::Push("feature".to_string()),
+    )?;
+    let data = report(tmp.path());
+    assert!(data.pushable.iter().all(|entry| entry.branch != "feature"));
+
+    let Err(err) = run_action(
+        &app,
+        &DefaultGitRunner,
+        &RepoAction::Push("local".to_string()),
+    ) else {
+        return Err("push without an upstream succeeded".into());
+    };
+    assert_eq!(err.to_string(), "branch local has no upstream configured");
+    Ok(())
+}
+
+#[test]
+fn test04_stash_includes_untracked_files() -> Result<(), Box<dyn std::error::Error>> {
+    if !git_available() {
+        return Ok(());
+    }
+    let tmp = TempDir::new()?;
+    create_fixture(tmp.path())?;
+    let app = tmp.path().join("app");
+
+    let details = repo_details(&app, &DefaultGitRunner);
+    assert!(details.status.iter().any(|line| line == "?? notes.txt"));
+    assert!(
+        details
+            .diffstat
+            .iter()
+            .any(|line| line.contains("README.md"))
+    );
+
+    run_action(&app, &DefaultGitRunner, &RepoAction::Stash)?;
+    let data = report(tmp.path());
+    assert!(data.uncommitted.is_empty());
+    assert_eq!(data.stashes.len(), 1);
+    Ok(())
+}
+
+#[test]
+fn test04_failed_action_reports_the_command() -> Result<(), Box<dyn std::error::Error>> {
+    if !git_available() {
+        return Ok(());
+    }
+    let tmp = TempDir::new()?;
+    create_fixture(tmp.path())?;
+    let app = tmp.path().join("app");
+
+    let Err(err) = run_action(&app, &DefaultGitRunner, &RepoAction::Commit("empty".into())) else {
+        return Err("commit without staged changes succeeded".into());
+    };
+    assert!(
+        err.to_string()
+            .starts_with("`git commit --quiet -m empty` exited with 1")
+    );
+    Ok(())
+}
+
+#[test]
+fn test04_push_plan_targets_upstreams_and_reports_failures()
+-> Result<(), Box<dyn std::error::Error>> {
+    if !git_available() {
+        return Ok(());
+    }
+    let tmp = TempDir::new()?;
+    create_fixture(tmp.path())?;
+    let app = tmp.path().join("app");
+    git(&app, &["commit", "--quiet", "-am", "two"])?;
+    git(&app, &["checkout", "--quiet", "-b", "topic"])?;
+    git(&app, &["push", "--quiet", "-u", "origin", "topic"])?;
+    commit_file(&app, "topic.txt", "topic\n", "topic work")?;
+
+    let data = report(tmp.path());
+    let all = PushFilter::new(&[], &[], &[])?;
+    let plan = plan_push(&data, &all, &DefaultGitRunner);
+    assert!(plan.skipped.is_empty(), "{:?}", plan.skipped);
+    let planned: Vec<(&str, String)> = plan
+        .targets
+        .iter()
+        .map(|t| (t.branch.as_str(), t.upstream()))
+        .collect();
+    assert_eq!(
+        planned,
+        vec![
+            ("main", "origin/main".to_string()),
+            ("topic", "origin/topic".to_string())
+        ]
+    );
+    let topic_only = PushFilter::new(&[], &["app".to_string()], &["t*".to_string()])?;
+    assert_eq!(
+        plan_push(&data, &topic_only, &DefaultGitRunner)
+            .targets
+            .len(),
+        1
+    );
+
+    let results = push_all(&plan.targets, &DefaultGitRunner, Some(2));
+    assert!(results.iter().all(|r| r.error.is_none()), "{results:?}");
+    assert!(report(tmp.path()).pushable.is_empty());
+
+    commit_file(&app, "topic.txt", "more\n", "more topic work")?;
+    let plan = plan_push(&report(tmp.path()), &all, &DefaultGitRunner);
+    fs::remove_dir_all(tmp.path().join("origin.git"))?;
+    let results = push_all(&plan.targets, &DefaultGitRunner, Some(2));
+    assert_eq!(results.len(), 1);
+    let error = results[0]
+        .error
+        .as_deref()
+        .ok_or("push to a missing remote succeeded")?;
+    assert!(error.starts_with("`git push origin refs/heads/topic:refs/heads/topic` exited with"));
+    Ok(())
+}
+
+#[test]
+fn test04_push_plan_keeps_repos_sharing_a_name_apart() -> Result<(), Box<dyn std::error::Error>> {
+    if !git_available() {
+        return Ok(());
+    }
+    let tmp = TempDir::new()?;
+    create_fixture(tmp.path())?;
+    // Two more clones of origin.git in one root, both named `app`, each with a commit of its
+    // own.
+    for team in ["team-a", "team-b"] {
+        let dir = tmp.path().join(team);
+        fs::create_dir(&dir)?;
+        git(&dir, &["clone", "--quiet", "../origin.git", "app"])?;
+        let app = dir.join("app");
+        git(&app, &["checkout", "--quiet", "-b", team])?;
+        git(&app, &["push", "--quiet", "-u", "origin", team])?;
+        commit_file(&app, "team.txt", team, team)?;
+    }
+
+    let opts = Options {
+        depth: Depth::Levels(2),
+        ..options(tmp.path())
+    };
+    let data = collect_report_data(&opts, &DefaultFsOps, &DefaultGitRunner, &FixedClock);
+    let plan = plan_push(&data, &PushFilter::new(&[], &[], &[])?, &DefaultGitRunner);
+    assert!(plan.skipped.is_empty(), "{:?}", plan.skipped);
+    let planned: Vec<(&str, &Path)> = plan
+        .targets
+        .iter()
+        .map(|t| (t.branch.as_str(), t.path.as_path()))
+        .collect();
+    let (team_a, team_b) = (tmp.path().join("team-a/app"), tmp.path().join("team-b/app"));
+    assert_eq!(
+        planned,
+        vec![("team-a", team_a.as_path()), ("team-b", team_b.as_path())]
+    );
+
+    let results = push_all(&plan.targets, &DefaultGitRunner, Some(2));
+    assert!(results.iter().all(|r| r.error.is_none()), "{results:?}");
+    let data = collect_report_data(&opts, &DefaultFsOps, &DefaultGitRunner, &FixedClock);
+    assert!(data.pushable.is_empty(), "{:?}", data.pushable);
+    Ok(())
+}
@@ -0,0 +1,89 @@
+mod common;
+
+use common::{FixedClock, commit_file, git, git_available, git_output, options};
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+use tempfile::TempDir;
+use uncommitted::{
+    DefaultFsOps, DefaultGitRunner, FetchStatus, Options, ReportData, collect_report_data,
+    output::to_json,
+};
+
+/// Clones `a` and `b` of `origin.git` and a clone `c` of a remote that no longer exists.
+/// The scanned repositories live under `root/work`; remotes and the seed live beside it.
+fn create_fixture(root: &Path) -> std::io::Result<()> {
+    let seed = root.join("seed");
+    fs::create_dir(&seed)?;
+    git(&seed, &["init", "--quiet"])?;
+    commit_file(&seed, "README.md", "one\n", "initial")?;
+    git(root, &["clone", "--quiet", "--bare", "seed", "origin.git"])?;
+    git(root, &["clone", "--quiet", "--bare", "seed", "gone.git"])?;
+    git(&seed, &["remote", "add", "origin", "../origin.git"])?;
+
+    let work = root.join("work");
+    fs::create_dir(&work)?;
+    for (name, remote) in [("a", "origin.git"), ("b", "origin.git"), ("c", "gone.git")] {
+        git(&work, &["clone", "--quiet", &format!("../{remote}"), name])?;
+    }
+    fs::remove_dir_all(root.join("gone.git"))?;
+
+    commit_file(&seed, "README.md", "one\ntwo\n", "second")?;
+    git(&seed, &["push", "--quiet", "origin", "main"])?;
+    Ok(())
+}
+
+fn report(work: &Path) -> ReportData {
+    let opts = Options {
+        jobs: Some(2),
+        refresh_remotes: true,
+        fetch_timeout: Some(Duration::from_secs(30)),
+        fetch_retries: 1,
+        ..options(work)
+    };
+    collect_report_data(&opts, &DefaultFsOps, &DefaultGitRunner, &FixedClock)
+}
+
+#[test]
+fn test05_fetch_shares_identical_urls_and_reports_failures()
+-> Result<(), Box<dyn std::error::Error>> {
+    if !git_available() {
+        return Ok(());
+    }
+    let temp = TempDir::new()?;
+    create_fixture(temp.path())?;
+    let work = temp.path().join("work");
+
+    let data = report(&work);
+    let statuses: Vec<(&str, FetchStatus, u32)> = data
+        .fetches
+        .iter()
+        .map(|entry| (entry.repo.as_str(), entry.status, entry.attempts))
+        .collect();
+    assert_eq!(
+        statuses,
+        [
+            ("a", FetchStatus::Fetched, 1),
+            ("b", FetchStatus::Shared, 0),
+            ("c", FetchStatus::Failed, 2),
+        ]
+    );
+    assert_eq!(data.fetches[1].source.as_deref(), Some("a"));
+    assert!(data.fetches[2].error.is_some());
+
+    // Both clones see the commit pushed after they were cloned.
+    let origin = git_output(
+        temp.path().join("origin.git").as_path(),
+        &["rev-parse", "main"],
+    )?;
+    for repo in ["a", "b"] {
+        let tracking = git_output(&work.join(repo), &["rev-parse", "origin/main"])?;
+        assert_eq!(tracking, origin, "{repo} was not refreshed");
+    }
+    assert_eq!(data.pullable.len(), 2);
+
+    let json: serde_json::Value = serde_json::from_str(&to_json(&data))?;
+    assert_eq!(json["fetches"][1]["status"], "shared");
+    assert_eq!(json["fetches"][2]["status"], "failed");
+    Ok(())
+}
@@ -0,0 +1,75 @@
+mod common;
+
+use common::{FixedClock, commit_file, git, git_available, options};
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+use uncommitted::{
+    DefaultFsOps, DefaultGitRunner, GitFixture, RecordingGitRunner, ReplayGitRunner,
+    collect_report_data,
+    output::{TabColors, TabStyle, format_tab},
+};
+
+/// A repository `app` with an edited tracked file and a staged file, a clone `lib` with an
+/// unpushed commit, and the bare `origin.git` both push to.
+fn create_fixture(root: &Path) -> std::io::Result<()> {
+    let app = root.join("app");
+    fs::create_dir(&app)?;
+    git(&app, &["init", "--quiet"])?;
+    commit_file(&app, "README.md", "one\n", "initial")?;
+    fs::write(app.join("README.md"), "one\ntwo\n")?;
+    fs::write(app.join("new.txt"), "new\n")?;
+    git(&app, &["add", "new.txt"])?;
+
+    let remotes = root.join("remotes");
+    fs::create_dir(&remotes)?;
+    git(
+        &remotes,
+        &["clone", "--quiet", "--bare", "../app", "origin.git"],
+    )?;
+    git(root, &["clone", "--quiet", "remotes/origin.git", "lib"])?;
+    commit_file(&root.join("lib"), "lib.rs", "fn main() {}\n", "add lib")
+}
+
+#[test]
+fn test06_replayed_capture_reproduces_the_report() -> Result<(), Box<dyn std::error::Error>> {
+    if !git_available() {
+        return Ok(());
+    }
+    let captured = TempDir::new()?;
+    create_fixture(captured.path())?;
+    let recorder = RecordingGitRunner::new(Box::new(DefaultGitRunner));
+    let live = collect_report_data(
+        &options(captured.path()),
+        &DefaultFsOps,
+        &recorder,
+        &FixedClock,
+    );
+    assert!(live.errors.is_empty());
+    let expected = format_tab(&live, TabStyle::Ascii, false, TabColors::OFF);
+    let path = captured.path().join("capture.json");
+    recorder.fixture().save(&path)?;
+
+    // Recreate only the repository markers elsewhere; git itself is never run.
+    let fixture = GitFixture::load(&path)?;
+    let replayed = TempDir::new()?;
+    for repo in fixture.repos() {
+        let relative = repo.strip_prefix(captured.path())?;
+        fs::create_dir_all(replayed.path().join(relative).join(".git"))?;
+    }
+    let replay = ReplayGitRunner::new(fixture).relocate(captured.path(), replayed.path());
+    let data = collect_report_data(
+        &options(replayed.path()),
+        &DefaultFsOps,
+        &replay,
+        &FixedClock,
+    );
+    assert!(data.errors.is_empty(), "{:?}", data.errors);
+    assert_eq!(
+        format_tab(&data, TabStyle::Ascii, false, TabColors::OFF),
+        expected
+    );
+    assert_eq!(data.staged.len(), 1);
+    assert_eq!(data.pushable.len(), 1);
+    Ok(())
+}